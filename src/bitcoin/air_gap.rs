@@ -0,0 +1,222 @@
+use bdk_wallet::{
+    bitcoin::{
+        bip32::DerivationPath,
+        key::Secp256k1,
+        psbt::{Input, PsbtSighashType},
+        sighash::{EcdsaSighashType, TapSighashType},
+        Psbt as BdkPsbt, WitnessVersion,
+    },
+    miniscript::{
+        descriptor::DescriptorPublicKey,
+        interpreter::{KeySigPair, SatisfiedConstraint},
+        psbt::PsbtExt,
+        ForEachKey, Interpreter,
+    },
+    serde_json::json,
+    KeychainKind, SignOptions, Wallet as BdkWallet,
+};
+
+use crate::types::{BdkError, BdkErrorCode, Psbt};
+
+/// Complete a PSBT with everything an offline signer needs to verify and sign the inputs of the wallet.
+///
+/// Adds the BIP32 derivations of the wallet's inputs and outputs, the taproot internal keys and key origins,
+/// the previous transactions of the non-taproot inputs, and the wallet's extended public keys as global xpubs.
+/// Inputs and outputs that do not belong to the wallet are left as they are.
+pub fn export_psbt(wallet: &BdkWallet, psbt: &BdkPsbt) -> Result<BdkPsbt, BdkError> {
+    let mut psbt = psbt.clone();
+
+    for index in 0..psbt.inputs.len() {
+        let outpoint = psbt.unsigned_tx.input[index].previous_output;
+        let Some(prev_tx) = wallet.tx_graph().get_tx(outpoint.txid) else {
+            continue;
+        };
+        let Some(txout) = prev_tx.output.get(outpoint.vout as usize) else {
+            continue;
+        };
+        let Some((keychain, derivation_index)) = wallet.derivation_of_spk(txout.script_pubkey.clone()) else {
+            continue;
+        };
+        let descriptor = wallet
+            .public_descriptor(keychain)
+            .at_derivation_index(derivation_index)
+            .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?;
+
+        let input = &mut psbt.inputs[index];
+        match descriptor.desc_type().segwit_version() {
+            // BIP 371: taproot signatures commit to all the previous outputs, the transactions are not needed.
+            Some(WitnessVersion::V1) => input.witness_utxo = Some(txout.clone()),
+            Some(_) => {
+                input.witness_utxo = Some(txout.clone());
+                input.non_witness_utxo = Some(prev_tx.as_ref().clone());
+            }
+            None => input.non_witness_utxo = Some(prev_tx.as_ref().clone()),
+        }
+        psbt.update_input_with_descriptor(index, &descriptor)
+            .map_err(|e| BdkError::new(BdkErrorCode::InvalidPsbt, format!("input {index}: {e}")))?;
+    }
+
+    for index in 0..psbt.outputs.len() {
+        let script_pubkey = psbt.unsigned_tx.output[index].script_pubkey.clone();
+        let Some((keychain, derivation_index)) = wallet.derivation_of_spk(script_pubkey) else {
+            continue;
+        };
+        let descriptor = wallet
+            .public_descriptor(keychain)
+            .at_derivation_index(derivation_index)
+            .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?;
+        psbt.update_output_with_descriptor(index, &descriptor)
+            .map_err(|e| BdkError::new(BdkErrorCode::InvalidPsbt, format!("output {index}: {e}")))?;
+    }
+
+    for keychain in [KeychainKind::External, KeychainKind::Internal] {
+        wallet.public_descriptor(keychain).for_each_key(|key| {
+            let (xkey, origin) = match key {
+                DescriptorPublicKey::XPub(xpub) => (xpub.xkey, xpub.origin.clone()),
+                DescriptorPublicKey::MultiXPub(xpub) => (xpub.xkey, xpub.origin.clone()),
+                DescriptorPublicKey::Single(_) => return true,
+            };
+            // Without an origin, the key can only be described if it is a master key.
+            let origin = match origin {
+                Some(origin) => origin,
+                None if xkey.depth == 0 => (xkey.fingerprint(), DerivationPath::master()),
+                None => return true,
+            };
+            psbt.xpub.insert(xkey, origin);
+            true
+        });
+    }
+
+    Ok(psbt)
+}
+
+/// Check that a PSBT returned by an offline signer is the one that was exported, then finalize it.
+///
+/// The signed PSBT must be for the same transaction and must not change or add previous outputs of the inputs,
+/// nor use other sighash types than the exported PSBT, see [`check_sighashes`]. Its signatures are merged into
+/// the exported PSBT. The signatures of the finalized PSBT are verified against the previous outputs before it
+/// is returned.
+pub fn import_signed_psbt(wallet: &BdkWallet, exported: &BdkPsbt, signed: &BdkPsbt) -> Result<BdkPsbt, BdkError> {
+    if signed.unsigned_tx != exported.unsigned_tx {
+        return Err(BdkError::new(
+            BdkErrorCode::PsbtMismatch,
+            "the signed PSBT is for a different transaction",
+        ));
+    }
+    for (index, (ours, theirs)) in exported.inputs.iter().zip(&signed.inputs).enumerate() {
+        if is_altered(&ours.witness_utxo, &theirs.witness_utxo)
+            || is_altered(&ours.non_witness_utxo, &theirs.non_witness_utxo)
+        {
+            return Err(BdkError::new(
+                BdkErrorCode::PsbtMismatch,
+                format!("the signed PSBT has a different previous output for input {index}"),
+            )
+            .with_data(json!({ "input": index })));
+        }
+    }
+    check_sighashes(exported, signed)?;
+
+    let mut psbt = Psbt::from(exported.clone());
+    psbt.combine(&signed.clone().into())?;
+    let mut psbt = BdkPsbt::from(psbt);
+
+    if !wallet.finalize_psbt(&mut psbt, SignOptions::default())? {
+        return Err(BdkError::new(
+            BdkErrorCode::Sign,
+            "the signed PSBT is missing signatures",
+        ));
+    }
+    psbt.extract(&Secp256k1::verification_only())
+        .map_err(|e| BdkError::new(BdkErrorCode::InvalidPsbt, e))?;
+    Ok(psbt)
+}
+
+/// Whether the signed PSBT has a different value for a field, or a value the exported PSBT does not have.
+///
+/// A previous output added by the signer would be trusted when combining and verifying the PSBT.
+fn is_altered<T: PartialEq>(ours: &Option<T>, theirs: &Option<T>) -> bool {
+    theirs.is_some() && ours != theirs
+}
+
+/// Check that a PSBT returned by a signer does not use other sighash types than the exported PSBT.
+///
+/// The sighash type of an input must not be changed, and the signatures of an input, partial or in its final
+/// scriptSig and witness, must be `ALL` or the taproot `DEFAULT` unless the exported input asks for another
+/// sighash type. Signatures with `NONE`, `SINGLE` or `ANYONECANPAY` would let anyone change the outputs of the
+/// transaction once it is broadcast.
+pub(crate) fn check_sighashes(exported: &BdkPsbt, signed: &BdkPsbt) -> Result<(), BdkError> {
+    for (index, (ours, theirs)) in exported.inputs.iter().zip(&signed.inputs).enumerate() {
+        let mismatch = |message: &str| {
+            BdkError::new(BdkErrorCode::PsbtMismatch, format!("input {index} {message}"))
+                .with_data(json!({ "input": index }))
+        };
+        if is_altered(&ours.sighash_type, &theirs.sighash_type) {
+            return Err(mismatch("has a different sighash type"));
+        }
+
+        let ecdsa = theirs
+            .partial_sigs
+            .values()
+            .map(|signature| signature.sighash_type.into());
+        let schnorr = theirs.tap_key_sig.iter().chain(theirs.tap_script_sigs.values());
+        let mut sighash_types = ecdsa
+            .chain(schnorr.map(|signature| signature.sighash_type.into()))
+            .collect::<Vec<PsbtSighashType>>();
+        if is_altered(&ours.final_script_sig, &theirs.final_script_sig)
+            || is_altered(&ours.final_script_witness, &theirs.final_script_witness)
+        {
+            sighash_types.extend(final_sighash_types(exported, index, theirs)?);
+        }
+        let allowed = |sighash_type: &PsbtSighashType| {
+            *sighash_type == EcdsaSighashType::All.into()
+                || *sighash_type == TapSighashType::Default.into()
+                || Some(*sighash_type) == ours.sighash_type
+        };
+        if !sighash_types.iter().all(allowed) {
+            return Err(mismatch("has a signature with a sighash type that was not requested"));
+        }
+    }
+    Ok(())
+}
+
+/// The sighash types of the signatures in the final scriptSig and witness of a signed input.
+///
+/// The previous output is taken from the exported PSBT, inputs without one are skipped as they cannot be
+/// verified, nor extracted.
+fn final_sighash_types(exported: &BdkPsbt, index: usize, signed: &Input) -> Result<Vec<PsbtSighashType>, BdkError> {
+    let txin = &exported.unsigned_tx.input[index];
+    let input = &exported.inputs[index];
+    let script_pubkey = match (&input.witness_utxo, &input.non_witness_utxo) {
+        (Some(txout), _) => txout.script_pubkey.clone(),
+        (None, Some(tx)) => match tx.output.get(txin.previous_output.vout as usize) {
+            Some(txout) => txout.script_pubkey.clone(),
+            None => return Ok(Vec::new()),
+        },
+        (None, None) => return Ok(Vec::new()),
+    };
+
+    let invalid = |e| BdkError::new(BdkErrorCode::InvalidPsbt, format!("input {index}: {e}"));
+    let script_sig = signed.final_script_sig.clone().unwrap_or_default();
+    let witness = signed.final_script_witness.clone().unwrap_or_default();
+    let interpreter = Interpreter::from_txdata(
+        &script_pubkey,
+        &script_sig,
+        &witness,
+        txin.sequence,
+        exported.unsigned_tx.lock_time,
+    )
+    .map_err(invalid)?;
+    let mut sighash_types = Vec::new();
+    for constraint in interpreter.iter_assume_sigs() {
+        match constraint.map_err(invalid)? {
+            SatisfiedConstraint::PublicKey { key_sig } | SatisfiedConstraint::PublicKeyHash { key_sig, .. } => {
+                sighash_types.push(match key_sig {
+                    KeySigPair::Ecdsa(_, signature) => signature.sighash_type.into(),
+                    KeySigPair::Schnorr(_, signature) => signature.sighash_type.into(),
+                })
+            }
+            _ => {}
+        }
+    }
+    Ok(sighash_types)
+}
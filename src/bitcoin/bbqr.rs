@@ -0,0 +1,516 @@
+//! BBQr, the multi-part QR codes of Coldcard: each part is prefixed with a header giving the encoding, the
+//! type of the data, the number of parts and the index of the part.
+
+use std::collections::HashMap;
+
+use bdk_wallet::bitcoin::{
+    consensus::{deserialize, serialize},
+    hex::{DisplayHex, FromHex},
+    Psbt as BdkPsbt,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    result::JsResult,
+    types::{BbqrEncoding, BbqrFileType, BdkError, BdkErrorCode, DescriptorPair, Psbt, Transaction},
+};
+
+/// Length of the `B$` header of each part.
+const HEADER_LEN: usize = 8;
+/// Maximum number of parts, the largest two digits base 36 number.
+const MAX_PARTS: usize = 36 * 36 - 1;
+/// Largest decompressed data accepted, so a few parts cannot inflate to an unbounded size.
+const MAX_DATA_LEN: usize = 1 << 22;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const BASE36_ALPHABET: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Splits data into BBQr parts, to display one after the other as an animated QR code.
+#[wasm_bindgen]
+pub struct BbqrEncoder {
+    encoding: BbqrEncoding,
+    file_type: BbqrFileType,
+    parts: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl BbqrEncoder {
+    /// Split a PSBT in parts of at most `max_part_len` characters, header included.
+    pub fn from_psbt(psbt: &Psbt, encoding: BbqrEncoding, max_part_len: usize) -> JsResult<BbqrEncoder> {
+        BbqrEncoder::new(&psbt.serialize(), BbqrFileType::Psbt, encoding, max_part_len)
+    }
+
+    /// Split a signed transaction in parts of at most `max_part_len` characters, header included.
+    pub fn from_transaction(
+        transaction: &Transaction,
+        encoding: BbqrEncoding,
+        max_part_len: usize,
+    ) -> JsResult<BbqrEncoder> {
+        BbqrEncoder::new(
+            &serialize(&**transaction),
+            BbqrFileType::Transaction,
+            encoding,
+            max_part_len,
+        )
+    }
+
+    /// Split the public descriptors of a wallet, as the text of a multipath descriptor.
+    pub fn from_descriptor(
+        descriptors: &DescriptorPair,
+        encoding: BbqrEncoding,
+        max_part_len: usize,
+    ) -> JsResult<BbqrEncoder> {
+        let descriptor = descriptors.to_public_multipath()?.to_string();
+        BbqrEncoder::new(descriptor.as_bytes(), BbqrFileType::Unicode, encoding, max_part_len)
+    }
+
+    /// The encoding of the parts, base32 if zlib was requested but the data does not compress.
+    #[wasm_bindgen(getter)]
+    pub fn encoding(&self) -> BbqrEncoding {
+        self.encoding
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn file_type(&self) -> BbqrFileType {
+        self.file_type
+    }
+
+    /// The parts, in order.
+    #[wasm_bindgen(getter)]
+    pub fn parts(&self) -> Vec<String> {
+        self.parts.clone()
+    }
+}
+
+impl BbqrEncoder {
+    pub(crate) fn new(
+        data: &[u8],
+        file_type: BbqrFileType,
+        encoding: BbqrEncoding,
+        max_part_len: usize,
+    ) -> Result<BbqrEncoder, BdkError> {
+        let type_code = file_type_code(file_type)?;
+        let (encoding, encoded) = match encoding {
+            BbqrEncoding::Hex => (encoding, data.to_upper_hex_string()),
+            BbqrEncoding::Base32 => (encoding, base32_encode(data)),
+            BbqrEncoding::Zlib => match deflate(data) {
+                compressed if compressed.len() < data.len() => (encoding, base32_encode(&compressed)),
+                _ => (BbqrEncoding::Base32, base32_encode(data)),
+            },
+            _ => return Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown BBQr encoding")),
+        };
+
+        // Parts split base32 data on 5 bytes boundaries and hex data on byte boundaries.
+        let alignment = if encoding == BbqrEncoding::Hex { 2 } else { 8 };
+        let capacity = max_part_len.saturating_sub(HEADER_LEN) / alignment * alignment;
+        if capacity == 0 {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                format!("parts must be longer than {} characters", HEADER_LEN + alignment - 1),
+            ));
+        }
+        let count = encoded.len().div_ceil(capacity).max(1);
+        if count > MAX_PARTS {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                format!("the data needs {count} parts, more than the {MAX_PARTS} supported by BBQr"),
+            ));
+        }
+        // Spread the data evenly over the parts.
+        let part_len = encoded.len().div_ceil(count).div_ceil(alignment) * alignment;
+        let chunks = match encoded.len() {
+            0 => vec![""],
+            _ => encoded
+                .as_bytes()
+                .chunks(part_len.max(alignment))
+                .map(|chunk| std::str::from_utf8(chunk).expect("ASCII"))
+                .collect(),
+        };
+
+        let encoding_code = encoding_code(encoding)?;
+        let parts = chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                format!(
+                    "B${encoding_code}{type_code}{}{}{chunk}",
+                    base36(chunks.len()),
+                    base36(index)
+                )
+            })
+            .collect();
+        Ok(BbqrEncoder {
+            encoding,
+            file_type,
+            parts,
+        })
+    }
+}
+
+/// Joins BBQr parts, scanned in any order.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct BbqrDecoder {
+    /// Encoding, file type and number of parts shared by all the parts.
+    header: Option<(char, char, usize)>,
+    parts: HashMap<usize, String>,
+    data: Option<Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl BbqrDecoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> BbqrDecoder {
+        BbqrDecoder::default()
+    }
+
+    /// Add a scanned part.
+    ///
+    /// Errors if the part is not a valid BBQr part or belongs to different data than the previous parts.
+    /// Parts received after the data is complete are ignored.
+    pub fn receive(&mut self, part: &str) -> JsResult<()> {
+        let invalid = |message: &str| BdkError::new(BdkErrorCode::InvalidQr, message);
+
+        let part = part.trim();
+        if !part.is_ascii() || part.len() < HEADER_LEN || !part.starts_with("B$") {
+            return Err(invalid("the part is not a BBQr part"));
+        }
+        let header = part.as_bytes();
+        let (encoding, file_type) = (header[2] as char, header[3] as char);
+        encoding_from_code(encoding)?;
+        file_type_from_code(file_type)?;
+        let count = parse_base36(&part[4..6]).filter(|count| *count > 0);
+        let index = parse_base36(&part[6..8]);
+        let (Some(count), Some(index)) = (count, index) else {
+            return Err(invalid("invalid part numbers"));
+        };
+        if index >= count {
+            return Err(invalid("the part index exceeds the number of parts"));
+        }
+
+        if self.header.is_some_and(|header| header != (encoding, file_type, count)) {
+            return Err(invalid("the part belongs to different data"));
+        }
+        let body = &part[HEADER_LEN..];
+        if self.parts.get(&index).is_some_and(|received| received != body) {
+            return Err(invalid("the part belongs to different data"));
+        }
+        self.header = Some((encoding, file_type, count));
+        self.parts.insert(index, body.to_string());
+
+        if self.data.is_none() && self.parts.len() == count {
+            let encoded = (0..count).map(|index| self.parts[&index].as_str()).collect::<String>();
+            self.data = Some(decode(encoding, &encoded)?);
+        }
+        Ok(())
+    }
+
+    /// Whether all the parts have been received.
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        self.data.is_some()
+    }
+
+    /// The fraction of the parts received so far, between 0 and 1.
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        match self.header {
+            Some((_, _, count)) => self.parts.len() as f64 / count as f64,
+            None => 0.0,
+        }
+    }
+
+    /// The type of the data, known after the first part is received.
+    #[wasm_bindgen(getter)]
+    pub fn file_type(&self) -> Option<BbqrFileType> {
+        self.header
+            .and_then(|(_, file_type, _)| file_type_from_code(file_type).ok())
+    }
+
+    /// The decoded data.
+    pub fn data(&self) -> JsResult<Vec<u8>> {
+        Ok(self.data_of(&[])?.to_vec())
+    }
+
+    /// The decoded PSBT.
+    pub fn to_psbt(&self) -> JsResult<Psbt> {
+        let psbt = BdkPsbt::deserialize(self.data_of(&[BbqrFileType::Psbt])?)?;
+        Ok(psbt.into())
+    }
+
+    /// The decoded transaction.
+    pub fn to_transaction(&self) -> JsResult<Transaction> {
+        let tx: bdk_wallet::bitcoin::Transaction = deserialize(self.data_of(&[BbqrFileType::Transaction])?)?;
+        Ok(tx.into())
+    }
+
+    /// The decoded descriptors, from the text of a multipath descriptor or of two descriptors on separate
+    /// lines, the external one first.
+    pub fn to_descriptor(&self) -> JsResult<DescriptorPair> {
+        let text = std::str::from_utf8(self.data_of(&[BbqrFileType::Unicode])?)
+            .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?;
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<_>>();
+        match lines[..] {
+            [multipath] => DescriptorPair::from_multipath(multipath),
+            [external, internal] => Ok(DescriptorPair::new(external.to_string(), internal.to_string())),
+            _ => Err(BdkError::new(
+                BdkErrorCode::InvalidDescriptor,
+                "expected a multipath descriptor or two descriptors",
+            )),
+        }
+    }
+}
+
+impl BbqrDecoder {
+    /// The decoded data, checking its type if `file_types` is not empty.
+    fn data_of(&self, file_types: &[BbqrFileType]) -> Result<&[u8], BdkError> {
+        let (Some(data), Some(file_type)) = (&self.data, self.file_type()) else {
+            return Err(BdkError::new(BdkErrorCode::InvalidQr, "the BBQr data is not complete"));
+        };
+        if !file_types.is_empty() && !file_types.contains(&file_type) {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidQr,
+                format!("expected {:?} data, found {file_type:?}", file_types[0]),
+            ));
+        }
+        Ok(data)
+    }
+}
+
+fn encoding_code(encoding: BbqrEncoding) -> Result<char, BdkError> {
+    match encoding {
+        BbqrEncoding::Hex => Ok('H'),
+        BbqrEncoding::Base32 => Ok('2'),
+        BbqrEncoding::Zlib => Ok('Z'),
+        _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown BBQr encoding")),
+    }
+}
+
+fn encoding_from_code(code: char) -> Result<BbqrEncoding, BdkError> {
+    match code {
+        'H' => Ok(BbqrEncoding::Hex),
+        '2' => Ok(BbqrEncoding::Base32),
+        'Z' => Ok(BbqrEncoding::Zlib),
+        _ => Err(BdkError::new(
+            BdkErrorCode::InvalidQr,
+            format!("unsupported BBQr encoding {code}"),
+        )),
+    }
+}
+
+fn file_type_code(file_type: BbqrFileType) -> Result<char, BdkError> {
+    match file_type {
+        BbqrFileType::Psbt => Ok('P'),
+        BbqrFileType::Transaction => Ok('T'),
+        BbqrFileType::Json => Ok('J'),
+        BbqrFileType::Cbor => Ok('C'),
+        BbqrFileType::Unicode => Ok('U'),
+        BbqrFileType::Binary => Ok('B'),
+        _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown BBQr file type")),
+    }
+}
+
+fn file_type_from_code(code: char) -> Result<BbqrFileType, BdkError> {
+    match code {
+        'P' => Ok(BbqrFileType::Psbt),
+        'T' => Ok(BbqrFileType::Transaction),
+        'J' => Ok(BbqrFileType::Json),
+        'C' => Ok(BbqrFileType::Cbor),
+        'U' => Ok(BbqrFileType::Unicode),
+        'B' => Ok(BbqrFileType::Binary),
+        _ => Err(BdkError::new(
+            BdkErrorCode::InvalidQr,
+            format!("unsupported BBQr file type {code}"),
+        )),
+    }
+}
+
+fn base36(value: usize) -> String {
+    [value / 36, value % 36]
+        .iter()
+        .map(|digit| BASE36_ALPHABET[*digit] as char)
+        .collect()
+}
+
+fn parse_base36(digits: &str) -> Option<usize> {
+    usize::from_str_radix(digits, 36).ok()
+}
+
+fn decode(encoding: char, encoded: &str) -> Result<Vec<u8>, BdkError> {
+    let invalid = |message: &str| BdkError::new(BdkErrorCode::InvalidQr, message);
+    match encoding {
+        'H' => Vec::from_hex(encoded).map_err(|_| invalid("invalid hexadecimal data")),
+        '2' => base32_decode(encoded).ok_or_else(|| invalid("invalid base32 data")),
+        _ => {
+            let compressed = base32_decode(encoded).ok_or_else(|| invalid("invalid base32 data"))?;
+            miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DATA_LEN)
+                .map_err(|_| invalid("invalid or too large compressed data"))
+        }
+    }
+}
+
+/// RFC 4648 base32, without padding.
+fn base32_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in data {
+        buffer = buffer << 8 | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    encoded
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in encoded.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|a| *a == c)?;
+        buffer = buffer << 5 | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+    // The remaining bits are padding and must be zero.
+    (buffer & ((1 << bits) - 1) == 0).then_some(data)
+}
+
+/// Raw deflate with the fixed Huffman codes and back references within the 1 KiB window required by BBQr,
+/// for decoders with little memory.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    const WINDOW: usize = 1024;
+    const MIN_MATCH: usize = 3;
+    const MAX_MATCH: usize = 258;
+    const MAX_CANDIDATES: usize = 64;
+
+    let mut writer = BitWriter::default();
+    // A single final block with fixed Huffman codes.
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    let mut positions: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    let mut position = 0;
+    while position < data.len() {
+        let mut best = (0, 0);
+        if let Some(candidates) = data
+            .get(position..position + MIN_MATCH)
+            .and_then(|key| positions.get(key))
+        {
+            for &candidate in candidates.iter().rev().take(MAX_CANDIDATES) {
+                if position - candidate > WINDOW {
+                    break;
+                }
+                let len = data[candidate..]
+                    .iter()
+                    .zip(&data[position..])
+                    .take(MAX_MATCH)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if len > best.0 {
+                    best = (len, position - candidate);
+                }
+            }
+        }
+
+        let len = if best.0 >= MIN_MATCH {
+            writer.write_length(best.0);
+            writer.write_distance(best.1);
+            best.0
+        } else {
+            writer.write_literal(data[position] as usize);
+            1
+        };
+        for start in position..position + len {
+            if let Some(key) = data.get(start..start + MIN_MATCH) {
+                positions.entry(key).or_default().push(start);
+            }
+        }
+        position += len;
+    }
+
+    writer.write_literal(256);
+    writer.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    /// Write `count` bits of `value`, least significant bit first.
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.bits;
+        self.bits += count;
+        while self.bits >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Write a Huffman code, most significant bit first.
+    fn write_code(&mut self, code: u32, len: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - len), len);
+    }
+
+    /// Write a literal/length symbol with its fixed Huffman code.
+    fn write_literal(&mut self, symbol: usize) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_length(&mut self, len: usize) {
+        const BASES: [usize; 29] = [
+            3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195,
+            227, 258,
+        ];
+        const EXTRA_BITS: [u32; 29] = [
+            0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+        ];
+        let code = BASES
+            .iter()
+            .rposition(|base| *base <= len)
+            .expect("length of at least 3");
+        self.write_literal(257 + code);
+        self.write_bits((len - BASES[code]) as u32, EXTRA_BITS[code]);
+    }
+
+    fn write_distance(&mut self, distance: usize) {
+        const BASES: [usize; 20] = [
+            1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769,
+        ];
+        let code = BASES
+            .iter()
+            .rposition(|base| *base <= distance)
+            .expect("distance of at least 1");
+        let extra_bits = (code as u32 / 2).saturating_sub(1);
+        self.write_code(code as u32, 5);
+        self.write_bits((distance - BASES[code]) as u32, extra_bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push(self.buffer as u8);
+        }
+        self.out
+    }
+}
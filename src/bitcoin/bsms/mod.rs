@@ -0,0 +1,301 @@
+//! Bitcoin Secure Multisig Setup (BIP129) records.
+//!
+//! Signers send a key record with their key, signed by the key, to the coordinator. The coordinator
+//! sends back a descriptor record with the descriptor template and its first address, for the signers to
+//! check and display. Records are encrypted with the session token, unless the token is `00`.
+
+use std::str::FromStr;
+
+use aes::{
+    cipher::{KeyIvInit, StreamCipher},
+    Aes256,
+};
+
+use bdk_wallet::miniscript::{
+    descriptor::{checksum::desc_checksum, DerivPaths, DescriptorXKey, Wildcard},
+    Descriptor, DescriptorPublicKey, ForEachKey,
+};
+use bitcoin::{
+    bip32::{ChildNumber, DerivationPath, Xpriv, Xpub},
+    hashes::{hmac, sha256, Hash, HashEngine},
+    hex::{DisplayHex, FromHex},
+    secp256k1::{Message, Secp256k1},
+    sign_message::{signed_msg_hash, MessageSignature},
+    Network,
+};
+
+use crate::types::{BdkError, BdkErrorCode, BsmsEncryption, DescriptorPair};
+
+type Aes256Ctr = ctr::Ctr128BE<Aes256>;
+
+const HEADER: &str = "BSMS 1.0";
+/// The token of sessions without encryption.
+const NO_ENCRYPTION_TOKEN: &str = "00";
+/// The only path restrictions supported, the receive and change addresses of `/**`.
+const PATH_RESTRICTIONS: &str = "/0/*,/1/*";
+const MAX_DESCRIPTION_LEN: usize = 80;
+const PBKDF2_PASSWORD: &[u8] = b"No SPOF";
+const PBKDF2_ITERATIONS: u32 = 2048;
+
+/// Generate the token of a new session, shared by the coordinator with the signers.
+pub fn new_token(encryption: BsmsEncryption) -> Result<String, BdkError> {
+    let mut token = match encryption {
+        BsmsEncryption::None => return Ok(NO_ENCRYPTION_TOKEN.to_string()),
+        BsmsEncryption::Standard => vec![0u8; 8],
+        BsmsEncryption::Extended => vec![0u8; 16],
+        _ => return Err(BdkError::new(BdkErrorCode::InvalidValue, "Invalid BSMS encryption")),
+    };
+    getrandom::getrandom(&mut token).map_err(|e| BdkError::new(BdkErrorCode::Unexpected, e))?;
+    Ok(token.to_lower_hex_string())
+}
+
+/// Create the key record of a signer, with the key of the master key `master` at `path`.
+pub fn key_record(master: &Xpriv, path: &DerivationPath, token: &str, description: &str) -> Result<String, BdkError> {
+    let token = Token::parse(token)?;
+    if description.contains('\n') || description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidValue,
+            format!("the description must be a single line of at most {MAX_DESCRIPTION_LEN} characters"),
+        ));
+    }
+
+    let secp = Secp256k1::new();
+    let account = master.derive_priv(&secp, path)?;
+    let key = DescriptorPublicKey::XPub(DescriptorXKey {
+        origin: Some((master.fingerprint(&secp), path.clone())),
+        xkey: Xpub::from_priv(&secp, &account),
+        derivation_path: DerivationPath::master(),
+        wildcard: Wildcard::None,
+    });
+
+    let message = format!("{HEADER}\n{}\n{key}\n{description}", token.hex);
+    let digest = Message::from_digest(signed_msg_hash(&message).to_byte_array());
+    let signature = secp.sign_ecdsa_recoverable(&digest, &account.private_key);
+    let signature = MessageSignature::new(signature, true).to_base64();
+    Ok(token.encrypt(&format!("{message}\n{signature}")))
+}
+
+/// Decrypt and check the key record of a signer, returning its key and description.
+pub fn read_key_record(record: &str, token: &str) -> Result<(DescriptorPublicKey, String), BdkError> {
+    let token = Token::parse(token)?;
+    let record = token.decrypt(record)?;
+    let [header, record_token, key, description, signature] = lines(&record)?;
+    check_header(header)?;
+    if !record_token.eq_ignore_ascii_case(&token.hex) {
+        return Err(invalid("the key record is for another session"));
+    }
+
+    let descriptor_key = DescriptorPublicKey::from_str(key).map_err(|e| invalid(format!("invalid key: {e}")))?;
+    let public_key = match &descriptor_key {
+        DescriptorPublicKey::XPub(xkey)
+            if xkey.origin.is_some() && xkey.derivation_path.is_empty() && xkey.wildcard == Wildcard::None =>
+        {
+            xkey.xkey.public_key
+        }
+        _ => return Err(invalid("the key must be an extended public key with its origin")),
+    };
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(invalid("the description is too long"));
+    }
+
+    let message = format!("{header}\n{record_token}\n{key}\n{description}");
+    let signature = MessageSignature::from_base64(signature).map_err(|_| invalid("invalid signature"))?;
+    let signer = signature
+        .recover_pubkey(&Secp256k1::verification_only(), signed_msg_hash(&message))
+        .map_err(|_| invalid("invalid signature"))?;
+    if signer.inner != public_key {
+        return Err(invalid("the key record is not signed by its key"));
+    }
+    Ok((descriptor_key, description.to_string()))
+}
+
+/// Create the descriptor record of the coordinator for a multipath descriptor deriving its addresses with
+/// `<0;1>/*`.
+pub fn descriptor_record(descriptors: &DescriptorPair, network: Network, token: &str) -> Result<String, BdkError> {
+    let token = Token::parse(token)?;
+    let descriptor = descriptors.to_public_multipath()?;
+    let receive_and_change = DerivPaths::new(vec![
+        DerivationPath::from(vec![ChildNumber::Normal { index: 0 }]),
+        DerivationPath::from(vec![ChildNumber::Normal { index: 1 }]),
+    ])
+    .expect("two paths");
+    let supported = descriptor.for_each_key(|key| match key {
+        DescriptorPublicKey::MultiXPub(xkey) => {
+            xkey.derivation_paths == receive_and_change && xkey.wildcard == Wildcard::Unhardened
+        }
+        _ => false,
+    });
+    if !supported {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            "all the keys of the descriptor must be extended keys deriving with <0;1>/*",
+        ));
+    }
+
+    let descriptor_string = descriptor.to_string();
+    let template = descriptor_string
+        .split('#')
+        .next()
+        .unwrap_or_default()
+        .replace("/<0;1>/*", "/**");
+    let address = first_address(&descriptor, network)?;
+    Ok(token.encrypt(&format!("{HEADER}\n{template}\n{PATH_RESTRICTIONS}\n{address}")))
+}
+
+/// Decrypt and check the descriptor record of the coordinator, returning the descriptors and their first
+/// address. The descriptor must include `signer_key`, the key of the key record of the signer reading it.
+pub fn read_descriptor_record(
+    record: &str,
+    network: Network,
+    token: &str,
+    signer_key: &DescriptorPublicKey,
+) -> Result<(DescriptorPair, String), BdkError> {
+    let DescriptorPublicKey::XPub(signer_key) = signer_key else {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidKey,
+            "the signer key must be the extended public key of its key record",
+        ));
+    };
+    let token = Token::parse(token)?;
+    let record = token.decrypt(record)?;
+    let [header, template, path_restrictions, address] = lines(&record)?;
+    check_header(header)?;
+    if path_restrictions != PATH_RESTRICTIONS {
+        return Err(invalid(format!("unsupported path restrictions {path_restrictions}")));
+    }
+
+    let template = match template.split_once('#') {
+        Some((template, checksum)) if desc_checksum(template).ok().as_deref() == Some(checksum) => template,
+        Some(_) => return Err(invalid("invalid descriptor checksum")),
+        None => template,
+    };
+    if !template.contains("/**") {
+        return Err(invalid("the descriptor template has no key deriving with /**"));
+    }
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(&template.replace("/**", "/<0;1>/*"))
+        .map_err(|e| invalid(format!("invalid descriptor template: {e}")))?;
+    let is_signer = |key: &DescriptorPublicKey| match key {
+        DescriptorPublicKey::MultiXPub(xkey) => xkey.xkey == signer_key.xkey && xkey.origin == signer_key.origin,
+        _ => false,
+    };
+    if descriptor.for_each_key(|key| !is_signer(key)) {
+        return Err(invalid("the descriptor does not include the key of the signer"));
+    }
+    let first_address = first_address(&descriptor, network)?;
+    if first_address != address {
+        return Err(invalid(format!(
+            "the first address of the descriptor is {first_address}, not {address}"
+        )));
+    }
+    Ok((DescriptorPair::from_public_multipath(&descriptor)?, first_address))
+}
+
+/// The session token, and the key encrypting the records if it is not the token of sessions without
+/// encryption.
+struct Token {
+    hex: String,
+    bytes: Vec<u8>,
+    key: Option<[u8; 32]>,
+}
+
+impl Token {
+    fn parse(token: &str) -> Result<Self, BdkError> {
+        let hex = token.to_ascii_lowercase();
+        if hex == NO_ENCRYPTION_TOKEN {
+            return Ok(Token {
+                hex,
+                bytes: vec![],
+                key: None,
+            });
+        }
+        let bytes = Vec::from_hex(&hex)
+            .ok()
+            .filter(|bytes| matches!(bytes.len(), 8 | 16))
+            .ok_or_else(|| {
+                BdkError::new(
+                    BdkErrorCode::InvalidValue,
+                    "the token must be 00, or 16 or 32 hexadecimal characters",
+                )
+            })?;
+        // The key is the first 32 bytes of PBKDF2-HMAC-SHA512 of the password with the token as salt.
+        let key = pbkdf2::pbkdf2_hmac_array::<sha2::Sha512, 32>(PBKDF2_PASSWORD, &bytes, PBKDF2_ITERATIONS);
+        Ok(Token {
+            hex,
+            bytes,
+            key: Some(key),
+        })
+    }
+
+    /// The hex encoded MAC and ciphertext of the record, the MAC also being the IV of the cipher.
+    fn encrypt(&self, record: &str) -> String {
+        let Some(key) = &self.key else {
+            return record.to_string();
+        };
+        let mac = self.mac(key, record.as_bytes());
+        let mut data = record.as_bytes().to_vec();
+        Aes256Ctr::new(key.into(), mac[..16].into()).apply_keystream(&mut data);
+        [&mac[..], &data].concat().to_lower_hex_string()
+    }
+
+    fn decrypt(&self, record: &str) -> Result<String, BdkError> {
+        let Some(key) = &self.key else {
+            return Ok(record.to_string());
+        };
+        let encrypted = Vec::from_hex(record.trim()).map_err(|_| invalid("the record is not encrypted"))?;
+        if encrypted.len() < 32 {
+            return Err(invalid("the encrypted record is too short"));
+        }
+        let (mac, data) = encrypted.split_at(32);
+        let mut data = data.to_vec();
+        Aes256Ctr::new(key.into(), mac[..16].into()).apply_keystream(&mut data);
+        if self.mac(key, &data)[..] != *mac {
+            return Err(invalid("the record could not be decrypted, check the token"));
+        }
+        String::from_utf8(data).map_err(|_| invalid("the record is not valid UTF-8"))
+    }
+
+    /// HMAC-SHA256 of the token and the data, keyed with the SHA256 of the encryption key.
+    fn mac(&self, key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+        let mut engine = hmac::HmacEngine::<sha256::Hash>::new(sha256::Hash::hash(key).as_byte_array());
+        engine.input(&self.bytes);
+        engine.input(data);
+        hmac::Hmac::from_engine(engine).to_byte_array()
+    }
+}
+
+fn first_address(descriptor: &Descriptor<DescriptorPublicKey>, network: Network) -> Result<String, BdkError> {
+    let receive = descriptor
+        .clone()
+        .into_single_descriptors()?
+        .into_iter()
+        .next()
+        .expect("a descriptor");
+    let address = receive
+        .at_derivation_index(0)
+        .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?
+        .address(network)?;
+    Ok(address.to_string())
+}
+
+/// The lines of a record, without the line ending of the last line.
+fn lines<const N: usize>(record: &str) -> Result<[&str; N], BdkError> {
+    let lines = record
+        .trim_end_matches(['\r', '\n'])
+        .split('\n')
+        .map(|line| line.trim_end_matches('\r'))
+        .collect::<Vec<_>>();
+    lines
+        .try_into()
+        .map_err(|lines: Vec<_>| invalid(format!("the record has {} lines instead of {N}", lines.len())))
+}
+
+fn check_header(header: &str) -> Result<(), BdkError> {
+    if header != HEADER {
+        return Err(invalid(format!("unsupported record version {header}")));
+    }
+    Ok(())
+}
+
+fn invalid(message: impl std::fmt::Display) -> BdkError {
+    BdkError::new(BdkErrorCode::InvalidBsms, message)
+}
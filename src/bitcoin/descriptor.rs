@@ -1,26 +1,32 @@
-use anyhow::{anyhow, Error};
+use std::collections::BTreeMap;
+
 use bdk_wallet::{
     keys::{DerivableKey, ExtendedKey},
+    miniscript::{
+        descriptor::{checksum::desc_checksum, DerivPaths, DescriptorMultiXKey, DescriptorSecretKey, KeyMap},
+        translate_hash_clone, Descriptor, DescriptorPublicKey, ForEachKey, Legacy, Segwitv0, Tap, TranslateErr,
+        TranslatePk, Translator,
+    },
     template::{
         Bip44, Bip44Public, Bip49, Bip49Public, Bip84, Bip84Public, Bip86, Bip86Public, DescriptorTemplate,
-        DescriptorTemplateOut,
+        DescriptorTemplateOut, P2Pkh, P2Wpkh, P2Wpkh_P2Sh, P2TR,
     },
     KeychainKind,
 };
 use bitcoin::{
-    bip32::{ChainCode, Fingerprint, Xpriv, Xpub},
+    bip32::{ChainCode, ChildNumber, DerivationPath, Fingerprint, Xpriv, Xpub},
     hex::FromHex,
-    secp256k1::{PublicKey, SecretKey},
+    secp256k1::{PublicKey, Secp256k1, SecretKey},
     AddressType, Network,
 };
 
-use crate::types::SLIP10Node;
+use crate::types::{BdkError, BdkErrorCode, SLIP10Node};
 
 pub fn seed_to_descriptor(
     seed: &[u8],
     network: Network,
     address_type: AddressType,
-) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), Error> {
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError> {
     let xprv = seed_to_xpriv(seed, network)?;
 
     match address_type {
@@ -28,7 +34,10 @@ pub fn seed_to_descriptor(
         AddressType::P2sh => build_xpriv_descriptor(Bip49, xprv, network),
         AddressType::P2wpkh => build_xpriv_descriptor(Bip84, xprv, network),
         AddressType::P2tr => build_xpriv_descriptor(Bip86, xprv, network),
-        _ => Err(anyhow!("Unsupported address type")),
+        _ => Err(BdkError::new(
+            BdkErrorCode::UnsupportedAddressType,
+            "Unsupported address type",
+        )),
     }
 }
 
@@ -37,13 +46,16 @@ pub fn xpriv_to_descriptor(
     fingerprint: Fingerprint,
     network: Network,
     address_type: AddressType,
-) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), Error> {
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError> {
     match address_type {
         AddressType::P2pkh => build_descriptor(Bip44Public, xprv, fingerprint, network),
         AddressType::P2sh => build_descriptor(Bip49Public, xprv, fingerprint, network),
         AddressType::P2wpkh => build_descriptor(Bip84Public, xprv, fingerprint, network),
         AddressType::P2tr => build_descriptor(Bip86Public, xprv, fingerprint, network),
-        _ => Err(anyhow!("Unsupported address type")),
+        _ => Err(BdkError::new(
+            BdkErrorCode::UnsupportedAddressType,
+            "Unsupported address type",
+        )),
     }
 }
 
@@ -52,24 +64,27 @@ pub fn xpub_to_descriptor(
     fingerprint: Fingerprint,
     network: Network,
     address_type: AddressType,
-) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), Error> {
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError> {
     match address_type {
         AddressType::P2pkh => build_descriptor(Bip44Public, xpub, fingerprint, network),
         AddressType::P2sh => build_descriptor(Bip49Public, xpub, fingerprint, network),
         AddressType::P2wpkh => build_descriptor(Bip84Public, xpub, fingerprint, network),
         AddressType::P2tr => build_descriptor(Bip86Public, xpub, fingerprint, network),
-        _ => Err(anyhow!("Unsupported address type")),
+        _ => Err(BdkError::new(
+            BdkErrorCode::UnsupportedAddressType,
+            "Unsupported address type",
+        )),
     }
 }
 
-pub fn slip10_to_extended(node: SLIP10Node, network: Network) -> Result<ExtendedKey, anyhow::Error> {
+pub fn slip10_to_extended(node: SLIP10Node, network: Network) -> Result<ExtendedKey, BdkError> {
     let parent_fingerprint: Fingerprint = node.parent_fingerprint.to_be_bytes().into();
-    let chain_code = ChainCode::from_hex(strip_0x_prefix(&node.chain_code))?;
+    let chain_code = ChainCode::from_hex(strip_0x_prefix(&node.chain_code)).map_err(invalid_key)?;
 
     match node.private_key {
         Some(priv_key) => {
-            let priv_key_vec = Vec::from_hex(strip_0x_prefix(&priv_key))?;
-            let private_key = SecretKey::from_slice(&priv_key_vec)?;
+            let priv_key_vec = Vec::from_hex(strip_0x_prefix(&priv_key)).map_err(invalid_key)?;
+            let private_key = SecretKey::from_slice(&priv_key_vec).map_err(invalid_key)?;
 
             let xpriv = Xpriv {
                 network: network.into(),
@@ -83,8 +98,8 @@ pub fn slip10_to_extended(node: SLIP10Node, network: Network) -> Result<Extended
             Ok(xpriv.into())
         }
         None => {
-            let pubkey_vec = Vec::from_hex(strip_0x_prefix(&node.public_key))?;
-            let public_key = PublicKey::from_slice(&pubkey_vec)?;
+            let pubkey_vec = Vec::from_hex(strip_0x_prefix(&node.public_key)).map_err(invalid_key)?;
+            let public_key = PublicKey::from_slice(&pubkey_vec).map_err(invalid_key)?;
 
             let xpub = Xpub {
                 network: network.into(),
@@ -100,16 +115,262 @@ pub fn slip10_to_extended(node: SLIP10Node, network: Network) -> Result<Extended
     }
 }
 
-pub fn seed_to_xpriv(seed: &[u8], network: Network) -> Result<Xpriv, Error> {
+/// Build a descriptor pair from a SLIP-10 node at the account level of a BIP44/49/84/86 path.
+pub fn slip10_to_descriptor(
+    node: SLIP10Node,
+    network: Network,
+    address_type: AddressType,
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError> {
+    if node.curve != "secp256k1" {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidKey,
+            format!("Unsupported curve {}, expected secp256k1", node.curve),
+        ));
+    }
+
+    let fingerprint: Fingerprint = node
+        .master_fingerprint
+        .ok_or_else(|| {
+            BdkError::new(
+                BdkErrorCode::InvalidKey,
+                "SLIP-10 node is missing its master fingerprint",
+            )
+        })?
+        .to_be_bytes()
+        .into();
+
+    // BIP44/49/84/86 account keys are at m/purpose'/coin_type'/account'
+    if node.depth != 3 {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidKey,
+            format!(
+                "SLIP-10 node must be at the account level (depth 3), found depth {}",
+                node.depth
+            ),
+        ));
+    }
+    let account = ChildNumber::from(node.index);
+    if !account.is_hardened() {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidKey,
+            "SLIP-10 node index must be a hardened account index",
+        ));
+    }
+
+    match slip10_to_extended(node, network)? {
+        ExtendedKey::Private((xprv, _)) => build_account_descriptor(xprv, fingerprint, account, network, address_type),
+        ExtendedKey::Public((xpub, _)) => build_account_descriptor(xpub, fingerprint, account, network, address_type),
+    }
+}
+
+pub fn seed_to_xpriv(seed: &[u8], network: Network) -> Result<Xpriv, BdkError> {
     let xprv = Xpriv::new_master(network, seed)?;
     Ok(xprv)
 }
 
+/// Split a BIP389 multipath descriptor with two paths into its external and internal descriptors.
+///
+/// The split is done on the descriptor string because miniscript cannot parse multipath extended
+/// private keys, each resulting descriptor is then parsed and validated.
+pub fn split_multipath_descriptor(descriptor: &str) -> Result<(String, String), BdkError> {
+    let descriptor = match descriptor.split_once('#') {
+        Some((descriptor, checksum)) => {
+            let expected = desc_checksum(descriptor)?;
+            if checksum != expected {
+                return Err(BdkError::new(
+                    BdkErrorCode::InvalidDescriptor,
+                    format!("Invalid checksum '{checksum}', expected '{expected}'"),
+                ));
+            }
+            descriptor
+        }
+        None => descriptor,
+    };
+
+    let mut external = String::new();
+    let mut internal = String::new();
+    let mut rest = descriptor;
+    while let Some(start) = rest.find('<') {
+        let end = rest[start..]
+            .find('>')
+            .map(|end| start + end)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::InvalidDescriptor, "Unterminated multipath expression"))?;
+        let paths: Vec<&str> = rest[start + 1..end].split(';').collect();
+        if paths.len() != 2 {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidDescriptor,
+                format!("Multipath expressions must have exactly 2 paths, found {}", paths.len()),
+            ));
+        }
+
+        external.push_str(&rest[..start]);
+        external.push_str(paths[0]);
+        internal.push_str(&rest[..start]);
+        internal.push_str(paths[1]);
+        rest = &rest[end + 1..];
+    }
+    if rest.len() == descriptor.len() {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            "Descriptor is not a multipath descriptor",
+        ));
+    }
+    external.push_str(rest);
+    internal.push_str(rest);
+
+    let secp = Secp256k1::new();
+    let (external, external_keymap) = Descriptor::parse_descriptor(&secp, &external)?;
+    let (internal, internal_keymap) = Descriptor::parse_descriptor(&secp, &internal)?;
+
+    Ok((
+        external.to_string_with_secret(&external_keymap),
+        internal.to_string_with_secret(&internal_keymap),
+    ))
+}
+
+/// Join an external and an internal descriptor into a single BIP389 multipath descriptor.
+///
+/// Both descriptors must be identical except for the derivation paths of their extended keys.
+pub fn join_multipath_descriptor(external: &str, internal: &str) -> Result<String, BdkError> {
+    let secp = Secp256k1::new();
+    let (external, external_keymap) = Descriptor::parse_descriptor(&secp, external)?;
+    let (internal, internal_keymap) = Descriptor::parse_descriptor(&secp, internal)?;
+    if external.is_multipath() || internal.is_multipath() {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            "Descriptors are already multipath descriptors",
+        ));
+    }
+
+    let external_keys = collect_keys(&external);
+    let internal_keys = collect_keys(&internal);
+    if external_keys.len() != internal_keys.len() {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            "Descriptors do not have the same keys",
+        ));
+    }
+
+    let mut translator = MultipathTranslator(BTreeMap::new());
+    let mut keymap = KeyMap::new();
+    for (external_key, internal_key) in external_keys.into_iter().zip(internal_keys) {
+        let (multi_key, secret) = join_multipath_key(
+            &external_key,
+            &internal_key,
+            external_keymap.get(&external_key),
+            internal_keymap.get(&internal_key),
+        )?;
+        if let Some(secret) = secret {
+            keymap.insert(multi_key.clone(), secret);
+        }
+        if translator
+            .0
+            .insert(external_key, multi_key.clone())
+            .is_some_and(|k| k != multi_key)
+        {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidDescriptor,
+                "Descriptors do not have the same keys",
+            ));
+        }
+    }
+
+    let multipath = external.translate_pk(&mut translator).map_err(|e| match e {
+        TranslateErr::TranslatorErr(e) => e,
+        TranslateErr::OuterError(e) => e.into(),
+    })?;
+
+    // The translation only looked at the keys, make sure the scripts match as well.
+    if multipath.clone().into_single_descriptors()? != vec![external, internal] {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            "Descriptors differ beyond the derivation paths of their keys",
+        ));
+    }
+
+    Ok(multipath.to_string_with_secret(&keymap))
+}
+
+fn collect_keys(descriptor: &Descriptor<DescriptorPublicKey>) -> Vec<DescriptorPublicKey> {
+    let mut keys = Vec::new();
+    descriptor.for_each_key(|key| {
+        keys.push(key.clone());
+        true
+    });
+    keys
+}
+
+fn join_multipath_key(
+    external: &DescriptorPublicKey,
+    internal: &DescriptorPublicKey,
+    external_secret: Option<&DescriptorSecretKey>,
+    internal_secret: Option<&DescriptorSecretKey>,
+) -> Result<(DescriptorPublicKey, Option<DescriptorSecretKey>), BdkError> {
+    if external == internal {
+        return Ok((external.clone(), external_secret.cloned()));
+    }
+
+    let (DescriptorPublicKey::XPub(ext), DescriptorPublicKey::XPub(int)) = (external, internal) else {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            format!("Keys {external} and {internal} cannot be joined into a multipath key"),
+        ));
+    };
+    if ext.origin != int.origin || ext.xkey != int.xkey || ext.wildcard != int.wildcard {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            format!("Keys {external} and {internal} cannot be joined into a multipath key"),
+        ));
+    }
+    let derivation_paths = DerivPaths::new(vec![ext.derivation_path.clone(), int.derivation_path.clone()])
+        .expect("derivation paths are not empty");
+
+    let public = DescriptorPublicKey::MultiXPub(DescriptorMultiXKey {
+        origin: ext.origin.clone(),
+        xkey: ext.xkey,
+        derivation_paths: derivation_paths.clone(),
+        wildcard: ext.wildcard,
+    });
+
+    let secret = match (external_secret, internal_secret) {
+        (None, None) => None,
+        (Some(DescriptorSecretKey::XPrv(ext)), Some(DescriptorSecretKey::XPrv(int))) if ext.xkey == int.xkey => {
+            Some(DescriptorSecretKey::MultiXPrv(DescriptorMultiXKey {
+                origin: ext.origin.clone(),
+                xkey: ext.xkey,
+                derivation_paths,
+                wildcard: ext.wildcard,
+            }))
+        }
+        _ => {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidDescriptor,
+                format!("Keys {external} and {internal} do not have the same secret"),
+            ))
+        }
+    };
+
+    Ok((public, secret))
+}
+
+struct MultipathTranslator(BTreeMap<DescriptorPublicKey, DescriptorPublicKey>);
+
+impl Translator<DescriptorPublicKey, DescriptorPublicKey, BdkError> for MultipathTranslator {
+    fn pk(&mut self, pk: &DescriptorPublicKey) -> Result<DescriptorPublicKey, BdkError> {
+        self.0
+            .get(pk)
+            .cloned()
+            .ok_or_else(|| BdkError::new(BdkErrorCode::InvalidDescriptor, format!("Unknown key {pk}")))
+    }
+
+    translate_hash_clone!(DescriptorPublicKey, DescriptorPublicKey, BdkError);
+}
+
 fn build_xpriv_descriptor<T>(
     constructor: impl Fn(Xpriv, KeychainKind) -> T,
     xprv: Xpriv,
     network: Network,
-) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), Error>
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError>
 where
     T: DescriptorTemplate,
 {
@@ -130,7 +391,7 @@ fn build_descriptor<T, K>(
     key: T,
     fingerprint: Fingerprint,
     network: Network,
-) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), Error>
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError>
 where
     T: DerivableKey + Clone,
     K: DescriptorTemplate,
@@ -147,6 +408,61 @@ where
     Ok((ext, int))
 }
 
+fn build_account_descriptor<K>(
+    key: K,
+    fingerprint: Fingerprint,
+    account: ChildNumber,
+    network: Network,
+    address_type: AddressType,
+) -> Result<(DescriptorTemplateOut, DescriptorTemplateOut), BdkError>
+where
+    K: DerivableKey<Legacy> + DerivableKey<Segwitv0> + DerivableKey<Tap> + Clone,
+{
+    let purpose = match address_type {
+        AddressType::P2pkh => 44,
+        AddressType::P2sh => 49,
+        AddressType::P2wpkh => 84,
+        AddressType::P2tr => 86,
+        _ => {
+            return Err(BdkError::new(
+                BdkErrorCode::UnsupportedAddressType,
+                "Unsupported address type",
+            ))
+        }
+    };
+    let coin_type = match network {
+        Network::Bitcoin => 0,
+        _ => 1,
+    };
+    let origin = DerivationPath::from(vec![
+        ChildNumber::from_hardened_idx(purpose)?,
+        ChildNumber::from_hardened_idx(coin_type)?,
+        account,
+    ]);
+
+    let build = |keychain: KeychainKind| -> Result<DescriptorTemplateOut, BdkError> {
+        let path: DerivationPath = match keychain {
+            KeychainKind::External => vec![ChildNumber::from_normal_idx(0)?].into(),
+            KeychainKind::Internal => vec![ChildNumber::from_normal_idx(1)?].into(),
+        };
+        let key = (key.clone(), (fingerprint, origin.clone()), path);
+        let descriptor = match address_type {
+            AddressType::P2pkh => P2Pkh(key).build(network)?,
+            AddressType::P2sh => P2Wpkh_P2Sh(key).build(network)?,
+            AddressType::P2wpkh => P2Wpkh(key).build(network)?,
+            _ => P2TR(key).build(network)?,
+        };
+        descriptor.0.sanity_check()?;
+        Ok(descriptor)
+    };
+
+    Ok((build(KeychainKind::External)?, build(KeychainKind::Internal)?))
+}
+
+fn invalid_key(e: impl std::fmt::Display) -> BdkError {
+    BdkError::new(BdkErrorCode::InvalidKey, e)
+}
+
 fn strip_0x_prefix(s: &str) -> &str {
     if s.starts_with("0x") || s.starts_with("0X") {
         &s[2..]
@@ -1,22 +1,48 @@
-use bdk_core::spk_client::{FullScanRequest as BdkFullScanRequest, SyncRequest as BdkSyncRequest};
+use bdk_core::{
+    spk_client::{
+        FullScanRequest as BdkFullScanRequest, FullScanResponse as BdkFullScanResponse, SyncRequest as BdkSyncRequest,
+    },
+    BlockId, ConfirmationBlockTime, Indexed, TxUpdate,
+};
 use bdk_esplora::{
-    esplora_client::{AsyncClient, Builder},
+    esplora_client::{AsyncClient, Builder, Tx},
     EsploraAsyncExt,
 };
-use bdk_wallet::KeychainKind;
-use wasm_bindgen::prelude::wasm_bindgen;
+use bdk_wallet::{
+    bitcoin::{Amount, OutPoint, Script, ScriptBuf, TxOut, Txid},
+    serde_json::json,
+    KeychainKind, Wallet as BdkWallet,
+};
+use futures::{stream::FuturesOrdered, TryStreamExt};
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
 
 use crate::{
     result::JsResult,
-    types::{FullScanRequest, SyncRequest, Update},
+    types::{BdkError, BdkErrorCode, Birthday, FeeEstimates, FullScanRequest, SyncRequest, Update},
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::{pending, poll_fn, Future},
+    pin::pin,
+    task::Poll,
+    time::Duration,
 };
-use std::time::Duration;
 
 use bdk_esplora::esplora_client::Sleeper;
 use gloo_timers::future::{sleep, TimeoutFuture};
 
 use crate::utils::SendSyncWrapper;
 
+/// How far the timestamp of a block can be ahead of the time it was mined, in seconds, like the rescan window of
+/// Bitcoin Core.
+const TIMESTAMP_WINDOW: u64 = 2 * 60 * 60;
+
+/// The number of confirmed transactions per page of the history of a script.
+const PAGE_SIZE: usize = 25;
+
 #[wasm_bindgen]
 pub struct EsploraClient {
     client: AsyncClient<WebSleeper>,
@@ -30,22 +56,364 @@ impl EsploraClient {
         Ok(EsploraClient { client })
     }
 
+    /// Full scan the scripts of the request, up to `stop_gap` unused scripts per keychain.
+    ///
+    /// If the request has a birthday, the history of each script is only paged until it reaches the birthday,
+    /// and the transactions confirmed at or before it are skipped. The scripts used before the birthday still
+    /// count as used for the stop gap.
+    ///
+    /// The scan fails with [`BdkErrorCode::Aborted`] when `signal` is aborted, and with [`BdkErrorCode::Timeout`]
+    /// after `timeout` milliseconds. Its pending HTTP requests are then aborted, and as the wallet is only
+    /// changed by applying the returned [`Update`], nothing of the scan is kept.
     pub async fn full_scan(
         &mut self,
         request: FullScanRequest,
         stop_gap: usize,
         parallel_requests: usize,
+        signal: Option<AbortSignal>,
+        timeout: Option<u32>,
     ) -> JsResult<Update> {
+        let birthday = request.birthday();
         let request: BdkFullScanRequest<KeychainKind> = request.into();
-        let result = self.client.full_scan(request, stop_gap, parallel_requests).await?;
+        let scan = async {
+            match birthday {
+                Some(birthday) => {
+                    self.full_scan_from(request, birthday, stop_gap, parallel_requests)
+                        .await
+                }
+                None => Ok(self.client.full_scan(request, stop_gap, parallel_requests).await?),
+            }
+        };
+        let result = cancellable(scan, signal, timeout).await?;
         Ok(result.into())
     }
 
-    pub async fn sync(&mut self, request: SyncRequest, parallel_requests: usize) -> JsResult<Update> {
+    /// Sync the scripts, txids and outpoints of the request.
+    ///
+    /// Like [`EsploraClient::full_scan`], the sync can be aborted by `signal` or time out after `timeout`
+    /// milliseconds.
+    pub async fn sync(
+        &mut self,
+        request: SyncRequest,
+        parallel_requests: usize,
+        signal: Option<AbortSignal>,
+        timeout: Option<u32>,
+    ) -> JsResult<Update> {
         let request: BdkSyncRequest<(KeychainKind, u32)> = request.into();
-        let result = self.client.sync(request, parallel_requests).await?;
+        let sync = async { Ok(self.client.sync(request, parallel_requests).await?) };
+        let result = cancellable(sync, signal, timeout).await?;
         Ok(result.into())
     }
+
+    /// Get the fee rate estimates of the server, indexed by confirmation target in blocks.
+    pub async fn get_fee_estimates(&self) -> JsResult<FeeEstimates> {
+        let estimates = self.client.get_fee_estimates().await?;
+        Ok(estimates.into())
+    }
+
+    /// The birthday of a wallet whose first transaction is at `height` or later, the block before `height`.
+    ///
+    /// Errors if `height` is above the tip of the server.
+    pub async fn get_birthday_at_height(&self, height: u32) -> JsResult<Birthday> {
+        let tip = self.client.get_height().await?;
+        if height > tip {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                format!("the height {height} is above the tip {tip}"),
+            ));
+        }
+        Ok(self.get_block_time(height.saturating_sub(1)).await?.into())
+    }
+
+    /// The birthday of a wallet whose first transaction is at `timestamp` or later, in seconds since the Unix epoch.
+    ///
+    /// The birthday is the last block timestamped more than two hours before `timestamp`, as block timestamps can
+    /// be ahead of the time blocks are mined. Found with a binary search over the heights of the chain.
+    pub async fn get_birthday_at_time(&self, timestamp: u64) -> JsResult<Birthday> {
+        let timestamp = timestamp.saturating_sub(TIMESTAMP_WINDOW);
+        let tip = self.get_block_time(self.client.get_height().await?).await?;
+        if tip.confirmation_time < timestamp {
+            return Ok(tip.into());
+        }
+
+        // The block at `low` is timestamped before `timestamp`, the block at `high` is not.
+        let (mut low, mut high) = (self.get_block_time(0).await?, tip);
+        if low.confirmation_time >= timestamp {
+            return Ok(low.into());
+        }
+        while high.block_id.height - low.block_id.height > 1 {
+            let middle = low.block_id.height + (high.block_id.height - low.block_id.height) / 2;
+            let block = self.get_block_time(middle).await?;
+            if block.confirmation_time < timestamp {
+                low = block;
+            } else {
+                high = block;
+            }
+        }
+        Ok(low.into())
+    }
+}
+
+impl EsploraClient {
+    /// Full scan and update a wallet that is not exposed to JavaScript, like the temporary wallets of a sweep.
+    pub(crate) async fn full_scan_wallet(
+        &self,
+        wallet: &mut BdkWallet,
+        stop_gap: usize,
+        parallel_requests: usize,
+        seen_at: u64,
+    ) -> JsResult<()> {
+        let request = wallet.start_full_scan().build();
+        let update = self.client.full_scan(request, stop_gap, parallel_requests).await?;
+        wallet.apply_update_at(update, seen_at)?;
+        Ok(())
+    }
+
+    /// Full scan like [`EsploraAsyncExt::full_scan`], skipping the history confirmed at or before `birthday`.
+    ///
+    /// The chain update is the one of a sync of the chain tip alone, fetching the latest blocks before the
+    /// transactions like the full scan does, completed with the blocks of the transactions found.
+    async fn full_scan_from(
+        &self,
+        mut request: BdkFullScanRequest<KeychainKind>,
+        birthday: u32,
+        stop_gap: usize,
+        parallel_requests: usize,
+    ) -> JsResult<BdkFullScanResponse<KeychainKind>> {
+        let chain_update = match request.chain_tip() {
+            Some(chain_tip) => {
+                let sync = BdkSyncRequest::<()>::builder().chain_tip(chain_tip).build();
+                self.client.sync(sync, parallel_requests).await?.chain_update
+            }
+            None => None,
+        };
+
+        let mut tx_update = TxUpdate::default();
+        let mut inserted_txs = HashSet::new();
+        let mut last_active_indices = BTreeMap::new();
+        for keychain in request.keychains() {
+            let spks = request.iter_spks(keychain);
+            let last_active_index = self
+                .fetch_keychain_txs(
+                    spks,
+                    birthday,
+                    stop_gap,
+                    parallel_requests,
+                    &mut tx_update,
+                    &mut inserted_txs,
+                )
+                .await?;
+            if let Some(index) = last_active_index {
+                last_active_indices.insert(keychain, index);
+            }
+        }
+
+        // Like the full scan, blocks above the latest ones fetched are left for the next scan.
+        let chain_update = chain_update.map(|mut tip| {
+            for (anchor, _) in &tx_update.anchors {
+                let height = anchor.block_id.height;
+                if height <= tip.height() && tip.get(height).is_none() {
+                    tip = tip.insert(anchor.block_id);
+                }
+            }
+            tip
+        });
+        Ok(BdkFullScanResponse {
+            chain_update,
+            tx_update,
+            last_active_indices,
+        })
+    }
+
+    /// Fetch the transactions of the scripts of a keychain after `birthday`, until `stop_gap` consecutive scripts
+    /// have no history. Returns the index of the last script with a history.
+    async fn fetch_keychain_txs(
+        &self,
+        mut spks: impl Iterator<Item = Indexed<ScriptBuf>>,
+        birthday: u32,
+        stop_gap: usize,
+        parallel_requests: usize,
+        update: &mut TxUpdate<ConfirmationBlockTime>,
+        inserted_txs: &mut HashSet<Txid>,
+    ) -> JsResult<Option<u32>> {
+        let mut last_index = None;
+        let mut last_active_index = None;
+        loop {
+            let requests =
+                spks.by_ref()
+                    .take(parallel_requests)
+                    .map(|(index, spk)| async move {
+                        Ok::<_, BdkError>((index, self.fetch_script_txs(&spk, birthday).await?))
+                    })
+                    .collect::<FuturesOrdered<_>>();
+            if requests.is_empty() {
+                break;
+            }
+
+            for (index, txs) in requests.try_collect::<Vec<_>>().await? {
+                last_index = Some(index);
+                if !txs.is_empty() {
+                    last_active_index = Some(index);
+                }
+                let after_birthday = txs
+                    .into_iter()
+                    .filter(|tx| tx.status.block_height.map_or(true, |height| height > birthday));
+                for tx in after_birthday {
+                    insert_tx(update, inserted_txs, tx);
+                }
+            }
+
+            let last_index = last_index.expect("set for every request");
+            let gap_reached = match last_active_index {
+                Some(active) => last_index >= active.saturating_add(stop_gap as u32),
+                None => last_index + 1 >= stop_gap as u32,
+            };
+            if gap_reached {
+                break;
+            }
+        }
+        Ok(last_active_index)
+    }
+
+    /// The history of a script, newest first, without the pages after the first one reaching `birthday`.
+    async fn fetch_script_txs(&self, script: &Script, birthday: u32) -> JsResult<Vec<Tx>> {
+        let mut txs: Vec<Tx> = Vec::new();
+        loop {
+            let page = self.client.scripthash_txs(script, txs.last().map(|tx| tx.txid)).await?;
+            let reached_birthday = page
+                .last()
+                .and_then(|tx| tx.status.block_height)
+                .is_some_and(|height| height <= birthday);
+            let last_page = page.len() < PAGE_SIZE || reached_birthday;
+            txs.extend(page);
+            if last_page {
+                return Ok(txs);
+            }
+        }
+    }
+
+    async fn get_block_time(&self, height: u32) -> JsResult<ConfirmationBlockTime> {
+        let blocks = self.client.get_blocks(Some(height)).await?;
+        let block = blocks
+            .into_iter()
+            .find(|block| block.time.height == height)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::Esplora, format!("the server has no block at {height}")))?;
+        Ok(ConfirmationBlockTime {
+            block_id: BlockId { height, hash: block.id },
+            confirmation_time: block.time.timestamp,
+        })
+    }
+}
+
+/// Add a transaction from Esplora to the update, with its anchor and the previous outputs of its inputs.
+fn insert_tx(update: &mut TxUpdate<ConfirmationBlockTime>, inserted_txs: &mut HashSet<Txid>, tx: Tx) {
+    if inserted_txs.insert(tx.txid) {
+        update.txs.push(tx.to_tx().into());
+    }
+    if let (Some(height), Some(hash), Some(time)) = (tx.status.block_height, tx.status.block_hash, tx.status.block_time)
+    {
+        let anchor = ConfirmationBlockTime {
+            block_id: BlockId { height, hash },
+            confirmation_time: time,
+        };
+        update.anchors.insert((anchor, tx.txid));
+    }
+    for vin in tx.vin {
+        if let Some(prevout) = vin.prevout {
+            let txout = TxOut {
+                value: Amount::from_sat(prevout.value),
+                script_pubkey: prevout.scriptpubkey,
+            };
+            update.txouts.insert(OutPoint::new(vin.txid, vin.vout), txout);
+        }
+    }
+}
+
+/// Run `operation` until it completes, `signal` is aborted or `timeout` milliseconds elapse.
+///
+/// The operation is dropped when it is cancelled, aborting the fetches of its pending HTTP requests.
+async fn cancellable<T>(
+    operation: impl Future<Output = JsResult<T>>,
+    signal: Option<AbortSignal>,
+    timeout: Option<u32>,
+) -> JsResult<T> {
+    if let Some(signal) = &signal {
+        if signal.aborted() {
+            return Err(aborted(signal));
+        }
+    }
+
+    let timed_out = async {
+        match timeout {
+            Some(timeout) => {
+                TimeoutFuture::new(timeout).await;
+                Err(BdkError::new(
+                    BdkErrorCode::Timeout,
+                    format!("the operation did not complete in {timeout} ms"),
+                )
+                .with_data(json!({ "timeout": timeout })))
+            }
+            None => pending().await,
+        }
+    };
+    let abort = async {
+        match &signal {
+            Some(signal) => {
+                abort_event(signal).await;
+                Err(aborted(signal))
+            }
+            None => pending().await,
+        }
+    };
+    race(operation, race(timed_out, abort)).await
+}
+
+/// Wait for the `abort` event of `signal`, removing the listener when dropped.
+async fn abort_event(signal: &AbortSignal) {
+    let mut listener = None;
+    let promise = Promise::new(&mut |resolve, _| listener = Some(resolve));
+    let Some(listener) = listener else {
+        return pending().await;
+    };
+
+    struct Listening<'a>(&'a AbortSignal, &'a Function);
+    impl Drop for Listening<'_> {
+        fn drop(&mut self) {
+            let _ = self.0.remove_event_listener_with_callback("abort", self.1);
+        }
+    }
+
+    if signal.add_event_listener_with_callback("abort", &listener).is_err() {
+        return pending().await;
+    }
+    let _listening = Listening(signal, &listener);
+    let _ = JsFuture::from(promise).await;
+}
+
+/// The error of an aborted `signal`, a timeout if it was aborted by `AbortSignal.timeout()`.
+fn aborted(signal: &AbortSignal) -> BdkError {
+    let reason = signal.reason();
+    let name = Reflect::get(&reason, &JsValue::from_str("name"))
+        .ok()
+        .and_then(|name| name.as_string());
+    let message = Reflect::get(&reason, &JsValue::from_str("message"))
+        .ok()
+        .and_then(|message| message.as_string())
+        .unwrap_or_else(|| "the operation was aborted".to_string());
+    match name.as_deref() {
+        Some("TimeoutError") => BdkError::new(BdkErrorCode::Timeout, message),
+        _ => BdkError::new(BdkErrorCode::Aborted, message),
+    }
+}
+
+/// Poll both futures, resolving with the output of the first one ready and dropping the other one.
+async fn race<T>(first: impl Future<Output = T>, second: impl Future<Output = T>) -> T {
+    let (mut first, mut second) = (pin!(first), pin!(second));
+    poll_fn(|cx| match first.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(output),
+        Poll::Pending => second.as_mut().poll(cx),
+    })
+    .await
 }
 
 #[derive(Clone)]
@@ -0,0 +1,283 @@
+use std::str::FromStr;
+
+use bdk_wallet::{
+    chain::{ChainPosition, ConfirmationBlockTime},
+    export::FullyNodedExport,
+    miniscript::{
+        descriptor::{DescriptorMultiXKey, ShInner, WshInner},
+        Descriptor, DescriptorPublicKey,
+    },
+    serde_json::{self, json, Value},
+    KeychainKind, Wallet as BdkWallet,
+};
+use bitcoin::{
+    bip32::{DerivationPath, Fingerprint, Xpub},
+    AddressType, Network, NetworkKind,
+};
+use serde::Deserialize;
+
+use crate::types::{BdkError, BdkErrorCode, DescriptorPair, Slip132Version};
+
+use super::Slip132Key;
+
+/// Default size of the keypool of Bitcoin Core, the number of addresses it watches past the next index.
+const CORE_KEYPOOL_SIZE: u32 = 1000;
+
+/// Version of the Electrum wallet files we write, Electrum upgrades them when opening them.
+const ELECTRUM_SEED_VERSION: u32 = 17;
+
+/// Export the public descriptors of a wallet in the FullyNoded format.
+///
+/// The block height is the height of `birthday` or of the earliest confirmed transaction of the wallet, whichever
+/// is lower, 0 if there is neither.
+pub fn export_fully_noded(
+    wallet: &BdkWallet,
+    birthday: Option<ConfirmationBlockTime>,
+    label: &str,
+) -> Result<FullyNodedExport, BdkError> {
+    // Upstream checks that Bitcoin Core supports the descriptor and that the internal descriptor derives with
+    // `/1/*`, but its export includes the private keys of the wallet, so only the label is kept.
+    let export = FullyNodedExport::export_wallet(wallet, label, false)
+        .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?;
+    let export = json!({
+        "descriptor": without_checksum(wallet, KeychainKind::External),
+        "blockheight": birth(wallet, birthday).map_or(0, |anchor| anchor.block_id.height),
+        "label": export.label,
+    });
+    Ok(serde_json::from_value(export)?)
+}
+
+/// The `importdescriptors` request making a Bitcoin Core wallet watch the public descriptors of a wallet.
+///
+/// Core rescans from the time of `birthday` or of the block of the earliest confirmed transaction of the wallet,
+/// whichever is lower, or from the genesis block if there is neither.
+pub fn export_core_descriptors(wallet: &BdkWallet, birthday: Option<ConfirmationBlockTime>) -> Value {
+    let timestamp = birth(wallet, birthday).map_or(0, |anchor| anchor.confirmation_time);
+    let requests = [KeychainKind::External, KeychainKind::Internal]
+        .into_iter()
+        .map(|keychain| {
+            let descriptor = wallet.public_descriptor(keychain);
+            let mut request = json!({
+                "desc": descriptor.to_string(),
+                "timestamp": timestamp,
+                "internal": keychain == KeychainKind::Internal,
+            });
+            // Core rejects ranges for descriptors without wildcard, and only ranged descriptors can be active.
+            if descriptor.has_wildcard() {
+                let next_index = wallet.next_derivation_index(keychain);
+                request["active"] = json!(true);
+                request["range"] = json!([0, next_index + CORE_KEYPOOL_SIZE - 1]);
+                request["next_index"] = json!(next_index);
+            }
+            request
+        })
+        .collect();
+    Value::Array(requests)
+}
+
+/// A watch-only Electrum wallet file with the extended public keys of a wallet, also imported by Sparrow.
+///
+/// Electrum encodes the script type in the SLIP-132 version of the keys, so only single key P2PKH, P2WPKH
+/// and P2SH-P2WPKH wallets and `sortedmulti` wallets are supported, deriving their addresses with `<0;1>/*`.
+pub fn export_electrum(wallet: &BdkWallet) -> Result<Value, BdkError> {
+    let descriptors = DescriptorPair::new(
+        wallet.public_descriptor(KeychainKind::External).to_string(),
+        wallet.public_descriptor(KeychainKind::Internal).to_string(),
+    );
+    let descriptor = descriptors.to_public_multipath()?;
+    let (version, threshold, keys) = match &descriptor {
+        Descriptor::Pkh(pkh) => (Slip132Version::Xpub, None, vec![pkh.as_inner()]),
+        Descriptor::Wpkh(wpkh) => (Slip132Version::Zpub, None, vec![wpkh.as_inner()]),
+        Descriptor::Sh(sh) => match sh.as_inner() {
+            ShInner::Wpkh(wpkh) => (Slip132Version::Ypub, None, vec![wpkh.as_inner()]),
+            ShInner::SortedMulti(multi) => (Slip132Version::Xpub, Some(multi.k()), multi.pks().iter().collect()),
+            ShInner::Wsh(wsh) => match wsh.as_inner() {
+                WshInner::SortedMulti(multi) => (
+                    Slip132Version::MultisigYpub,
+                    Some(multi.k()),
+                    multi.pks().iter().collect(),
+                ),
+                WshInner::Ms(_) => return Err(unsupported_by_electrum()),
+            },
+            ShInner::Ms(_) => return Err(unsupported_by_electrum()),
+        },
+        Descriptor::Wsh(wsh) => match wsh.as_inner() {
+            WshInner::SortedMulti(multi) => (
+                Slip132Version::MultisigZpub,
+                Some(multi.k()),
+                multi.pks().iter().collect(),
+            ),
+            WshInner::Ms(_) => return Err(unsupported_by_electrum()),
+        },
+        _ => return Err(unsupported_by_electrum()),
+    };
+
+    let mut file = json!({
+        "use_encryption": false,
+        "seed_version": ELECTRUM_SEED_VERSION,
+    });
+    match threshold {
+        None => {
+            file["wallet_type"] = json!("standard");
+            file["keystore"] = electrum_keystore(keys[0], version)?;
+        }
+        Some(threshold) => {
+            file["wallet_type"] = json!(format!("{threshold}of{}", keys.len()));
+            for (index, key) in keys.into_iter().enumerate() {
+                file[format!("x{}/", index + 1)] = electrum_keystore(key, version)?;
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// The generic JSON export of Coldcard and Passport signers.
+#[derive(Deserialize)]
+struct GenericExport {
+    chain: String,
+    xfp: String,
+    bip44: Option<GenericAccount>,
+    bip49: Option<GenericAccount>,
+    bip84: Option<GenericAccount>,
+    bip86: Option<GenericAccount>,
+}
+
+#[derive(Deserialize)]
+struct GenericAccount {
+    deriv: String,
+    xpub: String,
+}
+
+/// Build the single key descriptors of the accounts in the generic JSON export of a Coldcard or Passport.
+///
+/// The accounts are returned in the order P2PKH, P2SH-P2WPKH, P2WPKH and P2TR, skipping the ones missing
+/// from the export. The multisig keys of the export are ignored.
+pub fn generic_json_to_descriptors(json: &str, network: Network) -> Result<Vec<DescriptorPair>, BdkError> {
+    let export: GenericExport = serde_json::from_str(json)?;
+    let chain = match export.chain.as_str() {
+        "BTC" => NetworkKind::Main,
+        "XTN" | "XRT" => NetworkKind::Test,
+        chain => {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                format!("unknown chain {chain}"),
+            ))
+        }
+    };
+    if chain != NetworkKind::from(network) {
+        return Err(BdkError::new(
+            BdkErrorCode::NetworkMismatch,
+            format!("the export is for the chain {}", export.chain),
+        ));
+    }
+    let fingerprint = Fingerprint::from_str(&export.xfp).map_err(|e| BdkError::new(BdkErrorCode::InvalidKey, e))?;
+
+    let accounts = [
+        (export.bip44, AddressType::P2pkh),
+        (export.bip49, AddressType::P2sh),
+        (export.bip84, AddressType::P2wpkh),
+        (export.bip86, AddressType::P2tr),
+    ];
+    let descriptors = accounts
+        .into_iter()
+        .filter_map(|(account, address_type)| account.map(|account| (account, address_type)))
+        .map(|(account, address_type)| generic_account_descriptor(&account, address_type, fingerprint, chain))
+        .collect::<Result<Vec<_>, _>>()?;
+    if descriptors.is_empty() {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidDescriptor,
+            "the export has no single key account",
+        ));
+    }
+    Ok(descriptors)
+}
+
+fn generic_account_descriptor(
+    account: &GenericAccount,
+    address_type: AddressType,
+    fingerprint: Fingerprint,
+    network: NetworkKind,
+) -> Result<DescriptorPair, BdkError> {
+    // Keys are usually in the standard encoding, with their SLIP-132 encoding aside.
+    let key = Slip132Key::decode(&account.xpub)?;
+    if key.private || key.network != network {
+        return Err(BdkError::new(
+            BdkErrorCode::NetworkMismatch,
+            format!(
+                "the key of the account {} is not a public key of the network",
+                account.deriv
+            ),
+        ));
+    }
+    let xpub = key.to_xpub()?;
+    let path = DerivationPath::from_str(&account.deriv)?;
+    let key = format!("[{fingerprint}{}]{xpub}/<0;1>/*", path_suffix(&path));
+    let descriptor = match address_type {
+        AddressType::P2pkh => format!("pkh({key})"),
+        AddressType::P2sh => format!("sh(wpkh({key}))"),
+        AddressType::P2wpkh => format!("wpkh({key})"),
+        _ => format!("tr({key})"),
+    };
+    DescriptorPair::from_multipath(&descriptor)
+}
+
+/// The earliest of the birthday block and the blocks confirming a transaction of the wallet.
+fn birth(wallet: &BdkWallet, birthday: Option<ConfirmationBlockTime>) -> Option<ConfirmationBlockTime> {
+    wallet
+        .transactions()
+        .filter_map(|tx| match tx.chain_position {
+            ChainPosition::Confirmed { anchor, .. } => Some(anchor),
+            ChainPosition::Unconfirmed { .. } => None,
+        })
+        .chain(birthday)
+        .min_by_key(|anchor| anchor.block_id.height)
+}
+
+fn without_checksum(wallet: &BdkWallet, keychain: KeychainKind) -> String {
+    let descriptor = wallet.public_descriptor(keychain).to_string();
+    match descriptor.split_once('#') {
+        Some((descriptor, _)) => descriptor.to_string(),
+        None => descriptor,
+    }
+}
+
+fn electrum_keystore(key: &DescriptorPublicKey, version: Slip132Version) -> Result<Value, BdkError> {
+    let DescriptorPublicKey::MultiXPub(DescriptorMultiXKey { origin, xkey, .. }) = key else {
+        return Err(unsupported_by_electrum());
+    };
+    let (fingerprint, path) = match origin {
+        Some((fingerprint, path)) => (*fingerprint, path.clone()),
+        None if xkey.depth == 0 => (xkey.fingerprint(), DerivationPath::master()),
+        None => {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidDescriptor,
+                "Electrum needs the origin of the extended keys",
+            ))
+        }
+    };
+
+    Ok(json!({
+        "type": "bip32",
+        "xpub": encode_slip132(xkey, version)?,
+        "xprv": null,
+        "derivation": format!("m{}", path_suffix(&path)),
+        "root_fingerprint": fingerprint.to_string(),
+        "pw_hash_version": 1,
+    }))
+}
+
+fn encode_slip132(xpub: &Xpub, version: Slip132Version) -> Result<String, BdkError> {
+    Slip132Key::decode(&xpub.to_string())?.encode(version)
+}
+
+/// The derivation path without the `m` prefix, each step starting with `/`.
+fn path_suffix(path: &DerivationPath) -> String {
+    path.into_iter().map(|child| format!("/{child}")).collect()
+}
+
+fn unsupported_by_electrum() -> BdkError {
+    BdkError::new(
+        BdkErrorCode::UnsupportedAddressType,
+        "Electrum only supports single key P2PKH, P2WPKH and P2SH-P2WPKH wallets and sortedmulti wallets",
+    )
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use bdk_wallet::serde_json::{self, json, Value};
+use bitcoin::{address::NetworkUnchecked, bip32::Xpub, Address, Network, OutPoint, PublicKey, Txid};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BdkError, BdkErrorCode};
+
+/// The type of the object a BIP329 record labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelKind {
+    Tx,
+    Addr,
+    Pubkey,
+    Input,
+    Output,
+    Xpub,
+}
+
+/// A BIP329 label record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelRecord {
+    #[serde(rename = "type")]
+    pub kind: LabelKind,
+    #[serde(rename = "ref")]
+    pub reference: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The descriptor of the wallet the object belongs to, without its keys after the origin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// Whether an output can be spent, only for outputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spendable: Option<bool>,
+}
+
+impl LabelRecord {
+    /// Whether the record holds no data, removing the record it replaces.
+    fn is_removal(&self) -> bool {
+        self.label.is_none() && self.spendable.is_none()
+    }
+}
+
+/// Label records by type and reference.
+///
+/// Used both as the labels of a wallet and as the changes to them, where a record without label and
+/// spendable flag removes the record.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Labels(BTreeMap<(LabelKind, String), LabelRecord>);
+
+impl Labels {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, kind: LabelKind, reference: &str) -> Option<&LabelRecord> {
+        self.0.get(&(kind, reference.to_string()))
+    }
+
+    /// Insert a record, replacing the record of the same object.
+    pub fn insert(&mut self, record: LabelRecord) {
+        self.0.insert((record.kind, record.reference.clone()), record);
+    }
+
+    /// Replace the records of `other`, keeping the removals.
+    pub fn merge(&mut self, other: Labels) {
+        self.0.extend(other.0);
+    }
+
+    /// Apply the changes of `changes`, dropping the removed records.
+    pub fn apply(&mut self, changes: Labels) {
+        for (key, record) in changes.0 {
+            if record.is_removal() {
+                self.0.remove(&key);
+            } else {
+                self.0.insert(key, record);
+            }
+        }
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &LabelRecord> {
+        self.0.values()
+    }
+
+    pub fn from_records(records: impl IntoIterator<Item = LabelRecord>) -> Self {
+        let mut labels = Labels::default();
+        for record in records {
+            labels.insert(record);
+        }
+        labels
+    }
+
+    /// Parse BIP329 JSON lines, normalizing the references.
+    ///
+    /// Empty lines and records of unknown types are skipped, so exports of newer wallets can be imported.
+    pub fn from_jsonl(jsonl: &str, network: Network) -> Result<Self, BdkError> {
+        let mut labels = Labels::default();
+        for (index, line) in jsonl.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |e: &dyn std::fmt::Display| {
+                BdkError::new(BdkErrorCode::InvalidLabel, format!("line {}: {e}", index + 1))
+                    .with_data(json!({ "line": index + 1 }))
+            };
+
+            let value: Value = serde_json::from_str(line).map_err(|e| invalid(&e))?;
+            let Some(kind) = value.get("type") else {
+                return Err(invalid(&"the record has no type"));
+            };
+            if kind.is_string() && LabelKind::deserialize(kind).is_err() {
+                continue;
+            }
+            let mut record: LabelRecord = serde_json::from_value(value).map_err(|e| invalid(&e))?;
+            record.reference =
+                normalize_label_reference(record.kind, &record.reference, network).map_err(|e| invalid(&e))?;
+            if record.kind != LabelKind::Output {
+                record.spendable = None;
+            }
+            labels.insert(record);
+        }
+        Ok(labels)
+    }
+
+    pub fn to_jsonl(&self) -> Result<String, BdkError> {
+        let mut jsonl = String::new();
+        for record in self.records() {
+            jsonl.push_str(&serde_json::to_string(record)?);
+            jsonl.push('\n');
+        }
+        Ok(jsonl)
+    }
+}
+
+impl Serialize for Labels {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.records())
+    }
+}
+
+impl<'de> Deserialize<'de> for Labels {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let records = Vec::<LabelRecord>::deserialize(deserializer)?;
+        Ok(Labels::from_records(records))
+    }
+}
+
+/// Check the reference to an object of type `kind`, returning it in its canonical form.
+pub fn normalize_label_reference(kind: LabelKind, reference: &str, network: Network) -> Result<String, BdkError> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        BdkError::new(
+            BdkErrorCode::InvalidLabel,
+            format!("invalid reference {reference}: {e}"),
+        )
+    };
+    let reference = match kind {
+        LabelKind::Tx => Txid::from_str(reference).map_err(|e| invalid(&e))?.to_string(),
+        LabelKind::Addr => Address::<NetworkUnchecked>::from_str(reference)
+            .and_then(|address| address.require_network(network))
+            .map_err(|e| invalid(&e))?
+            .to_string(),
+        LabelKind::Pubkey => PublicKey::from_str(reference).map_err(|e| invalid(&e))?.to_string(),
+        LabelKind::Input | LabelKind::Output => OutPoint::from_str(reference).map_err(|e| invalid(&e))?.to_string(),
+        LabelKind::Xpub => Xpub::from_str(reference).map_err(|e| invalid(&e))?.to_string(),
+    };
+    Ok(reference)
+}
@@ -0,0 +1,325 @@
+use bdk_wallet::{
+    bitcoin::{
+        absolute::LockTime,
+        base64::{prelude::BASE64_STANDARD, Engine},
+        bip32::ChildNumber,
+        consensus::{deserialize, serialize},
+        ecdsa,
+        hashes::{sha256, Hash, HashEngine},
+        key::Secp256k1,
+        opcodes::{
+            all::{OP_PUSHBYTES_0, OP_RETURN},
+            OP_0,
+        },
+        script::{Builder, Instruction},
+        secp256k1::{Message, Verification},
+        sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType},
+        sign_message::{signed_msg_hash, MessageSignature},
+        taproot, transaction, Address, Amount, OutPoint, PrivateKey, Psbt, PublicKey, Script, ScriptBuf, Sequence,
+        Transaction, TxIn, TxOut, Txid, Witness, XOnlyPublicKey,
+    },
+    miniscript::{
+        descriptor::{DescriptorSecretKey, Wildcard},
+        psbt::PsbtExt,
+    },
+    KeychainKind, Wallet as BdkWallet,
+};
+
+use crate::types::{BdkError, BdkErrorCode};
+
+/// Tag of the BIP322 message hash.
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+
+/// The BIP322 tagged hash of a message.
+fn message_hash(message: &str) -> sha256::Hash {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_ref());
+    engine.input(tag.as_ref());
+    engine.input(message.as_bytes());
+    sha256::Hash::from_engine(engine)
+}
+
+/// The virtual transaction committing to the message, whose single output is spent by the signature.
+fn to_spend(script_pubkey: &Script, message: &str) -> Transaction {
+    let script_sig = Builder::new()
+        .push_opcode(OP_0)
+        .push_slice(message_hash(message).to_byte_array())
+        .into_script();
+    Transaction {
+        version: transaction::Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0xFFFFFFFF),
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: script_pubkey.into(),
+        }],
+    }
+}
+
+/// The virtual transaction spending `to_spend`, whose input holds the signature.
+fn to_sign(to_spend: &Transaction) -> Transaction {
+    Transaction {
+        version: transaction::Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend.compute_txid(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
+/// The format of a signed message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSignatureFormat {
+    /// BIP322 simple signature, the witness of the `to_sign` transaction.
+    Simple,
+    /// BIP322 full signature, the whole `to_sign` transaction.
+    Full,
+    /// BIP137 signature, only for P2PKH addresses.
+    Legacy,
+}
+
+/// Sign a message with the key of one of the wallet's addresses.
+///
+/// Only P2WPKH, P2TR and P2PKH addresses are supported, like [`verify_message`], and the signature is verified
+/// before it is returned, so that the library never produces a signature it cannot verify.
+pub fn sign_message(
+    wallet: &BdkWallet,
+    address: &Address,
+    message: &str,
+    format: MessageSignatureFormat,
+) -> Result<String, BdkError> {
+    let script_pubkey = address.script_pubkey();
+    let (keychain, index) = wallet.derivation_of_spk(script_pubkey.clone()).ok_or_else(|| {
+        BdkError::new(
+            BdkErrorCode::InvalidAddress,
+            format!("the address {address} does not belong to the wallet"),
+        )
+    })?;
+    if !(script_pubkey.is_p2wpkh() || script_pubkey.is_p2tr() || script_pubkey.is_p2pkh()) {
+        return Err(unsupported_address_type());
+    }
+
+    if format == MessageSignatureFormat::Legacy {
+        if !script_pubkey.is_p2pkh() {
+            return Err(BdkError::new(
+                BdkErrorCode::UnsupportedAddressType,
+                "legacy signatures are only supported for P2PKH addresses",
+            ));
+        }
+        let key = private_key(wallet, keychain, index, &script_pubkey)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::Sign, "the wallet has no private key for the address"))?;
+        let secp = Secp256k1::new();
+        let message = Message::from_digest(signed_msg_hash(message).to_byte_array());
+        let signature = secp.sign_ecdsa_recoverable(&message, &key.inner);
+        return Ok(MessageSignature::new(signature, key.compressed).to_base64());
+    }
+
+    let to_spend = to_spend(&script_pubkey, message);
+    let prevout = to_spend.output[0].clone();
+    let mut psbt =
+        Psbt::from_unsigned_tx(to_sign(&to_spend)).map_err(|e| BdkError::new(BdkErrorCode::Unexpected, e))?;
+    psbt.inputs[0].witness_utxo = Some(prevout.clone());
+    psbt.inputs[0].non_witness_utxo = Some(to_spend);
+    let descriptor = wallet
+        .public_descriptor(keychain)
+        .at_derivation_index(index)
+        .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?;
+    psbt.update_input_with_descriptor(0, &descriptor)
+        .map_err(|e| BdkError::new(BdkErrorCode::InvalidPsbt, e))?;
+
+    if !wallet.sign(&mut psbt, Default::default())? {
+        return Err(BdkError::new(
+            BdkErrorCode::Sign,
+            "the wallet cannot sign for the address",
+        ));
+    }
+    let to_sign = psbt.extract_tx_unchecked_fee_rate();
+    // Spends that `verify_input` does not support, like taproot script paths, are not returned.
+    if !verify_input(&Secp256k1::verification_only(), &to_sign, &prevout)? {
+        return Err(BdkError::new(
+            BdkErrorCode::UnsupportedAddressType,
+            "message signatures are only supported for single key spends",
+        ));
+    }
+
+    let signature = match format {
+        MessageSignatureFormat::Simple if !to_sign.input[0].script_sig.is_empty() => {
+            return Err(BdkError::new(
+                BdkErrorCode::UnsupportedAddressType,
+                "simple signatures are only supported for segwit addresses, use the full format",
+            ))
+        }
+        MessageSignatureFormat::Simple => serialize(&to_sign.input[0].witness),
+        _ => serialize(&to_sign),
+    };
+    Ok(BASE64_STANDARD.encode(signature))
+}
+
+/// The private key of the wallet for the P2PKH script at `index` of `keychain`.
+fn private_key(wallet: &BdkWallet, keychain: KeychainKind, index: u32, script_pubkey: &Script) -> Option<PrivateKey> {
+    let secp = Secp256k1::new();
+    wallet
+        .get_signers(keychain)
+        .signers()
+        .iter()
+        .filter_map(|signer| {
+            let key = match signer.descriptor_secret_key()? {
+                DescriptorSecretKey::Single(single) => single.key,
+                DescriptorSecretKey::XPrv(xkey) => {
+                    let path = match xkey.wildcard {
+                        Wildcard::None => xkey.derivation_path.clone(),
+                        Wildcard::Unhardened => xkey.derivation_path.child(ChildNumber::from_normal_idx(index).ok()?),
+                        Wildcard::Hardened => xkey.derivation_path.child(ChildNumber::from_hardened_idx(index).ok()?),
+                    };
+                    xkey.xkey.derive_priv(&secp, &path).ok()?.to_priv()
+                }
+                DescriptorSecretKey::MultiXPrv(_) => return None,
+            };
+            Some(key)
+        })
+        .find(|key| ScriptBuf::new_p2pkh(&key.public_key(&secp).pubkey_hash()) == *script_pubkey)
+}
+
+/// Verify a BIP322 simple or full signature, or a BIP137 signature for P2PKH addresses.
+///
+/// Returns `false` if the signature is well formed but invalid for this address and message.
+pub fn verify_message(address: &Address, message: &str, signature: &str) -> Result<bool, BdkError> {
+    let bytes = BASE64_STANDARD
+        .decode(signature)
+        .map_err(|e| BdkError::new(BdkErrorCode::InvalidValue, e))?;
+    let script_pubkey = address.script_pubkey();
+    let secp = Secp256k1::verification_only();
+
+    if script_pubkey.is_p2pkh() && bytes.len() == 65 {
+        let Ok(signature) = MessageSignature::from_slice(&bytes) else {
+            return Ok(false);
+        };
+        return Ok(signature
+            .is_signed_by_address(&secp, address, signed_msg_hash(message))
+            .unwrap_or(false));
+    }
+
+    let to_spend = to_spend(&script_pubkey, message);
+    let mut to_sign = to_sign(&to_spend);
+    if let Ok(witness) = deserialize::<Witness>(&bytes) {
+        to_sign.input[0].witness = witness;
+    } else if let Ok(tx) = deserialize::<Transaction>(&bytes) {
+        // The signer may choose the version, lock time and sequence, the rest is fixed.
+        if tx.input.len() != 1
+            || tx.input[0].previous_output != to_sign.input[0].previous_output
+            || tx.output != to_sign.output
+        {
+            return Ok(false);
+        }
+        to_sign = tx;
+    } else {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidValue,
+            "the signature is not a BIP322 or BIP137 signature",
+        ));
+    }
+
+    verify_input(&secp, &to_sign, &to_spend.output[0])
+}
+
+/// Verify the signature of the single input of `tx`, spending `prevout`.
+fn verify_input<C: Verification>(secp: &Secp256k1<C>, tx: &Transaction, prevout: &TxOut) -> Result<bool, BdkError> {
+    let script_pubkey = &prevout.script_pubkey;
+    let input = &tx.input[0];
+    let mut cache = SighashCache::new(tx);
+
+    if script_pubkey.is_p2wpkh() {
+        let (Some(signature), Some(pubkey), 2) = (input.witness.nth(0), input.witness.nth(1), input.witness.len())
+        else {
+            return Ok(false);
+        };
+        let (Ok(signature), Ok(pubkey)) = (ecdsa::Signature::from_slice(signature), PublicKey::from_slice(pubkey))
+        else {
+            return Ok(false);
+        };
+        if !input.script_sig.is_empty()
+            || signature.sighash_type != EcdsaSighashType::All
+            || pubkey.wpubkey_hash().map(|hash| ScriptBuf::new_p2wpkh(&hash)).as_ref() != Ok(script_pubkey)
+        {
+            return Ok(false);
+        }
+        let sighash = cache
+            .p2wpkh_signature_hash(0, script_pubkey, prevout.value, signature.sighash_type)
+            .map_err(|e| BdkError::new(BdkErrorCode::Unexpected, e))?;
+        Ok(secp
+            .verify_ecdsa(&Message::from(sighash), &signature.signature, &pubkey.inner)
+            .is_ok())
+    } else if script_pubkey.is_p2tr() {
+        let (Some(signature), 1) = (input.witness.nth(0), input.witness.len()) else {
+            return Ok(false);
+        };
+        let (Ok(signature), Ok(output_key)) = (
+            taproot::Signature::from_slice(signature),
+            XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..]),
+        ) else {
+            return Ok(false);
+        };
+        if !input.script_sig.is_empty()
+            || !matches!(signature.sighash_type, TapSighashType::Default | TapSighashType::All)
+        {
+            return Ok(false);
+        }
+        let sighash = cache
+            .taproot_key_spend_signature_hash(0, &Prevouts::All(&[prevout]), signature.sighash_type)
+            .map_err(|e| BdkError::new(BdkErrorCode::Unexpected, e))?;
+        Ok(secp
+            .verify_schnorr(&signature.signature, &Message::from(sighash), &output_key)
+            .is_ok())
+    } else if script_pubkey.is_p2pkh() {
+        let pushes = input
+            .script_sig
+            .instructions()
+            .map(|instruction| match instruction {
+                Ok(Instruction::PushBytes(bytes)) => Some(bytes.as_bytes().to_vec()),
+                Ok(Instruction::Op(op)) if op == OP_PUSHBYTES_0 => Some(vec![]),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some([signature, pubkey]) = pushes.as_deref() else {
+            return Ok(false);
+        };
+        let (Ok(signature), Ok(pubkey)) = (ecdsa::Signature::from_slice(signature), PublicKey::from_slice(pubkey))
+        else {
+            return Ok(false);
+        };
+        if !input.witness.is_empty()
+            || signature.sighash_type != EcdsaSighashType::All
+            || ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()) != *script_pubkey
+        {
+            return Ok(false);
+        }
+        let sighash = cache
+            .legacy_signature_hash(0, script_pubkey, signature.sighash_type.to_u32())
+            .map_err(|e| BdkError::new(BdkErrorCode::Unexpected, e))?;
+        Ok(secp
+            .verify_ecdsa(&Message::from(sighash), &signature.signature, &pubkey.inner)
+            .is_ok())
+    } else {
+        Err(unsupported_address_type())
+    }
+}
+
+fn unsupported_address_type() -> BdkError {
+    BdkError::new(
+        BdkErrorCode::UnsupportedAddressType,
+        "message signatures are only supported for P2WPKH, P2TR and P2PKH addresses",
+    )
+}
@@ -1,7 +1,25 @@
+mod air_gap;
+mod bbqr;
+mod bsms;
 mod descriptor;
+mod export;
+mod labels;
+mod message;
+mod slip132;
+mod tx_builder;
+mod ur;
 mod wallet;
 
+pub use air_gap::*;
+pub use bbqr::{BbqrDecoder, BbqrEncoder};
+pub use bsms::*;
 pub use descriptor::*;
+pub use export::*;
+pub use labels::*;
+pub use message::*;
+pub use slip132::*;
+pub use tx_builder::*;
+pub use ur::{UrDecoder, UrEncoder};
 pub use wallet::*;
 
 #[cfg(feature = "esplora")]
@@ -9,3 +27,6 @@ mod esplora_client;
 
 #[cfg(feature = "esplora")]
 pub use esplora_client::EsploraClient;
+
+#[cfg(feature = "esplora")]
+mod sweep;
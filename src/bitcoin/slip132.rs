@@ -0,0 +1,192 @@
+use bitcoin::{
+    base58,
+    bip32::{Xpriv, Xpub},
+    AddressType, NetworkKind,
+};
+
+use crate::types::{BdkError, BdkErrorCode, Slip132Version};
+
+/// Version bytes of SLIP-132 encodings, in the order: mainnet public, mainnet private, testnet public,
+/// testnet private.
+const VERSIONS: [(Slip132Version, [[u8; 4]; 4]); 5] = [
+    (
+        Slip132Version::Xpub,
+        [
+            [0x04, 0x88, 0xb2, 0x1e],
+            [0x04, 0x88, 0xad, 0xe4],
+            [0x04, 0x35, 0x87, 0xcf],
+            [0x04, 0x35, 0x83, 0x94],
+        ],
+    ),
+    (
+        Slip132Version::Ypub,
+        [
+            [0x04, 0x9d, 0x7c, 0xb2],
+            [0x04, 0x9d, 0x78, 0x78],
+            [0x04, 0x4a, 0x52, 0x62],
+            [0x04, 0x4a, 0x4e, 0x28],
+        ],
+    ),
+    (
+        Slip132Version::Zpub,
+        [
+            [0x04, 0xb2, 0x47, 0x46],
+            [0x04, 0xb2, 0x43, 0x0c],
+            [0x04, 0x5f, 0x1c, 0xf6],
+            [0x04, 0x5f, 0x18, 0xbc],
+        ],
+    ),
+    (
+        Slip132Version::MultisigYpub,
+        [
+            [0x02, 0x95, 0xb4, 0x3f],
+            [0x02, 0x95, 0xb0, 0x05],
+            [0x02, 0x42, 0x89, 0xef],
+            [0x02, 0x42, 0x85, 0xb5],
+        ],
+    ),
+    (
+        Slip132Version::MultisigZpub,
+        [
+            [0x02, 0xaa, 0x7e, 0xd3],
+            [0x02, 0xaa, 0x7a, 0x99],
+            [0x02, 0x57, 0x54, 0x83],
+            [0x02, 0x57, 0x50, 0x48],
+        ],
+    ),
+];
+
+/// An extended key decoded from any SLIP-132 encoding.
+#[derive(Debug)]
+pub struct Slip132Key {
+    /// The encoding the key was decoded from.
+    pub version: Slip132Version,
+    /// The network the version bytes are meant for.
+    pub network: NetworkKind,
+    /// Whether the key is private.
+    pub private: bool,
+    /// The key serialized with the version bytes removed.
+    data: [u8; 74],
+}
+
+impl Slip132Key {
+    pub fn decode(key: &str) -> Result<Self, BdkError> {
+        let bytes = base58::decode_check(key).map_err(|e| BdkError::new(BdkErrorCode::InvalidKey, e))?;
+        if bytes.len() != 78 {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidKey,
+                format!("Invalid extended key length: {}", bytes.len()),
+            ));
+        }
+
+        let (version, network, private) = VERSIONS
+            .iter()
+            .find_map(|(version, prefixes)| {
+                prefixes
+                    .iter()
+                    .position(|prefix| prefix[..] == bytes[..4])
+                    .map(|i| (*version, network_of(i), i % 2 == 1))
+            })
+            .ok_or_else(|| {
+                BdkError::new(
+                    BdkErrorCode::InvalidKey,
+                    format!("Unknown extended key version {:02x?}", &bytes[..4]),
+                )
+            })?;
+
+        let mut data = [0u8; 74];
+        data.copy_from_slice(&bytes[4..]);
+
+        Ok(Slip132Key {
+            version,
+            network,
+            private,
+            data,
+        })
+    }
+
+    /// Encode the key with the version bytes of the given SLIP-132 encoding.
+    pub fn encode(&self, version: Slip132Version) -> Result<String, BdkError> {
+        let mut bytes = Vec::with_capacity(78);
+        bytes.extend_from_slice(&version_bytes(version, self.network, self.private)?);
+        bytes.extend_from_slice(&self.data);
+        Ok(base58::encode_check(&bytes))
+    }
+
+    /// The address type implied by the version bytes, if the encoding is not the standard BIP32 one.
+    pub fn address_type(&self) -> Option<AddressType> {
+        match self.version {
+            Slip132Version::Ypub | Slip132Version::MultisigYpub => Some(AddressType::P2sh),
+            Slip132Version::Zpub => Some(AddressType::P2wpkh),
+            Slip132Version::MultisigZpub => Some(AddressType::P2wsh),
+            _ => None,
+        }
+    }
+
+    /// Check that the key can be used to build a single-sig descriptor of the given address type.
+    pub fn check_address_type(&self, address_type: AddressType) -> Result<(), BdkError> {
+        if matches!(
+            self.version,
+            Slip132Version::MultisigYpub | Slip132Version::MultisigZpub
+        ) {
+            return Err(BdkError::new(
+                BdkErrorCode::UnsupportedAddressType,
+                "Multisig extended keys cannot be used in a single-sig descriptor",
+            ));
+        }
+        match self.address_type() {
+            Some(implied) if implied != address_type => Err(BdkError::new(
+                BdkErrorCode::UnsupportedAddressType,
+                format!("Extended key is encoded for {implied} addresses, not {address_type}"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn to_xpub(&self) -> Result<Xpub, BdkError> {
+        if self.private {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidKey,
+                "Expected an extended public key",
+            ));
+        }
+        Ok(Xpub::decode(&self.standard_bytes()?)?)
+    }
+
+    pub fn to_xpriv(&self) -> Result<Xpriv, BdkError> {
+        if !self.private {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidKey,
+                "Expected an extended private key",
+            ));
+        }
+        Ok(Xpriv::decode(&self.standard_bytes()?)?)
+    }
+
+    fn standard_bytes(&self) -> Result<Vec<u8>, BdkError> {
+        let mut bytes = Vec::with_capacity(78);
+        bytes.extend_from_slice(&version_bytes(Slip132Version::Xpub, self.network, self.private)?);
+        bytes.extend_from_slice(&self.data);
+        Ok(bytes)
+    }
+}
+
+fn network_of(index: usize) -> NetworkKind {
+    if index < 2 {
+        NetworkKind::Main
+    } else {
+        NetworkKind::Test
+    }
+}
+
+fn version_bytes(version: Slip132Version, network: NetworkKind, private: bool) -> Result<[u8; 4], BdkError> {
+    let (_, prefixes) = VERSIONS
+        .iter()
+        .find(|(v, _)| *v == version)
+        .ok_or_else(|| BdkError::new(BdkErrorCode::InvalidValue, "Unsupported SLIP-132 version"))?;
+    let index = match network {
+        NetworkKind::Main => 0,
+        NetworkKind::Test => 2,
+    };
+    Ok(prefixes[index + private as usize])
+}
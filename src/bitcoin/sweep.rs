@@ -0,0 +1,115 @@
+use bdk_wallet::{
+    bitcoin::{psbt::Input, Network, NetworkKind, OutPoint, PrivateKey, Psbt, Weight},
+    KeychainKind, SignOptions, Wallet as BdkWallet,
+};
+
+use crate::{
+    result::JsResult,
+    types::{BdkError, BdkErrorCode},
+};
+
+use super::EsploraClient;
+
+/// Number of consecutive unused scripts after which scanning stops, for descriptors with a wildcard.
+const SWEEP_STOP_GAP: usize = 20;
+const SWEEP_PARALLEL_REQUESTS: usize = 4;
+
+/// A UTXO of the keys to sweep, with what is needed to add it as a foreign UTXO.
+pub(crate) struct SweepUtxo {
+    pub outpoint: OutPoint,
+    pub psbt_input: Input,
+    pub satisfaction_weight: Weight,
+}
+
+/// In-memory wallets holding the keys to sweep.
+///
+/// A WIF key gets one wallet per script type its funds may have been received to. The wallets are never
+/// persisted, the keys are dropped with them.
+pub(crate) struct SweepWallets {
+    wallets: Vec<BdkWallet>,
+}
+
+impl SweepWallets {
+    pub(crate) fn new(wif_or_descriptor: &str, network: Network) -> JsResult<SweepWallets> {
+        let descriptors = match PrivateKey::from_wif(wif_or_descriptor) {
+            Ok(key) => {
+                if key.network != NetworkKind::from(network) {
+                    return Err(BdkError::new(
+                        BdkErrorCode::NetworkMismatch,
+                        format!("the private key is not valid for {network}"),
+                    ));
+                }
+                // Taproot first: the ECDSA signers of the other wallets cannot sign its inputs, they skip them
+                // once finalized.
+                if key.compressed {
+                    vec![
+                        format!("tr({wif_or_descriptor})"),
+                        format!("wpkh({wif_or_descriptor})"),
+                        format!("sh(wpkh({wif_or_descriptor}))"),
+                        format!("pkh({wif_or_descriptor})"),
+                    ]
+                } else {
+                    vec![format!("pkh({wif_or_descriptor})")]
+                }
+            }
+            Err(_) => vec![wif_or_descriptor.to_string()],
+        };
+
+        let wallets = descriptors
+            .into_iter()
+            .map(|descriptor| {
+                BdkWallet::create_single(descriptor)
+                    .network(network)
+                    .create_wallet_no_persist()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if wallets
+            .iter()
+            .any(|wallet| wallet.get_signers(KeychainKind::External).signers().is_empty())
+        {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidKey,
+                "the descriptor to sweep has no private key",
+            ));
+        }
+
+        Ok(SweepWallets { wallets })
+    }
+
+    pub(crate) async fn scan(&mut self, client: &EsploraClient, seen_at: u64) -> JsResult<()> {
+        for wallet in &mut self.wallets {
+            client
+                .full_scan_wallet(wallet, SWEEP_STOP_GAP, SWEEP_PARALLEL_REQUESTS, seen_at)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn utxos(&self) -> JsResult<Vec<SweepUtxo>> {
+        let mut utxos = vec![];
+        for wallet in &self.wallets {
+            for utxo in wallet.list_unspent() {
+                let descriptor = wallet
+                    .public_descriptor(utxo.keychain)
+                    .at_derivation_index(utxo.derivation_index)
+                    .map_err(|e| BdkError::new(BdkErrorCode::InvalidDescriptor, e))?;
+                utxos.push(SweepUtxo {
+                    outpoint: utxo.outpoint,
+                    satisfaction_weight: descriptor.max_weight_to_satisfy()?,
+                    psbt_input: wallet.get_psbt_input(utxo, None, false)?,
+                });
+            }
+        }
+        Ok(utxos)
+    }
+
+    /// Sign and finalize the inputs spending the swept UTXOs, returns whether the PSBT is finalized.
+    pub(crate) fn sign(&self, psbt: &mut Psbt) -> JsResult<bool> {
+        let mut finalized = false;
+        for wallet in &self.wallets {
+            // Inputs already finalized are skipped, so the last wallet tells whether all of them are.
+            finalized = wallet.sign(psbt, SignOptions::default())?;
+        }
+        Ok(finalized)
+    }
+}
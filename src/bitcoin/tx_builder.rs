@@ -0,0 +1,511 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use bdk_wallet::{
+    bitcoin::{
+        absolute::LockTime, psbt::Input as BdkInput, script::PushBytesBuf, Amount as BdkAmount, FeeRate as BdkFeeRate,
+        OutPoint as BdkOutPoint, ScriptBuf, Sequence, Txid, Weight,
+    },
+    descriptor::policy::Condition,
+    serde_json::json,
+    KeychainKind, Wallet as BdkWallet,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    result::JsResult,
+    types::{Address, Amount, BdkError, BdkErrorCode, FeeRate, OutPoint, Psbt, PsbtInput},
+};
+
+/// Maximum size of the data of a standard OP_RETURN output, as relayed by Bitcoin Core's default policy.
+const MAX_STANDARD_DATA_SIZE: usize = 80;
+
+/// A transaction builder.
+///
+/// A `TxBuilder` is created by calling [`build_tx`] or [`build_fee_bump`] on a wallet. After
+/// assigning it, you set options on it until finally calling [`finish`] to consume the builder and
+/// generate the transaction.
+///
+/// Each option setting method on `TxBuilder` takes and returns the builder, so you can chain calls.
+///
+/// [`build_tx`]: crate::bitcoin::Wallet::build_tx
+/// [`build_fee_bump`]: crate::bitcoin::Wallet::build_fee_bump
+/// [`finish`]: TxBuilder::finish
+#[wasm_bindgen]
+pub struct TxBuilder {
+    wallet: Rc<RefCell<BdkWallet>>,
+    fee_bump: Option<Txid>,
+    recipients: Vec<Recipient>,
+    fee_rate: Option<BdkFeeRate>,
+    fee_absolute: Option<BdkAmount>,
+    drain_wallet: bool,
+    drain_to: Option<ScriptBuf>,
+    allow_dust: bool,
+    data: Vec<Vec<u8>>,
+    allow_nonstandard_data: bool,
+    nlocktime: Option<LockTime>,
+    current_height: Option<u32>,
+    anti_fee_sniping: bool,
+    exact_sequence: Option<Sequence>,
+    input_sequences: BTreeMap<BdkOutPoint, Sequence>,
+    utxos: Vec<BdkOutPoint>,
+    foreign_utxos: Vec<(BdkOutPoint, BdkInput, Weight)>,
+    manually_selected_only: bool,
+    only_witness_utxo: bool,
+}
+
+impl TxBuilder {
+    pub(crate) fn new(wallet: Rc<RefCell<BdkWallet>>, fee_bump: Option<Txid>) -> TxBuilder {
+        TxBuilder {
+            wallet,
+            fee_bump,
+            recipients: vec![],
+            fee_rate: None,
+            fee_absolute: None,
+            drain_wallet: false,
+            drain_to: None,
+            allow_dust: false,
+            data: vec![],
+            allow_nonstandard_data: false,
+            nlocktime: None,
+            current_height: None,
+            anti_fee_sniping: true,
+            exact_sequence: None,
+            input_sequences: BTreeMap::new(),
+            utxos: vec![],
+            foreign_utxos: vec![],
+            manually_selected_only: false,
+            only_witness_utxo: false,
+        }
+    }
+
+    fn check_current_height(&self, wallet: &BdkWallet) -> JsResult<()> {
+        let Some(height) = self.current_height else {
+            return Ok(());
+        };
+        if LockTime::from_height(height).is_err() {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidTimelock,
+                format!("current height {height} is not a valid block height"),
+            ));
+        }
+        let tip = wallet.latest_checkpoint().height();
+        if height < tip {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidTimelock,
+                format!("current height {height} is below the wallet's latest checkpoint at height {tip}"),
+            )
+            .with_data(json!({ "height": height, "tip": tip })));
+        }
+        Ok(())
+    }
+
+    /// Check the sequences of [`TxBuilder::input_sequence`] against the timelocks of the policy of the inputs.
+    ///
+    /// The sequences are set after BDK builds the transaction, which only checks the sequence of all the inputs.
+    /// Foreign inputs and outputs the wallet does not know are not checked.
+    fn check_input_sequences(&self, wallet: &BdkWallet) -> JsResult<()> {
+        for (outpoint, sequence) in &self.input_sequences {
+            let Some(utxo) = wallet.get_utxo(*outpoint) else {
+                continue;
+            };
+            let condition = policy_condition(wallet, utxo.keychain)?;
+            if let Some(csv) = condition.csv {
+                let satisfied = sequence.is_relative_lock_time()
+                    && sequence.is_time_locked() == csv.is_time_locked()
+                    && sequence.to_consensus_u32() & 0xffff >= csv.to_consensus_u32() & 0xffff;
+                if !satisfied {
+                    return Err(BdkError::new(
+                        BdkErrorCode::InvalidTimelock,
+                        format!("sequence {sequence} of {outpoint} does not satisfy the relative lock time {csv} of its policy"),
+                    ));
+                }
+            }
+            if condition.timelock.is_some() && *sequence == Sequence::MAX {
+                return Err(BdkError::new(
+                    BdkErrorCode::InvalidTimelock,
+                    format!("the final sequence of {outpoint} disables the lock time required by its policy"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The lock time required by the wallet's descriptors, used when anti-fee-sniping is disabled.
+    fn required_lock_time(wallet: &BdkWallet) -> JsResult<Option<LockTime>> {
+        let mut required: Option<LockTime> = None;
+        for keychain in [KeychainKind::External, KeychainKind::Internal] {
+            let Some(lock_time) = policy_condition(wallet, keychain)?.timelock else {
+                continue;
+            };
+            required = match required {
+                Some(other) if !other.is_same_unit(lock_time) => {
+                    return Err(BdkError::new(
+                        BdkErrorCode::InvalidTimelock,
+                        "the descriptors require lock times in both blocks and seconds",
+                    ))
+                }
+                Some(other) if other.to_consensus_u32() >= lock_time.to_consensus_u32() => Some(other),
+                _ => Some(lock_time),
+            };
+        }
+        Ok(required)
+    }
+
+    /// Whether one of the requested sequences encodes a relative lock time, which requires a version 2 transaction.
+    fn has_relative_lock_time(&self) -> bool {
+        self.exact_sequence
+            .iter()
+            .chain(self.input_sequences.values())
+            .any(|sequence| sequence.is_relative_lock_time())
+    }
+
+    fn apply_input_sequences(&self, psbt: &mut Psbt) -> JsResult<()> {
+        for (outpoint, sequence) in &self.input_sequences {
+            let input = psbt
+                .unsigned_tx
+                .input
+                .iter_mut()
+                .find(|input| input.previous_output == *outpoint)
+                .ok_or_else(|| {
+                    BdkError::new(
+                        BdkErrorCode::InvalidTimelock,
+                        format!("sequence set for {outpoint}, which is not an input of the transaction"),
+                    )
+                })?;
+            input.sequence = *sequence;
+        }
+
+        let tx = &psbt.unsigned_tx;
+        if tx.lock_time != LockTime::ZERO && !tx.is_lock_time_enabled() {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidTimelock,
+                format!(
+                    "lock time {} is disabled, all the inputs have a final sequence",
+                    tx.lock_time
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_data(&self) -> JsResult<Vec<PushBytesBuf>> {
+        if !self.allow_nonstandard_data {
+            if self.data.len() > 1 {
+                return Err(BdkError::new(
+                    BdkErrorCode::NonStandardData,
+                    format!("{} OP_RETURN outputs, only one is standard", self.data.len()),
+                ));
+            }
+            if let Some(data) = self.data.iter().find(|data| data.len() > MAX_STANDARD_DATA_SIZE) {
+                return Err(BdkError::new(
+                    BdkErrorCode::NonStandardData,
+                    format!(
+                        "OP_RETURN data of {} bytes exceeds the standard limit of {MAX_STANDARD_DATA_SIZE} bytes",
+                        data.len()
+                    ),
+                )
+                .with_data(json!({ "size": data.len(), "limit": MAX_STANDARD_DATA_SIZE })));
+            }
+        }
+
+        self.data
+            .iter()
+            .map(|data| {
+                PushBytesBuf::try_from(data.clone()).map_err(|e| BdkError::new(BdkErrorCode::NonStandardData, e))
+            })
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+impl TxBuilder {
+    /// Add a recipient to the internal list of recipients.
+    ///
+    /// When bumping a fee, the recipients of the original transaction are kept and this one is added.
+    pub fn add_recipient(mut self, recipient: Recipient) -> Self {
+        self.recipients.push(recipient);
+        self
+    }
+
+    /// Replace the recipients already added with a new list.
+    pub fn set_recipients(mut self, recipients: Vec<Recipient>) -> Self {
+        self.recipients = recipients;
+        self
+    }
+
+    /// Set a custom fee rate.
+    ///
+    /// This method sets the mining fee paid by the transaction as a rate on its size.
+    /// This means that the total fee paid is equal to `fee_rate` times the size
+    /// of the transaction. Default is 1 sat/vB in accordance with Bitcoin Core's default
+    /// relay policy.
+    ///
+    /// Note that this is really a minimum feerate -- it's possible to
+    /// overshoot it slightly since adding a change output to drain the remaining
+    /// excess might not be viable.
+    pub fn fee_rate(mut self, fee_rate: FeeRate) -> Self {
+        self.fee_absolute = None;
+        self.fee_rate = Some(fee_rate.into());
+        self
+    }
+
+    /// Set an absolute fee.
+    ///
+    /// The absolute fee and the fee rate are mutually exclusive, the last one set is used.
+    ///
+    /// Note that this is really a minimum absolute fee -- it's possible to
+    /// overshoot it slightly since adding a change output to drain the remaining
+    /// excess might not be viable.
+    pub fn fee_absolute(mut self, fee: Amount) -> Self {
+        self.fee_rate = None;
+        self.fee_absolute = Some(fee.into());
+        self
+    }
+
+    /// Spend all the available inputs. This respects the change policy.
+    pub fn drain_wallet(mut self) -> Self {
+        self.drain_wallet = true;
+        self
+    }
+
+    /// Sets the address to drain excess coins to.
+    ///
+    /// Unless explicitly specified, the change address is used. If `drain_wallet` is set and no
+    /// recipients are added, all the inputs go to this address.
+    pub fn drain_to(mut self, address: Address) -> Self {
+        self.drain_to = Some(address.script_pubkey().into());
+        self
+    }
+
+    /// Set whether or not the dust limit is checked.
+    ///
+    /// **Note**: by avoiding a dust limit check you may end up with a transaction that is non-standard.
+    pub fn allow_dust(mut self, allow_dust: bool) -> Self {
+        self.allow_dust = allow_dust;
+        self
+    }
+
+    /// Add data as an OP_RETURN output.
+    ///
+    /// Standard transactions carry at most one OP_RETURN output of up to 80 bytes of data, [`TxBuilder::finish`]
+    /// errors otherwise unless [`TxBuilder::allow_nonstandard_data`] is set.
+    pub fn add_data(mut self, data: Vec<u8>) -> Self {
+        self.data.push(data);
+        self
+    }
+
+    /// Set whether OP_RETURN outputs beyond the standardness limits are allowed.
+    ///
+    /// **Note**: non-standard transactions are not relayed by nodes running the default policy, they
+    /// have to be submitted to a miner directly.
+    pub fn allow_nonstandard_data(mut self, allow_nonstandard_data: bool) -> Self {
+        self.allow_nonstandard_data = allow_nonstandard_data;
+        self
+    }
+
+    /// Add a UTXO of the wallet to the transaction inputs, it is spent even if not needed.
+    ///
+    /// [`TxBuilder::finish`] errors if the UTXO is not in the wallet.
+    pub fn add_utxo(mut self, outpoint: OutPoint) -> Self {
+        self.utxos.push(outpoint.into());
+        self
+    }
+
+    /// Add UTXOs of the wallet to the transaction inputs, they are spent even if not needed.
+    pub fn add_utxos(mut self, outpoints: Vec<OutPoint>) -> Self {
+        self.utxos.extend(outpoints.into_iter().map(BdkOutPoint::from));
+        self
+    }
+
+    /// Only spend the UTXOs added with [`TxBuilder::add_utxo`], [`TxBuilder::add_utxos`] or
+    /// [`TxBuilder::add_foreign_utxo`], the other UTXOs of the wallet are not selected.
+    pub fn manually_selected_only(mut self) -> Self {
+        self.manually_selected_only = true;
+        self
+    }
+
+    /// Only spend the given UTXOs of the wallet, a shorthand for [`TxBuilder::add_utxos`] and
+    /// [`TxBuilder::manually_selected_only`].
+    pub fn only_spend_from(self, outpoints: Vec<OutPoint>) -> Self {
+        self.add_utxos(outpoints).manually_selected_only()
+    }
+
+    /// Add a UTXO that is not owned by the wallet, like the input of a counterparty in a payjoin.
+    ///
+    /// `psbt_input` must contain the spent output, its other metadata is carried to the input of the resulting
+    /// PSBT so that its owner can sign it. `satisfaction_weight` is the weight, in weight units, of the
+    /// scriptSig and witness that will spend the output, used to compute the fee.
+    ///
+    /// Unlike the UTXOs of the wallet, the value of a foreign UTXO given only with a `witness_utxo` cannot be
+    /// verified. Non-taproot inputs also need their `non_witness_utxo` unless [`TxBuilder::only_witness_utxo`]
+    /// is set, [`TxBuilder::finish`] errors otherwise.
+    pub fn add_foreign_utxo(mut self, outpoint: OutPoint, psbt_input: PsbtInput, satisfaction_weight: u64) -> Self {
+        self.foreign_utxos
+            .push((outpoint.into(), psbt_input.into(), Weight::from_wu(satisfaction_weight)));
+        self
+    }
+
+    /// Only fill in the `witness_utxo` of segwit inputs, and accept foreign UTXOs without `non_witness_utxo`.
+    ///
+    /// This reduces the size of the PSBT, but signers need to trust the value of the spent outputs.
+    pub fn only_witness_utxo(mut self) -> Self {
+        self.only_witness_utxo = true;
+        self
+    }
+
+    /// Set an absolute lock time, in its consensus encoding.
+    ///
+    /// Values below 500,000,000 are block heights, others are UNIX timestamps. The transaction cannot be mined
+    /// before that height or time. This overrides the anti-fee-sniping lock time.
+    pub fn nlocktime(mut self, lock_time: u32) -> Self {
+        self.nlocktime = Some(LockTime::from_consensus(lock_time));
+        self
+    }
+
+    /// Set the current blockchain height.
+    ///
+    /// This will be used to set the anti-fee-sniping lock time and to decide whether coinbase outputs are mature.
+    /// Defaults to the height of the wallet's latest checkpoint, [`TxBuilder::finish`] errors if it is lower.
+    pub fn current_height(mut self, height: u32) -> Self {
+        self.current_height = Some(height);
+        self
+    }
+
+    /// Set whether the lock time is set to the current height when no [`TxBuilder::nlocktime`] is given.
+    ///
+    /// Enabled by default, this makes fee sniping, re-mining a block to steal its fees, less profitable. When
+    /// disabled, the lock time is the one required by the `after()` of the wallet's descriptors, 0 if they have
+    /// none, so a pre-signed transaction can be mined as soon as the policy allows it.
+    pub fn anti_fee_sniping(mut self, enabled: bool) -> Self {
+        self.anti_fee_sniping = enabled;
+        self
+    }
+
+    /// Set the sequence of all the inputs.
+    ///
+    /// Defaults to `0xFFFFFFFD`, signaling RBF. The sequence must be compatible with the relative lock times
+    /// required by the wallet's descriptors.
+    pub fn set_exact_sequence(mut self, sequence: u32) -> Self {
+        self.exact_sequence = Some(Sequence::from_consensus(sequence));
+        self
+    }
+
+    /// Set the sequence of the input spending `outpoint`, overriding [`TxBuilder::set_exact_sequence`].
+    ///
+    /// Use it to set a relative lock time (BIP 68) on a single input. [`TxBuilder::finish`] errors if the
+    /// transaction does not spend `outpoint`, or if the sequence does not satisfy the `older()` of the policy of
+    /// the output or disables the lock time its `after()` requires.
+    pub fn input_sequence(mut self, outpoint: OutPoint, sequence: u32) -> Self {
+        self.input_sequences
+            .insert(outpoint.into(), Sequence::from_consensus(sequence));
+        self
+    }
+
+    /// Finish building the transaction.
+    ///
+    /// Returns a new [`Psbt`] per [`BIP174`].
+    ///
+    /// [`BIP174`]: https://github.com/bitcoin/bips/blob/master/bip-0174.mediawiki
+    pub fn finish(self) -> JsResult<Psbt> {
+        let data = self.check_data()?;
+
+        let mut wallet = self.wallet.borrow_mut();
+        self.check_current_height(&wallet)?;
+        self.check_input_sequences(&wallet)?;
+        // The lock time required by the descriptors is only looked up when it is used.
+        let lock_time = match self.nlocktime {
+            Some(lock_time) => Some(lock_time),
+            None if !self.anti_fee_sniping => Some(Self::required_lock_time(&wallet)?.unwrap_or(LockTime::ZERO)),
+            None => None,
+        };
+
+        let mut builder = match self.fee_bump {
+            Some(txid) => wallet.build_fee_bump(txid)?,
+            None => wallet.build_tx(),
+        };
+
+        for recipient in &self.recipients {
+            builder.add_recipient(recipient.address.script_pubkey().into(), recipient.amount.into());
+        }
+        if let Some(fee_rate) = self.fee_rate {
+            builder.fee_rate(fee_rate);
+        }
+        if let Some(fee) = self.fee_absolute {
+            builder.fee_absolute(fee);
+        }
+        if self.drain_wallet {
+            builder.drain_wallet();
+        }
+        if let Some(drain_to) = &self.drain_to {
+            builder.drain_to(drain_to.clone());
+        }
+        builder.allow_dust(self.allow_dust);
+        for data in &data {
+            builder.add_data(data);
+        }
+        if let Some(lock_time) = lock_time {
+            builder.nlocktime(lock_time);
+        }
+        if let Some(height) = self.current_height {
+            builder.current_height(height);
+        }
+        if let Some(sequence) = self.exact_sequence {
+            builder.set_exact_sequence(sequence);
+        }
+        if self.has_relative_lock_time() {
+            builder.version(2);
+        }
+        builder.add_utxos(&self.utxos)?;
+        for (outpoint, psbt_input, satisfaction_weight) in &self.foreign_utxos {
+            // Foreign UTXOs get a final sequence by default, give them the one of the wallet's inputs instead.
+            let sequence = self.exact_sequence.unwrap_or(Sequence::ENABLE_RBF_NO_LOCKTIME);
+            builder.add_foreign_utxo_with_sequence(*outpoint, psbt_input.clone(), *satisfaction_weight, sequence)?;
+        }
+        if self.manually_selected_only {
+            builder.manually_selected_only();
+        }
+        if self.only_witness_utxo {
+            builder.only_witness_utxo();
+        }
+
+        let mut psbt = builder.finish()?.into();
+        self.apply_input_sequences(&mut psbt)?;
+        Ok(psbt)
+    }
+}
+
+/// An output of a transaction to build, paying an amount to an address.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    address: Address,
+    amount: Amount,
+}
+
+#[wasm_bindgen]
+impl Recipient {
+    #[wasm_bindgen(constructor)]
+    pub fn new(address: Address, amount: Amount) -> Self {
+        Recipient { address, amount }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn address(&self) -> Address {
+        self.address.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+}
+
+/// The lock time and sequence required to spend the outputs of `keychain` without choosing a policy path.
+///
+/// Policies requiring a path have no condition here, BDK rejects them when building the transaction.
+fn policy_condition(wallet: &BdkWallet, keychain: KeychainKind) -> JsResult<Condition> {
+    Ok(wallet
+        .policies(keychain)?
+        .and_then(|policy| policy.get_condition(&BTreeMap::new()).ok())
+        .unwrap_or_default())
+}
@@ -0,0 +1,92 @@
+use crate::types::{BdkError, BdkErrorCode};
+
+/// The Bytewords list of BCR-2020-012, one word per byte value.
+#[rustfmt::skip]
+const WORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt",
+    "away", "axis", "back", "bald", "barn", "belt", "beta", "bias",
+    "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost",
+    "crux", "curl", "cusp", "cyan", "dark", "data", "days", "deli",
+    "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam",
+    "exit", "eyes", "fact", "fair", "fern", "figs", "film", "fish",
+    "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow",
+    "good", "gray", "grim", "guru", "gush", "gyro", "half", "hang",
+    "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into",
+    "iris", "iron", "item", "jade", "jazz", "join", "jolt", "jowl",
+    "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb",
+    "lava", "lazy", "leaf", "legs", "liar", "limp", "lion", "list",
+    "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss",
+    "monk", "nail", "navy", "need", "news", "next", "noon", "note",
+    "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose",
+    "puff", "puma", "purr", "quad", "quiz", "race", "ramp", "real",
+    "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot",
+    "soap", "solo", "song", "stub", "surf", "swan", "taco", "task",
+    "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user",
+    "vast", "very", "veto", "vial", "vibe", "view", "visa", "void",
+    "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell",
+    "yoga", "yurt", "zaps", "zero", "zest", "zinc", "zone", "zoom",
+];
+
+/// Encode data as minimal Bytewords, the first and last letters of each word, followed by its CRC32.
+pub(crate) fn encode(data: &[u8]) -> String {
+    let checksum = crc32(data).to_be_bytes();
+    data.iter()
+        .chain(&checksum)
+        .flat_map(|byte| {
+            let word = WORDS[*byte as usize].as_bytes();
+            [word[0] as char, word[3] as char]
+        })
+        .collect()
+}
+
+/// Decode minimal Bytewords, verifying and removing the CRC32.
+pub(crate) fn decode(text: &str) -> Result<Vec<u8>, BdkError> {
+    let invalid = |message: &str| BdkError::new(BdkErrorCode::InvalidQr, format!("invalid Bytewords: {message}"));
+
+    let letters = text.as_bytes();
+    if letters.len() % 2 != 0 {
+        return Err(invalid("odd number of letters"));
+    }
+    let bytes = letters
+        .chunks(2)
+        .map(|pair| {
+            WORDS
+                .iter()
+                .position(|word| word.as_bytes()[0] == pair[0] && word.as_bytes()[3] == pair[1])
+                .map(|byte| byte as u8)
+                .ok_or_else(|| invalid("unknown word"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if bytes.len() < 4 {
+        return Err(invalid("missing checksum"));
+    }
+    let (data, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc32(data).to_be_bytes() != checksum {
+        return Err(invalid("checksum mismatch"));
+    }
+    Ok(data.to_vec())
+}
+
+/// CRC-32 (ISO-HDLC), as used by Bytewords and the fountain encoder.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
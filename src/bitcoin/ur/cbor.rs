@@ -0,0 +1,212 @@
+use crate::types::{BdkError, BdkErrorCode};
+
+/// Nesting limit of decoded data items, the UR registry types never go deeper than a few levels.
+const MAX_DEPTH: usize = 16;
+
+/// The subset of CBOR (RFC 8949) data items used by the UR registry types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Cbor {
+    Unsigned(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Tag(u64, Box<Cbor>),
+    Bool(bool),
+}
+
+impl Cbor {
+    pub(crate) fn tagged(tag: u64, value: Cbor) -> Cbor {
+        Cbor::Tag(tag, Box::new(value))
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Cbor::Unsigned(value) => write_head(out, 0, *value),
+            Cbor::Bytes(bytes) => {
+                write_head(out, 2, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Cbor::Text(text) => {
+                write_head(out, 3, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Cbor::Array(items) => {
+                write_head(out, 4, items.len() as u64);
+                items.iter().for_each(|item| item.encode(out));
+            }
+            Cbor::Map(entries) => {
+                write_head(out, 5, entries.len() as u64);
+                for (key, value) in entries {
+                    key.encode(out);
+                    value.encode(out);
+                }
+            }
+            Cbor::Tag(tag, value) => {
+                write_head(out, 6, *tag);
+                value.encode(out);
+            }
+            Cbor::Bool(value) => out.push(if *value { 0xf5 } else { 0xf4 }),
+        }
+    }
+
+    /// Decode a single data item, erroring on trailing bytes.
+    pub(crate) fn from_slice(data: &[u8]) -> Result<Cbor, BdkError> {
+        let mut decoder = Decoder { data, position: 0 };
+        let value = decoder.item(0)?;
+        if decoder.position != data.len() {
+            return Err(invalid("trailing bytes after the data item"));
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn as_unsigned(&self) -> Option<u64> {
+        match self {
+            Cbor::Unsigned(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Cbor]> {
+        match self {
+            Cbor::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bool(&self) -> Option<bool> {
+        match self {
+            Cbor::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value of a map entry with an unsigned integer key.
+    pub(crate) fn get(&self, key: u64) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| *k == Cbor::Unsigned(key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// The tag and the tagged value.
+    pub(crate) fn as_tag(&self) -> Option<(u64, &Cbor)> {
+        match self {
+            Cbor::Tag(tag, value) => Some((*tag, value)),
+            _ => None,
+        }
+    }
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+fn invalid(message: &str) -> BdkError {
+    BdkError::new(BdkErrorCode::InvalidQr, format!("invalid CBOR: {message}"))
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Decoder<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], BdkError> {
+        if self.data.len() - self.position < len {
+            return Err(invalid("unexpected end of data"));
+        }
+        let bytes = &self.data[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn head(&mut self) -> Result<(u8, u8, u64), BdkError> {
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let value = match info {
+            0..=23 => info as u64,
+            24..=27 => {
+                let len = 1 << (info - 24);
+                self.take(len)?.iter().fold(0, |value, byte| value << 8 | *byte as u64)
+            }
+            _ => return Err(invalid("indefinite lengths are not supported")),
+        };
+        Ok((major, info, value))
+    }
+
+    fn len(&mut self, value: u64) -> Result<usize, BdkError> {
+        // Every data item takes at least one byte, longer lengths cannot be valid.
+        match usize::try_from(value) {
+            Ok(len) if len <= self.data.len() - self.position => Ok(len),
+            _ => Err(invalid("length exceeds the data")),
+        }
+    }
+
+    fn item(&mut self, depth: usize) -> Result<Cbor, BdkError> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("too deeply nested"));
+        }
+        let (major, info, value) = self.head()?;
+        match major {
+            0 => Ok(Cbor::Unsigned(value)),
+            2 => {
+                let len = self.len(value)?;
+                Ok(Cbor::Bytes(self.take(len)?.to_vec()))
+            }
+            3 => {
+                let len = self.len(value)?;
+                let text = String::from_utf8(self.take(len)?.to_vec()).map_err(|_| invalid("text is not UTF-8"))?;
+                Ok(Cbor::Text(text))
+            }
+            4 => {
+                let len = self.len(value)?;
+                let items = (0..len).map(|_| self.item(depth + 1)).collect::<Result<_, _>>()?;
+                Ok(Cbor::Array(items))
+            }
+            5 => {
+                let len = self.len(value)?;
+                let entries = (0..len)
+                    .map(|_| Ok((self.item(depth + 1)?, self.item(depth + 1)?)))
+                    .collect::<Result<_, BdkError>>()?;
+                Ok(Cbor::Map(entries))
+            }
+            6 => Ok(Cbor::tagged(value, self.item(depth + 1)?)),
+            7 if info == 20 => Ok(Cbor::Bool(false)),
+            7 if info == 21 => Ok(Cbor::Bool(true)),
+            _ => Err(invalid("unsupported data item")),
+        }
+    }
+}
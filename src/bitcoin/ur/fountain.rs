@@ -0,0 +1,344 @@
+//! Fountain codes of BCR-2020-005, splitting a message into an unbounded sequence of parts from which it can
+//! be recovered once enough of them, in any order, are received.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bdk_wallet::bitcoin::hashes::{sha256, Hash};
+
+use super::{bytewords::crc32, cbor::Cbor};
+use crate::types::{BdkError, BdkErrorCode};
+
+/// Smallest fragment length chosen when splitting a message.
+const MIN_FRAGMENT_LEN: usize = 10;
+/// Largest message accepted, well above the size of the data exchanged through QR codes.
+pub(crate) const MAX_MESSAGE_LEN: usize = 1 << 22;
+/// Largest number of fragments accepted, as choosing the fragments of a mix is quadratic in their number.
+pub(crate) const MAX_SEQ_LEN: usize = 10_000;
+
+/// A part of a message: a single fragment, or the XOR of several fragments for sequence numbers past the
+/// number of fragments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Part {
+    pub seq_num: u32,
+    pub seq_len: usize,
+    pub message_len: usize,
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    pub(crate) fn to_cbor(&self) -> Vec<u8> {
+        Cbor::Array(vec![
+            Cbor::Unsigned(self.seq_num as u64),
+            Cbor::Unsigned(self.seq_len as u64),
+            Cbor::Unsigned(self.message_len as u64),
+            Cbor::Unsigned(self.checksum as u64),
+            Cbor::Bytes(self.data.clone()),
+        ])
+        .to_vec()
+    }
+
+    pub(crate) fn from_cbor(data: &[u8]) -> Result<Part, BdkError> {
+        let invalid = || BdkError::new(BdkErrorCode::InvalidQr, "invalid fountain part");
+
+        let cbor = Cbor::from_slice(data)?;
+        let [seq_num, seq_len, message_len, checksum, data] = cbor.as_array().ok_or_else(invalid)? else {
+            return Err(invalid());
+        };
+        let unsigned = |value: &Cbor, max: u64| value.as_unsigned().filter(|value| *value <= max).ok_or_else(invalid);
+        let part = Part {
+            seq_num: unsigned(seq_num, u32::MAX as u64)? as u32,
+            seq_len: unsigned(seq_len, MAX_SEQ_LEN as u64)? as usize,
+            message_len: unsigned(message_len, MAX_MESSAGE_LEN as u64)? as usize,
+            checksum: unsigned(checksum, u32::MAX as u64)? as u32,
+            data: data.as_bytes().ok_or_else(invalid)?.to_vec(),
+        };
+        if part.seq_num == 0 || part.seq_len == 0 || part.data.is_empty() {
+            return Err(invalid());
+        }
+        Ok(part)
+    }
+}
+
+/// Splits a message into fragments and emits parts, the fragments first and then mixes of them.
+#[derive(Debug, Clone)]
+pub(crate) struct FountainEncoder {
+    fragments: Vec<Vec<u8>>,
+    message_len: usize,
+    checksum: u32,
+    seq_num: u32,
+}
+
+impl FountainEncoder {
+    pub(crate) fn new(message: &[u8], max_fragment_len: usize) -> FountainEncoder {
+        let fragment_len = fragment_len(message.len(), max_fragment_len);
+        let mut padded = message.to_vec();
+        padded.resize(message.len().div_ceil(fragment_len).max(1) * fragment_len, 0);
+        FountainEncoder {
+            fragments: padded.chunks(fragment_len).map(<[u8]>::to_vec).collect(),
+            message_len: message.len(),
+            checksum: crc32(message),
+            seq_num: 0,
+        }
+    }
+
+    pub(crate) fn fragment_count(&self) -> usize {
+        self.fragments.len()
+    }
+
+    pub(crate) fn next_part(&mut self) -> Part {
+        self.seq_num = self.seq_num.wrapping_add(1).max(1);
+        let mut data = vec![0; self.fragments[0].len()];
+        for index in choose_fragments(self.seq_num, self.fragments.len(), self.checksum) {
+            xor_into(&mut data, &self.fragments[index]);
+        }
+        Part {
+            seq_num: self.seq_num,
+            seq_len: self.fragments.len(),
+            message_len: self.message_len,
+            checksum: self.checksum,
+            data,
+        }
+    }
+}
+
+/// Recovers a message from its parts, received in any order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FountainDecoder {
+    /// Sequence length, message length, checksum and fragment length shared by all the parts.
+    params: Option<(usize, usize, u32, usize)>,
+    fragments: BTreeMap<usize, Vec<u8>>,
+    mixed: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    message: Option<Vec<u8>>,
+}
+
+impl FountainDecoder {
+    pub(crate) fn receive(&mut self, part: Part) -> Result<(), BdkError> {
+        let params = (part.seq_len, part.message_len, part.checksum, part.data.len());
+        match self.params {
+            // The message spans all the fragments, only the last one being padded.
+            None if !part
+                .seq_len
+                .checked_mul(part.data.len())
+                .is_some_and(|len| part.message_len <= len && part.message_len > len - part.data.len()) =>
+            {
+                return Err(BdkError::new(
+                    BdkErrorCode::InvalidQr,
+                    "the message length does not match the fragments",
+                ))
+            }
+            None => self.params = Some(params),
+            Some(expected) if expected != params => {
+                return Err(BdkError::new(
+                    BdkErrorCode::InvalidQr,
+                    "the part belongs to a different message",
+                ))
+            }
+            Some(_) => {}
+        }
+        if self.message.is_some() {
+            return Ok(());
+        }
+
+        let indexes = choose_fragments(part.seq_num, part.seq_len, part.checksum);
+        let mut queue = vec![(indexes.into_iter().collect::<BTreeSet<_>>(), part.data)];
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            // Remove the fragments already known from the mix.
+            indexes.retain(|index| match self.fragments.get(index) {
+                Some(fragment) => {
+                    xor_into(&mut data, fragment);
+                    false
+                }
+                None => true,
+            });
+            match indexes.len() {
+                0 => {}
+                1 => {
+                    let index = *indexes.first().expect("one index");
+                    self.fragments.insert(index, data);
+                    // The mixes including the new fragment can be reduced.
+                    let (reducible, mixed) = self.mixed.drain(..).partition(|(mix, _)| mix.contains(&index));
+                    self.mixed = mixed;
+                    queue.extend(reducible);
+                }
+                _ => {
+                    if !self.mixed.iter().any(|(mix, _)| *mix == indexes) {
+                        self.mixed.push((indexes, data));
+                    }
+                }
+            }
+        }
+
+        if self.fragments.len() == part.seq_len {
+            let mut message = self.fragments.values().flatten().copied().collect::<Vec<_>>();
+            message.truncate(part.message_len);
+            if crc32(&message) != part.checksum {
+                *self = FountainDecoder::default();
+                return Err(BdkError::new(
+                    BdkErrorCode::InvalidQr,
+                    "the checksum of the message does not match",
+                ));
+            }
+            self.mixed.clear();
+            self.message = Some(message);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+
+    /// The fraction of the fragments recovered so far, between 0 and 1.
+    pub(crate) fn progress(&self) -> f64 {
+        match (&self.message, self.params) {
+            (Some(_), _) => 1.0,
+            (None, Some((seq_len, ..))) => self.fragments.len() as f64 / seq_len as f64,
+            (None, None) => 0.0,
+        }
+    }
+}
+
+/// The length of the fragments, as even as possible and no longer than `max_fragment_len` unless the
+/// message is too short to split in fragments of at least `MIN_FRAGMENT_LEN` bytes.
+fn fragment_len(message_len: usize, max_fragment_len: usize) -> usize {
+    let max_fragment_count = (message_len / MIN_FRAGMENT_LEN).max(1);
+    let mut fragment_len = message_len.max(1);
+    for fragment_count in 1..=max_fragment_count {
+        fragment_len = message_len.div_ceil(fragment_count).max(1);
+        if fragment_len <= max_fragment_len {
+            break;
+        }
+    }
+    fragment_len
+}
+
+fn xor_into(data: &mut [u8], other: &[u8]) {
+    data.iter_mut().zip(other).for_each(|(byte, other)| *byte ^= other);
+}
+
+/// The indexes of the fragments mixed in the part with sequence number `seq_num`.
+pub(crate) fn choose_fragments(seq_num: u32, fragment_count: usize, checksum: u32) -> Vec<usize> {
+    if seq_num as usize <= fragment_count {
+        return vec![seq_num as usize - 1];
+    }
+    let mut seed = seq_num.to_be_bytes().to_vec();
+    seed.extend(checksum.to_be_bytes());
+    let mut rng = Xoshiro256::new(&seed);
+    let degree = rng.choose_degree(fragment_count);
+    let mut indexes = rng.shuffled((0..fragment_count).collect());
+    indexes.truncate(degree);
+    indexes
+}
+
+/// The Xoshiro256** generator seeded with the SHA256 of a seed, as specified by BCR-2020-005.
+pub(crate) struct Xoshiro256 {
+    state: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub(crate) fn new(seed: &[u8]) -> Xoshiro256 {
+        let hash = sha256::Hash::hash(seed).to_byte_array();
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(hash.chunks(8)) {
+            *word = u64::from_be_bytes(bytes.try_into().expect("8 bytes"));
+        }
+        Xoshiro256 { state }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next_u64() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    fn next_int(&mut self, low: usize, high: usize) -> usize {
+        (self.next_double() * (high - low + 1) as f64) as usize + low
+    }
+
+    fn shuffled<T>(&mut self, mut items: Vec<T>) -> Vec<T> {
+        let mut shuffled = Vec::with_capacity(items.len());
+        while !items.is_empty() {
+            let index = self.next_int(0, items.len() - 1);
+            shuffled.push(items.remove(index));
+        }
+        shuffled
+    }
+
+    /// A degree between 1 and `fragment_count`, the degree `d` being chosen with a probability
+    /// proportional to `1/d`.
+    pub(crate) fn choose_degree(&mut self, fragment_count: usize) -> usize {
+        let weights = (1..=fragment_count)
+            .map(|degree| 1.0 / degree as f64)
+            .collect::<Vec<_>>();
+        WeightedSampler::new(&weights).next(self) + 1
+    }
+}
+
+/// Samples indexes with the given weights, using Vose's alias method.
+struct WeightedSampler {
+    aliases: Vec<usize>,
+    probabilities: Vec<f64>,
+}
+
+impl WeightedSampler {
+    fn new(weights: &[f64]) -> WeightedSampler {
+        let count = weights.len();
+        let sum = weights.iter().sum::<f64>();
+        let mut weights = weights
+            .iter()
+            .map(|weight| weight * count as f64 / sum)
+            .collect::<Vec<_>>();
+
+        let (mut small, mut large) = (Vec::new(), Vec::new());
+        for index in (0..count).rev() {
+            if weights[index] < 1.0 {
+                small.push(index);
+            } else {
+                large.push(index);
+            }
+        }
+
+        let mut probabilities = vec![0.0; count];
+        let mut aliases = vec![0; count];
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            probabilities[less] = weights[less];
+            aliases[less] = more;
+            weights[more] += weights[less] - 1.0;
+            if weights[more] < 1.0 {
+                small.push(more);
+            } else {
+                large.push(more);
+            }
+        }
+        for index in large.into_iter().chain(small) {
+            probabilities[index] = 1.0;
+        }
+
+        WeightedSampler { aliases, probabilities }
+    }
+
+    fn next(&self, rng: &mut Xoshiro256) -> usize {
+        let r1 = rng.next_double();
+        let r2 = rng.next_double();
+        let index = (self.probabilities.len() as f64 * r1) as usize;
+        if r2 < self.probabilities[index] {
+            index
+        } else {
+            self.aliases[index]
+        }
+    }
+}
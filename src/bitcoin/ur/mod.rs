@@ -0,0 +1,253 @@
+//! Uniform Resources (BCR-2020-005), the multi-part QR codes of Blockchain Commons used by Keystone, Passport
+//! and other air-gapped signers.
+
+mod bytewords;
+mod cbor;
+mod fountain;
+mod registry;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    result::JsResult,
+    types::{BdkError, BdkErrorCode, DescriptorPair, Psbt},
+};
+
+use fountain::{FountainDecoder, FountainEncoder, Part, MAX_MESSAGE_LEN, MAX_SEQ_LEN};
+use registry::{BYTES, CRYPTO_ACCOUNT, CRYPTO_OUTPUT, CRYPTO_PSBT, PSBT};
+
+/// Encodes data as a sequence of UR parts to display as an animated QR code.
+///
+/// Once all the fragments of the data have been emitted, the encoder keeps emitting parts mixing several
+/// fragments, so a scanner missing some frames can recover them from the following ones. The data is limited
+/// to 4 MiB in at most 10000 fragments, the limits enforced when decoding.
+#[wasm_bindgen]
+pub struct UrEncoder {
+    ur_type: String,
+    message: Vec<u8>,
+    encoder: FountainEncoder,
+}
+
+#[wasm_bindgen]
+impl UrEncoder {
+    /// Encode a PSBT as a `crypto-psbt`, in fragments of at most `max_fragment_len` bytes.
+    pub fn from_psbt(psbt: &Psbt, max_fragment_len: usize) -> JsResult<UrEncoder> {
+        UrEncoder::new(CRYPTO_PSBT, registry::psbt_to_cbor(psbt), max_fragment_len)
+    }
+
+    /// Encode the public descriptors of a wallet as a `crypto-output`.
+    ///
+    /// The descriptors must derive their addresses with `/0/*` and `/1/*` from the same extended keys,
+    /// which are encoded at the account level. Private keys are not included.
+    pub fn from_descriptor(descriptors: &DescriptorPair, max_fragment_len: usize) -> JsResult<UrEncoder> {
+        let descriptor = descriptors.to_public_multipath()?;
+        UrEncoder::new(CRYPTO_OUTPUT, registry::output_to_cbor(&descriptor)?, max_fragment_len)
+    }
+
+    /// Encode the public descriptors of several wallets of the same master key as a `crypto-account`.
+    pub fn from_account(descriptors: Vec<DescriptorPair>, max_fragment_len: usize) -> JsResult<UrEncoder> {
+        let descriptors = descriptors
+            .iter()
+            .map(DescriptorPair::to_public_multipath)
+            .collect::<Result<Vec<_>, _>>()?;
+        UrEncoder::new(
+            CRYPTO_ACCOUNT,
+            registry::account_to_cbor(&descriptors)?,
+            max_fragment_len,
+        )
+    }
+
+    /// Encode arbitrary data as `bytes`.
+    pub fn from_bytes(data: &[u8], max_fragment_len: usize) -> JsResult<UrEncoder> {
+        UrEncoder::new(BYTES, registry::bytes_to_cbor(data), max_fragment_len)
+    }
+
+    /// The type of the encoded data, such as `crypto-psbt`.
+    #[wasm_bindgen(getter)]
+    pub fn ur_type(&self) -> String {
+        self.ur_type.clone()
+    }
+
+    /// The number of fragments of the data, the minimum number of parts needed to decode it.
+    #[wasm_bindgen(getter)]
+    pub fn fragment_count(&self) -> usize {
+        self.encoder.fragment_count()
+    }
+
+    /// Whether the data fits in a single part, that can be displayed as a static QR code.
+    #[wasm_bindgen(getter)]
+    pub fn is_single_part(&self) -> bool {
+        self.encoder.fragment_count() == 1
+    }
+
+    /// The next part to display, in lower case. Upper case it to use the alphanumeric mode of QR codes.
+    pub fn next_part(&mut self) -> String {
+        if self.is_single_part() {
+            return format!("ur:{}/{}", self.ur_type, bytewords::encode(&self.message));
+        }
+        let part = self.encoder.next_part();
+        format!(
+            "ur:{}/{}-{}/{}",
+            self.ur_type,
+            part.seq_num,
+            part.seq_len,
+            bytewords::encode(&part.to_cbor())
+        )
+    }
+}
+
+impl UrEncoder {
+    pub(crate) fn new(ur_type: &str, message: Vec<u8>, max_fragment_len: usize) -> Result<UrEncoder, BdkError> {
+        if max_fragment_len == 0 {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                "the maximum fragment length must be positive",
+            ));
+        }
+        if message.len() > MAX_MESSAGE_LEN {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                "the data is too large for a UR",
+            ));
+        }
+        let encoder = FountainEncoder::new(&message, max_fragment_len);
+        if encoder.fragment_count() > MAX_SEQ_LEN {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                format!("the data needs more than {MAX_SEQ_LEN} fragments, the maximum fragment length is too small"),
+            ));
+        }
+        Ok(UrEncoder {
+            ur_type: ur_type.to_string(),
+            encoder,
+            message,
+        })
+    }
+}
+
+/// Decodes data from the parts of a UR, scanned in any order.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct UrDecoder {
+    ur_type: Option<String>,
+    message: Option<Vec<u8>>,
+    decoder: FountainDecoder,
+}
+
+#[wasm_bindgen]
+impl UrDecoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> UrDecoder {
+        UrDecoder::default()
+    }
+
+    /// Add a scanned part, in lower or upper case.
+    ///
+    /// Errors if the part is not a valid UR or belongs to different data than the previous parts. Parts
+    /// received after the data is complete are ignored.
+    pub fn receive(&mut self, part: &str) -> JsResult<()> {
+        let part = part.trim().to_ascii_lowercase();
+        let invalid = |message: &str| BdkError::new(BdkErrorCode::InvalidQr, message);
+
+        let components = part
+            .strip_prefix("ur:")
+            .ok_or_else(|| invalid("the part is not a UR"))?
+            .split('/')
+            .collect::<Vec<_>>();
+        let ur_type = components[0];
+        if ur_type.is_empty()
+            || !ur_type
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(invalid("invalid UR type"));
+        }
+        if self.ur_type.as_deref().is_some_and(|expected| expected != ur_type) {
+            return Err(invalid("the part belongs to a UR of a different type"));
+        }
+
+        match components[1..] {
+            [body] => {
+                let message = bytewords::decode(body)?;
+                if self.message.as_ref().is_some_and(|expected| *expected != message) {
+                    return Err(invalid("the part belongs to different data"));
+                }
+                self.message = Some(message);
+            }
+            [sequence, body] => {
+                let (seq_num, seq_len) = sequence
+                    .split_once('-')
+                    .and_then(|(seq_num, seq_len)| Some((seq_num.parse::<u32>().ok()?, seq_len.parse::<usize>().ok()?)))
+                    .ok_or_else(|| invalid("invalid sequence"))?;
+                let part = Part::from_cbor(&bytewords::decode(body)?)?;
+                if part.seq_num != seq_num || part.seq_len != seq_len {
+                    return Err(invalid("the sequence does not match the part"));
+                }
+                self.decoder.receive(part)?;
+                self.message = self.decoder.message().map(<[u8]>::to_vec);
+            }
+            _ => return Err(invalid("invalid number of path components")),
+        }
+        self.ur_type = Some(ur_type.to_string());
+        Ok(())
+    }
+
+    /// The type of the data being decoded, known after the first part is received.
+    #[wasm_bindgen(getter)]
+    pub fn ur_type(&self) -> Option<String> {
+        self.ur_type.clone()
+    }
+
+    /// Whether all the data has been received.
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        self.message.is_some()
+    }
+
+    /// The fraction of the data received so far, between 0 and 1.
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        match self.message {
+            Some(_) => 1.0,
+            None => self.decoder.progress(),
+        }
+    }
+
+    /// The data of a decoded `bytes`.
+    pub fn to_bytes(&self) -> JsResult<Vec<u8>> {
+        registry::bytes_from_cbor(self.message(&[BYTES])?)
+    }
+
+    /// The decoded `crypto-psbt` or `psbt`.
+    pub fn to_psbt(&self) -> JsResult<Psbt> {
+        let psbt = registry::psbt_from_cbor(self.message(&[CRYPTO_PSBT, PSBT])?)?;
+        Ok(psbt.into())
+    }
+
+    /// The descriptors of a decoded `crypto-output`.
+    pub fn to_descriptor(&self) -> JsResult<DescriptorPair> {
+        let descriptor = registry::output_from_cbor(self.message(&[CRYPTO_OUTPUT])?)?;
+        DescriptorPair::from_public_multipath(&descriptor)
+    }
+
+    /// The descriptors of the outputs of a decoded `crypto-account`.
+    pub fn to_account(&self) -> JsResult<Vec<DescriptorPair>> {
+        let descriptors = registry::account_from_cbor(self.message(&[CRYPTO_ACCOUNT])?)?;
+        descriptors.iter().map(DescriptorPair::from_public_multipath).collect()
+    }
+}
+
+impl UrDecoder {
+    fn message(&self, ur_types: &[&str]) -> Result<&[u8], BdkError> {
+        let (Some(ur_type), Some(message)) = (&self.ur_type, &self.message) else {
+            return Err(BdkError::new(BdkErrorCode::InvalidQr, "the UR is not complete"));
+        };
+        if !ur_types.contains(&ur_type.as_str()) {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidQr,
+                format!("expected a {} UR, found {ur_type}", ur_types[0]),
+            ));
+        }
+        Ok(message)
+    }
+}
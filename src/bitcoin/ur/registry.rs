@@ -0,0 +1,356 @@
+//! Conversions between PSBTs and descriptors and the UR registry types of BCR-2020-006, BCR-2020-007,
+//! BCR-2020-010 and BCR-2020-015.
+
+use std::str::FromStr;
+
+use bdk_wallet::{
+    bitcoin::{
+        bip32::{ChainCode, ChildNumber, DerivationPath, Fingerprint, Xpub},
+        secp256k1::PublicKey,
+        NetworkKind, Psbt as BdkPsbt,
+    },
+    miniscript::{
+        descriptor::{DerivPaths, DescriptorMultiXKey, ShInner, Wildcard, WshInner},
+        Descriptor, DescriptorPublicKey, ForEachKey, Miniscript, ScriptContext, Terminal,
+    },
+};
+
+use super::cbor::Cbor;
+use crate::types::{BdkError, BdkErrorCode};
+
+pub(crate) const BYTES: &str = "bytes";
+pub(crate) const CRYPTO_PSBT: &str = "crypto-psbt";
+pub(crate) const PSBT: &str = "psbt";
+pub(crate) const CRYPTO_OUTPUT: &str = "crypto-output";
+pub(crate) const CRYPTO_ACCOUNT: &str = "crypto-account";
+
+const TAG_HDKEY: u64 = 303;
+const TAG_KEYPATH: u64 = 304;
+const TAG_COIN_INFO: u64 = 305;
+const TAG_OUTPUT: u64 = 308;
+const TAG_SH: u64 = 400;
+const TAG_WSH: u64 = 401;
+const TAG_PK: u64 = 402;
+const TAG_PKH: u64 = 403;
+const TAG_WPKH: u64 = 404;
+const TAG_MULTI: u64 = 406;
+const TAG_SORTED_MULTI: u64 = 407;
+const TAG_TR: u64 = 409;
+
+fn invalid(message: impl std::fmt::Display) -> BdkError {
+    BdkError::new(BdkErrorCode::InvalidQr, message)
+}
+
+fn unsupported() -> BdkError {
+    BdkError::new(
+        BdkErrorCode::InvalidDescriptor,
+        "only single key and multisig descriptors of extended keys ending with /<0;1>/* can be encoded as UR",
+    )
+}
+
+pub(crate) fn bytes_to_cbor(data: &[u8]) -> Vec<u8> {
+    Cbor::Bytes(data.to_vec()).to_vec()
+}
+
+pub(crate) fn bytes_from_cbor(message: &[u8]) -> Result<Vec<u8>, BdkError> {
+    let cbor = Cbor::from_slice(message)?;
+    let bytes = cbor
+        .as_bytes()
+        .ok_or_else(|| invalid("the data is not a byte string"))?;
+    Ok(bytes.to_vec())
+}
+
+pub(crate) fn psbt_to_cbor(psbt: &BdkPsbt) -> Vec<u8> {
+    bytes_to_cbor(&psbt.serialize())
+}
+
+pub(crate) fn psbt_from_cbor(message: &[u8]) -> Result<BdkPsbt, BdkError> {
+    let cbor = Cbor::from_slice(message)?;
+    let bytes = cbor
+        .as_bytes()
+        .ok_or_else(|| invalid("the PSBT is not a byte string"))?;
+    Ok(BdkPsbt::deserialize(bytes)?)
+}
+
+/// Encode a multipath descriptor as a `crypto-output`, its keys at the account level.
+pub(crate) fn output_to_cbor(descriptor: &Descriptor<DescriptorPublicKey>) -> Result<Vec<u8>, BdkError> {
+    Ok(output(descriptor)?.to_vec())
+}
+
+pub(crate) fn output_from_cbor(message: &[u8]) -> Result<Descriptor<DescriptorPublicKey>, BdkError> {
+    parse_output(&Cbor::from_slice(message)?)
+}
+
+/// Encode the multipath descriptors of an account as a `crypto-account`.
+///
+/// All the keys must derive from the same master key.
+pub(crate) fn account_to_cbor(descriptors: &[Descriptor<DescriptorPublicKey>]) -> Result<Vec<u8>, BdkError> {
+    let mut fingerprints = Vec::new();
+    for descriptor in descriptors {
+        descriptor.for_each_key(|key| {
+            fingerprints.push(key.master_fingerprint());
+            true
+        });
+    }
+    let master_fingerprint = match fingerprints.split_first() {
+        Some((first, rest)) if rest.iter().all(|fingerprint| fingerprint == first) => *first,
+        _ => {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidDescriptor,
+                "the descriptors of an account must derive from the same master key",
+            ))
+        }
+    };
+
+    let outputs = descriptors
+        .iter()
+        .map(|descriptor| Ok(Cbor::tagged(TAG_OUTPUT, output(descriptor)?)))
+        .collect::<Result<_, BdkError>>()?;
+    Ok(Cbor::Map(vec![
+        (Cbor::Unsigned(1), fingerprint_to_cbor(master_fingerprint)),
+        (Cbor::Unsigned(2), Cbor::Array(outputs)),
+    ])
+    .to_vec())
+}
+
+pub(crate) fn account_from_cbor(message: &[u8]) -> Result<Vec<Descriptor<DescriptorPublicKey>>, BdkError> {
+    let cbor = Cbor::from_slice(message)?;
+    let outputs = cbor
+        .get(2)
+        .and_then(Cbor::as_array)
+        .ok_or_else(|| invalid("the account has no outputs"))?;
+    outputs
+        .iter()
+        .map(|output| match output.as_tag() {
+            Some((TAG_OUTPUT, output)) => parse_output(output),
+            _ => Err(invalid("the account output is not a crypto-output")),
+        })
+        .collect()
+}
+
+fn output(descriptor: &Descriptor<DescriptorPublicKey>) -> Result<Cbor, BdkError> {
+    let output = match descriptor {
+        Descriptor::Pkh(pkh) => Cbor::tagged(TAG_PKH, key(pkh.as_inner())?),
+        Descriptor::Wpkh(wpkh) => Cbor::tagged(TAG_WPKH, key(wpkh.as_inner())?),
+        Descriptor::Sh(sh) => Cbor::tagged(
+            TAG_SH,
+            match sh.as_inner() {
+                ShInner::Wpkh(wpkh) => Cbor::tagged(TAG_WPKH, key(wpkh.as_inner())?),
+                ShInner::Wsh(wsh) => Cbor::tagged(TAG_WSH, wsh_inner(wsh.as_inner())?),
+                ShInner::SortedMulti(multi) => multisig(TAG_SORTED_MULTI, multi.k(), multi.pks())?,
+                ShInner::Ms(ms) => miniscript(ms)?,
+            },
+        ),
+        Descriptor::Wsh(wsh) => Cbor::tagged(TAG_WSH, wsh_inner(wsh.as_inner())?),
+        Descriptor::Tr(tr) if tr.tap_tree().is_none() => Cbor::tagged(TAG_TR, key(tr.internal_key())?),
+        _ => return Err(unsupported()),
+    };
+    Ok(output)
+}
+
+fn wsh_inner(inner: &WshInner<DescriptorPublicKey>) -> Result<Cbor, BdkError> {
+    match inner {
+        WshInner::SortedMulti(multi) => multisig(TAG_SORTED_MULTI, multi.k(), multi.pks()),
+        WshInner::Ms(ms) => miniscript(ms),
+    }
+}
+
+fn miniscript<Ctx: ScriptContext>(ms: &Miniscript<DescriptorPublicKey, Ctx>) -> Result<Cbor, BdkError> {
+    match &ms.node {
+        Terminal::Multi(threshold) => multisig(TAG_MULTI, threshold.k(), threshold.data()),
+        _ => Err(unsupported()),
+    }
+}
+
+fn multisig(tag: u64, threshold: usize, keys: &[DescriptorPublicKey]) -> Result<Cbor, BdkError> {
+    let keys = keys.iter().map(key).collect::<Result<_, _>>()?;
+    Ok(Cbor::tagged(
+        tag,
+        Cbor::Map(vec![
+            (Cbor::Unsigned(1), Cbor::Unsigned(threshold as u64)),
+            (Cbor::Unsigned(2), Cbor::Array(keys)),
+        ]),
+    ))
+}
+
+/// The paths `<0;1>/*` of the receive and change addresses of an account.
+fn account_paths() -> DerivPaths {
+    DerivPaths::new(vec![
+        DerivationPath::from(vec![ChildNumber::Normal { index: 0 }]),
+        DerivationPath::from(vec![ChildNumber::Normal { index: 1 }]),
+    ])
+    .expect("two paths")
+}
+
+/// Encode a key as a `crypto-hdkey`, without children since the paths are always `<0;1>/*`.
+fn key(key: &DescriptorPublicKey) -> Result<Cbor, BdkError> {
+    let DescriptorPublicKey::MultiXPub(xkey) = key else {
+        return Err(unsupported());
+    };
+    if xkey.wildcard != Wildcard::Unhardened || xkey.derivation_paths != account_paths() {
+        return Err(unsupported());
+    }
+
+    let xpub = &xkey.xkey;
+    let mut entries = vec![
+        (Cbor::Unsigned(3), Cbor::Bytes(xpub.public_key.serialize().to_vec())),
+        (Cbor::Unsigned(4), Cbor::Bytes(xpub.chain_code.to_bytes().to_vec())),
+    ];
+    if xpub.network == NetworkKind::Test {
+        let coin_info = Cbor::Map(vec![(Cbor::Unsigned(2), Cbor::Unsigned(1))]);
+        entries.push((Cbor::Unsigned(5), Cbor::tagged(TAG_COIN_INFO, coin_info)));
+    }
+    if let Some((fingerprint, path)) = &xkey.origin {
+        let components = path
+            .into_iter()
+            .flat_map(|child| match child {
+                ChildNumber::Normal { index } => [Cbor::Unsigned(*index as u64), Cbor::Bool(false)],
+                ChildNumber::Hardened { index } => [Cbor::Unsigned(*index as u64), Cbor::Bool(true)],
+            })
+            .collect();
+        let origin = Cbor::Map(vec![
+            (Cbor::Unsigned(1), Cbor::Array(components)),
+            (Cbor::Unsigned(2), fingerprint_to_cbor(*fingerprint)),
+            (Cbor::Unsigned(3), Cbor::Unsigned(xpub.depth as u64)),
+        ]);
+        entries.push((Cbor::Unsigned(6), Cbor::tagged(TAG_KEYPATH, origin)));
+    }
+    if xpub.parent_fingerprint != Fingerprint::default() {
+        entries.push((Cbor::Unsigned(8), fingerprint_to_cbor(xpub.parent_fingerprint)));
+    }
+    Ok(Cbor::tagged(TAG_HDKEY, Cbor::Map(entries)))
+}
+
+fn fingerprint_to_cbor(fingerprint: Fingerprint) -> Cbor {
+    Cbor::Unsigned(u32::from_be_bytes(fingerprint.to_bytes()) as u64)
+}
+
+fn fingerprint_from_cbor(cbor: &Cbor) -> Result<Fingerprint, BdkError> {
+    cbor.as_unsigned()
+        .and_then(|fingerprint| u32::try_from(fingerprint).ok())
+        .map(|fingerprint| Fingerprint::from(fingerprint.to_be_bytes()))
+        .ok_or_else(|| invalid("invalid fingerprint"))
+}
+
+fn parse_output(cbor: &Cbor) -> Result<Descriptor<DescriptorPublicKey>, BdkError> {
+    let descriptor = parse_script(cbor, 0)?;
+    Descriptor::from_str(&descriptor).map_err(|e| invalid(format!("invalid output descriptor: {e}")))
+}
+
+/// The descriptor expression of a script expression, the nesting being checked when parsing the result.
+fn parse_script(cbor: &Cbor, depth: usize) -> Result<String, BdkError> {
+    let (tag, inner) = cbor
+        .as_tag()
+        .filter(|_| depth < 3)
+        .ok_or_else(|| invalid("invalid script expression"))?;
+    let script = match tag {
+        TAG_SH => format!("sh({})", parse_script(inner, depth + 1)?),
+        TAG_WSH => format!("wsh({})", parse_script(inner, depth + 1)?),
+        TAG_PK => format!("pk({})", parse_key(inner)?),
+        TAG_PKH => format!("pkh({})", parse_key(inner)?),
+        TAG_WPKH => format!("wpkh({})", parse_key(inner)?),
+        TAG_TR => format!("tr({})", parse_key(inner)?),
+        TAG_MULTI | TAG_SORTED_MULTI => {
+            let threshold = inner
+                .get(1)
+                .and_then(Cbor::as_unsigned)
+                .ok_or_else(|| invalid("the multisig has no threshold"))?;
+            let keys = inner
+                .get(2)
+                .and_then(Cbor::as_array)
+                .ok_or_else(|| invalid("the multisig has no keys"))?
+                .iter()
+                .map(parse_key)
+                .collect::<Result<Vec<_>, _>>()?;
+            let name = if tag == TAG_MULTI { "multi" } else { "sortedmulti" };
+            format!("{name}({threshold},{})", keys.join(","))
+        }
+        _ => return Err(invalid(format!("unsupported script expression with tag {tag}"))),
+    };
+    Ok(script)
+}
+
+/// The descriptor key of a `crypto-hdkey`, deriving the receive and change addresses with `<0;1>/*`.
+fn parse_key(cbor: &Cbor) -> Result<String, BdkError> {
+    let hdkey = match cbor.as_tag() {
+        Some((TAG_HDKEY, hdkey)) => hdkey,
+        _ => return Err(invalid("only extended public keys are supported")),
+    };
+    if hdkey.get(2).and_then(Cbor::as_bool) == Some(true) {
+        return Err(invalid("private keys are not supported"));
+    }
+    let public_key = hdkey
+        .get(3)
+        .and_then(Cbor::as_bytes)
+        .and_then(|bytes| PublicKey::from_slice(bytes).ok())
+        .ok_or_else(|| invalid("invalid public key"))?;
+    let chain_code = hdkey
+        .get(4)
+        .and_then(Cbor::as_bytes)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(ChainCode::from)
+        .ok_or_else(|| invalid("invalid chain code"))?;
+    let network = match hdkey.get(5).and_then(Cbor::as_tag) {
+        Some((TAG_COIN_INFO, info)) if info.get(2).and_then(Cbor::as_unsigned).unwrap_or(0) != 0 => NetworkKind::Test,
+        _ => NetworkKind::Main,
+    };
+
+    let (mut path, mut source, mut depth) = (Vec::new(), None, None);
+    if let Some((TAG_KEYPATH, origin)) = hdkey.get(6).and_then(Cbor::as_tag) {
+        path = parse_components(origin)?;
+        source = origin.get(2).map(fingerprint_from_cbor).transpose()?;
+        depth = origin.get(3).and_then(Cbor::as_unsigned);
+    }
+    // Keys are exchanged at the account level, or with the path of the receive addresses.
+    if let Some((TAG_KEYPATH, children)) = hdkey.get(7).and_then(Cbor::as_tag) {
+        let wildcard = Cbor::Array(vec![]);
+        let receive = [Cbor::Unsigned(0), Cbor::Bool(false), wildcard, Cbor::Bool(false)];
+        if children.get(1).and_then(Cbor::as_array) != Some(&receive[..]) {
+            return Err(invalid("only keys deriving their addresses with 0/* are supported"));
+        }
+    }
+
+    let xpub = Xpub {
+        network,
+        depth: depth
+            .unwrap_or(path.len() as u64)
+            .try_into()
+            .map_err(|_| invalid("invalid depth"))?,
+        parent_fingerprint: hdkey.get(8).map(fingerprint_from_cbor).transpose()?.unwrap_or_default(),
+        child_number: path.last().copied().unwrap_or(ChildNumber::Normal { index: 0 }),
+        chain_code,
+        public_key,
+    };
+    let key = DescriptorPublicKey::MultiXPub(DescriptorMultiXKey {
+        origin: source.map(|fingerprint| (fingerprint, DerivationPath::from(path))),
+        xkey: xpub,
+        derivation_paths: account_paths(),
+        wildcard: Wildcard::Unhardened,
+    });
+    Ok(key.to_string())
+}
+
+fn parse_components(keypath: &Cbor) -> Result<Vec<ChildNumber>, BdkError> {
+    let components = keypath
+        .get(1)
+        .and_then(Cbor::as_array)
+        .ok_or_else(|| invalid("the key path has no components"))?;
+    if components.len() % 2 != 0 {
+        return Err(invalid("invalid key path components"));
+    }
+    components
+        .chunks(2)
+        .map(|component| {
+            let index = component[0]
+                .as_unsigned()
+                .and_then(|index| u32::try_from(index).ok())
+                .ok_or_else(|| invalid("only fixed indexes are supported in the key origin"))?;
+            let child = match component[1].as_bool() {
+                Some(false) => ChildNumber::from_normal_idx(index),
+                Some(true) => ChildNumber::from_hardened_idx(index),
+                None => return Err(invalid("invalid key path components")),
+            };
+            child.map_err(|_| invalid("invalid child index"))
+        })
+        .collect()
+}
@@ -1,48 +1,158 @@
-use bdk_wallet::Wallet as BdkWallet;
+use std::{cell::RefCell, rc::Rc, str::FromStr};
+
+use bdk_core::{BlockId as BdkBlockId, CheckPoint as BdkCheckPoint, ConfirmationBlockTime};
+use bdk_wallet::{
+    bitcoin::{bip32::Fingerprint, Transaction as BdkTransaction, Txid},
+    miniscript::ForEachKey,
+    serde_json::json,
+    AddressInfo as BdkAddressInfo, KeychainKind as BdkKeychainKind, SignOptions, Update as BdkUpdate,
+    Wallet as BdkWallet,
+};
 use js_sys::Date;
 use serde_wasm_bindgen::to_value;
-use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 use crate::{
     result::JsResult,
     types::{
-        AddressInfo, Balance, ChangeSet, CheckPoint, DescriptorPair, FullScanRequest, KeychainKind, Network,
-        SyncRequest, Update,
+        Address, AddressInfo, Amount, Balance, BdkError, BdkErrorCode, Birthday, ChangeSet, CheckPoint, DescriptorPair,
+        ExternalSigner, FeeRate, FullScanRequest, KeychainKind, LabelType, Network, OutPoint, Psbt,
+        ScanProgressCallback, SignatureFormat, SyncRequest, Transaction, TxOut, Update,
     },
 };
 
+use super::{normalize_label_reference, LabelKind, LabelRecord, Labels, TxBuilder};
+#[cfg(feature = "esplora")]
+use super::{sweep::SweepWallets, EsploraClient};
+#[cfg(feature = "esplora")]
+use bdk_wallet::bitcoin::Sequence;
+
 #[wasm_bindgen]
 pub struct Wallet {
-    wallet: BdkWallet,
+    wallet: Rc<RefCell<BdkWallet>>,
+    external_signers: Vec<(Fingerprint, ExternalSigner)>,
+    labels: Labels,
+    staged_labels: Labels,
+    birthday: Option<ConfirmationBlockTime>,
+    staged_birthday: Option<ConfirmationBlockTime>,
 }
 
 #[wasm_bindgen]
 impl Wallet {
     pub fn create(network: Network, descriptors: DescriptorPair) -> JsResult<Wallet> {
         let wallet = BdkWallet::create(descriptors.external(), descriptors.internal())
-            .network(network.into())
+            .network(network.try_into()?)
             .create_wallet_no_persist()?;
 
-        Ok(Wallet { wallet })
+        Ok(Wallet {
+            wallet: Rc::new(RefCell::new(wallet)),
+            external_signers: vec![],
+            labels: Labels::default(),
+            staged_labels: Labels::default(),
+            birthday: None,
+            staged_birthday: None,
+        })
+    }
+
+    /// Create a wallet whose history starts after `birthday`, seeding its checkpoint with the birthday block.
+    ///
+    /// Full scans skip the transactions confirmed at or before the birthday, the Esplora client stops paging the
+    /// history of a script once it reaches them. Scans connect their chain updates to the birthday block, and the
+    /// exports use it as the rescan start. The birthday is kept in the changeset, see [`Wallet::birthday`].
+    pub fn create_with_birthday(network: Network, descriptors: DescriptorPair, birthday: Birthday) -> JsResult<Wallet> {
+        let mut wallet = Wallet::create(network, descriptors)?;
+        let birthday = ConfirmationBlockTime::from(birthday);
+        {
+            let mut inner = wallet.wallet.borrow_mut();
+            let genesis_hash = inner.local_chain().genesis_hash();
+            if birthday.block_id.height == 0 {
+                if birthday.block_id.hash != genesis_hash {
+                    return Err(BdkError::new(
+                        BdkErrorCode::NetworkMismatch,
+                        "the birthday block at height 0 is not the genesis block of the network",
+                    ));
+                }
+            } else {
+                let genesis = BdkBlockId {
+                    height: 0,
+                    hash: genesis_hash,
+                };
+                let update = BdkUpdate {
+                    chain: Some(BdkCheckPoint::new(genesis).insert(birthday.block_id)),
+                    ..Default::default()
+                };
+                // The update has no transactions to be seen at some time.
+                inner.apply_update_at(update, 0)?;
+            }
+        }
+        wallet.birthday = Some(birthday);
+        wallet.staged_birthday = Some(birthday);
+        Ok(wallet)
+    }
+
+    pub fn create_from_multipath(network: Network, descriptor: &str) -> JsResult<Wallet> {
+        Wallet::create(network, DescriptorPair::from_multipath(descriptor)?)
     }
 
     pub fn load(changeset: ChangeSet) -> JsResult<Wallet> {
-        let wallet_opt = BdkWallet::load().load_wallet_no_persist(changeset.into())?;
+        let (changeset, label_changes, birthday) = changeset.into_parts();
+        let wallet_opt = BdkWallet::load().load_wallet_no_persist(changeset)?;
 
         let wallet = match wallet_opt {
             Some(wallet) => wallet,
-            None => return Err(JsError::new("Failed to load wallet, check the changeset")),
+            None => {
+                return Err(BdkError::new(
+                    BdkErrorCode::LoadWallet,
+                    "Failed to load wallet, check the changeset",
+                ))
+            }
         };
 
-        Ok(Wallet { wallet })
+        let mut labels = Labels::default();
+        labels.apply(label_changes);
+        Ok(Wallet {
+            wallet: Rc::new(RefCell::new(wallet)),
+            external_signers: vec![],
+            labels,
+            staged_labels: Labels::default(),
+            birthday,
+            staged_birthday: None,
+        })
+    }
+
+    /// The birthday the wallet was created with, if any.
+    pub fn birthday(&self) -> Option<Birthday> {
+        self.birthday.map(Into::into)
     }
 
+    /// Start a full scan of the wallet's keychains, skipping the history before the birthday of the wallet.
     pub fn start_full_scan(&self) -> FullScanRequest {
-        self.wallet.start_full_scan().build().into()
+        FullScanRequest::from(self.wallet.borrow().start_full_scan().build()).with_birthday(self.birthday_height())
     }
 
     pub fn start_sync_with_revealed_spks(&self) -> SyncRequest {
-        self.wallet.start_sync_with_revealed_spks().build().into()
+        self.wallet.borrow().start_sync_with_revealed_spks().build().into()
+    }
+
+    /// Like [`Wallet::start_full_scan`], calling `progress` for every script the chain source checks.
+    pub fn start_full_scan_with_progress(&self, progress: ScanProgressCallback) -> FullScanRequest {
+        let request = self
+            .wallet
+            .borrow()
+            .start_full_scan()
+            .inspect(progress.full_scan_inspector())
+            .build();
+        FullScanRequest::from(request).with_birthday(self.birthday_height())
+    }
+
+    /// Like [`Wallet::start_sync_with_revealed_spks`], calling `progress` for every script the chain source checks.
+    pub fn start_sync_with_progress(&self, progress: ScanProgressCallback) -> SyncRequest {
+        self.wallet
+            .borrow()
+            .start_sync_with_revealed_spks()
+            .inspect(progress.sync_inspector())
+            .build()
+            .into()
     }
 
     pub fn apply_update(&mut self, update: Update) -> JsResult<()> {
@@ -50,67 +160,406 @@ impl Wallet {
     }
 
     pub fn apply_update_at(&mut self, update: Update, seen_at: u64) -> JsResult<()> {
-        self.wallet.apply_update_at(update, seen_at)?;
+        self.wallet.borrow_mut().apply_update_at(update, seen_at)?;
         Ok(())
     }
 
     pub fn network(&self) -> Network {
-        self.wallet.network().into()
+        self.wallet.borrow().network().into()
     }
 
     pub fn balance(&self) -> Balance {
-        self.wallet.balance().into()
+        self.wallet.borrow().balance().into()
     }
 
-    pub fn next_unused_address(&mut self, keychain: KeychainKind) -> AddressInfo {
-        self.wallet.next_unused_address(keychain.into()).into()
+    pub fn next_unused_address(&mut self, keychain: KeychainKind) -> JsResult<AddressInfo> {
+        let address = self.wallet.borrow_mut().next_unused_address(keychain.try_into()?);
+        Ok(self.address_info(address))
     }
 
-    pub fn peek_address(&self, keychain: KeychainKind, index: u32) -> AddressInfo {
-        self.wallet.peek_address(keychain.into(), index).into()
+    pub fn peek_address(&self, keychain: KeychainKind, index: u32) -> JsResult<AddressInfo> {
+        let address = self.wallet.borrow().peek_address(keychain.try_into()?, index);
+        Ok(self.address_info(address))
     }
 
-    pub fn reveal_next_address(&mut self, keychain: KeychainKind) -> AddressInfo {
-        self.wallet.reveal_next_address(keychain.into()).into()
+    pub fn reveal_next_address(&mut self, keychain: KeychainKind) -> JsResult<AddressInfo> {
+        let address = self.wallet.borrow_mut().reveal_next_address(keychain.try_into()?);
+        Ok(self.address_info(address))
     }
 
-    pub fn reveal_addresses_to(&mut self, keychain: KeychainKind, index: u32) -> Vec<AddressInfo> {
-        self.wallet
-            .reveal_addresses_to(keychain.into(), index)
-            .map(Into::into)
-            .collect()
+    pub fn reveal_addresses_to(&mut self, keychain: KeychainKind, index: u32) -> JsResult<Vec<AddressInfo>> {
+        let addresses = self
+            .wallet
+            .borrow_mut()
+            .reveal_addresses_to(keychain.try_into()?, index)
+            .collect::<Vec<_>>();
+        Ok(addresses
+            .into_iter()
+            .map(|address| self.address_info(address))
+            .collect())
     }
 
-    pub fn list_unused_addresses(&self, keychain: KeychainKind) -> Vec<AddressInfo> {
-        self.wallet
-            .list_unused_addresses(keychain.into())
-            .map(Into::into)
-            .collect()
+    pub fn list_unused_addresses(&self, keychain: KeychainKind) -> JsResult<Vec<AddressInfo>> {
+        Ok(self
+            .wallet
+            .borrow()
+            .list_unused_addresses(keychain.try_into()?)
+            .map(|address| self.address_info(address))
+            .collect())
     }
 
     pub fn list_unspent(&self) -> JsResult<Vec<JsValue>> {
         self.wallet
+            .borrow()
             .list_unspent()
             .map(|output| to_value(&output).map_err(Into::into))
             .collect()
     }
 
-    pub fn transactions(&self) -> JsResult<Vec<JsValue>> {
+    /// The transactions relevant to the wallet, with their BIP329 label.
+    pub fn transactions(&self) -> Vec<Transaction> {
         self.wallet
+            .borrow()
             .transactions()
-            .map(|tx| to_value(&tx.tx_node.tx).map_err(Into::into))
+            .map(|tx| {
+                let label = self
+                    .labels
+                    .get(LabelKind::Tx, &tx.tx_node.txid.to_string())
+                    .and_then(|record| record.label.clone());
+                Transaction::from(tx.tx_node.tx.as_ref().clone()).with_label(label)
+            })
             .collect()
     }
 
+    /// Insert an unconfirmed transaction relevant to the wallet, seen now in the mempool.
+    pub fn insert_tx(&mut self, tx: Transaction) {
+        self.insert_tx_at(tx, (Date::now() / 1000.0) as u64)
+    }
+
+    /// Insert an unconfirmed transaction relevant to the wallet, last seen at `seen_at` (UNIX seconds).
+    ///
+    /// Irrelevant transactions are ignored.
+    pub fn insert_tx_at(&mut self, tx: Transaction, seen_at: u64) {
+        let tx: BdkTransaction = tx.into();
+        self.wallet.borrow_mut().apply_unconfirmed_txs([(tx, seen_at)]);
+    }
+
+    /// Inserts a [`TxOut`] at [`OutPoint`] into the wallet's transaction graph.
+    ///
+    /// This is used for providing a previous output's value so that we can use [`Wallet::calculate_fee`]
+    /// on transactions spending outputs the wallet does not own. Only insert `TxOut`s that you trust
+    /// the values for!
+    pub fn insert_txout(&mut self, outpoint: OutPoint, txout: TxOut) {
+        self.wallet.borrow_mut().insert_txout(outpoint.into(), txout.into());
+    }
+
+    /// Calculates the fee of a given transaction.
+    ///
+    /// Errors if the previous output of an input is unknown, see [`Wallet::insert_txout`].
+    pub fn calculate_fee(&self, tx: &Transaction) -> JsResult<Amount> {
+        let fee = self.wallet.borrow().calculate_fee(tx)?;
+        Ok(fee.into())
+    }
+
+    /// Calculate the [`FeeRate`] for a given transaction.
+    ///
+    /// Errors if the previous output of an input is unknown, see [`Wallet::insert_txout`].
+    pub fn calculate_fee_rate(&self, tx: &Transaction) -> JsResult<FeeRate> {
+        let fee_rate = self.wallet.borrow().calculate_fee_rate(tx)?;
+        Ok(fee_rate.into())
+    }
+
     pub fn latest_checkpoint(&self) -> CheckPoint {
-        self.wallet.latest_checkpoint().into()
+        self.wallet.borrow().latest_checkpoint().into()
     }
 
+    /// Take the changes to the wallet, to its labels and its birthday since the last call, to persist them.
     pub fn take_staged(&mut self) -> Option<ChangeSet> {
-        self.wallet.take_staged().map(Into::into)
+        let changeset = self.wallet.borrow_mut().take_staged();
+        let labels = std::mem::take(&mut self.staged_labels);
+        let birthday = self.staged_birthday.take();
+        if changeset.is_none() && labels.is_empty() && birthday.is_none() {
+            return None;
+        }
+        Some(ChangeSet::new(changeset.unwrap_or_default(), labels, birthday))
+    }
+
+    /// The BIP329 label of an object, like the txid of a transaction of [`Wallet::transactions`].
+    ///
+    /// Errors if the reference is not valid for the type of label.
+    pub fn label(&self, label_type: LabelType, reference: &str) -> JsResult<Option<String>> {
+        let kind = label_type.try_into()?;
+        let reference = normalize_label_reference(kind, reference, self.wallet.borrow().network())?;
+        Ok(self
+            .labels
+            .get(kind, &reference)
+            .and_then(|record| record.label.clone()))
+    }
+
+    /// Set or remove the BIP329 label of an object, keeping its origin and spendable flag.
+    ///
+    /// The change is staged, see [`Wallet::take_staged`]. Errors if the reference is not valid for the type of
+    /// label.
+    pub fn set_label(&mut self, label_type: LabelType, reference: &str, label: Option<String>) -> JsResult<()> {
+        let kind = label_type.try_into()?;
+        let reference = normalize_label_reference(kind, reference, self.wallet.borrow().network())?;
+        let mut record = self.labels.get(kind, &reference).cloned().unwrap_or(LabelRecord {
+            kind,
+            reference,
+            label: None,
+            origin: None,
+            spendable: None,
+        });
+        record.label = label.filter(|label| !label.is_empty());
+        self.stage_labels(Labels::from_records([record]));
+        Ok(())
+    }
+
+    /// Import BIP329 labels from JSON lines, replacing the labels of the same objects.
+    ///
+    /// Records of unknown types are skipped. Nothing is imported if a record is invalid. The changes are staged,
+    /// see [`Wallet::take_staged`].
+    pub fn import_labels(&mut self, jsonl: &str) -> JsResult<()> {
+        let labels = Labels::from_jsonl(jsonl, self.wallet.borrow().network())?;
+        self.stage_labels(labels);
+        Ok(())
+    }
+
+    /// Export the BIP329 labels of the wallet as JSON lines.
+    pub fn export_labels(&self) -> JsResult<String> {
+        self.labels.to_jsonl()
+    }
+
+    pub fn public_descriptor(&self, keychain: KeychainKind) -> JsResult<String> {
+        Ok(self.wallet.borrow().public_descriptor(keychain.try_into()?).to_string())
+    }
+
+    pub fn public_multipath_descriptor(&self) -> JsResult<String> {
+        let descriptors = DescriptorPair::new(
+            self.public_descriptor(KeychainKind::External)?,
+            self.public_descriptor(KeychainKind::Internal)?,
+        );
+        descriptors.to_multipath()
     }
 
-    pub fn public_descriptor(&self, keychain: KeychainKind) -> String {
-        self.wallet.public_descriptor(keychain.into()).to_string()
+    /// Export the wallet's public descriptors in the FullyNoded JSON format, read by BDK based wallets.
+    ///
+    /// The block height to rescan from is the height of the birthday of the wallet or of its earliest confirmed
+    /// transaction, whichever is lower, 0 if there is neither. Errors if Bitcoin Core does not support the
+    /// descriptors, or if the internal descriptor does not derive with `/1/*` where the external one derives
+    /// with `/0/*`.
+    pub fn export_fully_noded(&self, label: &str) -> JsResult<String> {
+        let export = super::export_fully_noded(&self.wallet.borrow(), self.birthday, label)?;
+        Ok(export.to_string())
+    }
+
+    /// The JSON request of the Bitcoin Core `importdescriptors` RPC, watching the wallet's public descriptors.
+    ///
+    /// Core rescans from the time of the birthday block of the wallet or of the block of its earliest confirmed
+    /// transaction, whichever is lower, or from the genesis block if there is neither. Only descriptors with a
+    /// wildcard are imported as active, to derive new addresses.
+    pub fn export_core_descriptors(&self) -> String {
+        super::export_core_descriptors(&self.wallet.borrow(), self.birthday).to_string()
+    }
+
+    /// Export the wallet's extended public keys as a watch-only Electrum wallet file, also imported by Sparrow.
+    ///
+    /// Errors if the wallet is not a single key P2PKH, P2WPKH or P2SH-P2WPKH wallet, or a `sortedmulti`
+    /// wallet, deriving its addresses with `<0;1>/*`.
+    pub fn export_electrum(&self) -> JsResult<String> {
+        let file = super::export_electrum(&self.wallet.borrow())?;
+        Ok(file.to_string())
+    }
+
+    pub fn build_tx(&self) -> TxBuilder {
+        TxBuilder::new(self.wallet.clone(), None)
+    }
+
+    /// Bump the fee of a transaction previously created with this wallet.
+    ///
+    /// Returns an error if `txid` is not a valid transaction id. Other errors, like a transaction that
+    /// is not in the wallet or already confirmed, are returned by [`TxBuilder::finish`].
+    pub fn build_fee_bump(&self, txid: &str) -> JsResult<TxBuilder> {
+        let txid = Txid::from_str(txid).map_err(|e| BdkError::new(BdkErrorCode::InvalidValue, e))?;
+        Ok(TxBuilder::new(self.wallet.clone(), Some(txid)))
+    }
+
+    /// Sign a transaction with all the wallet's signers, finalizing the inputs that are complete.
+    ///
+    /// Returns whether the PSBT is now finalized.
+    pub fn sign(&self, psbt: &mut Psbt) -> JsResult<bool> {
+        let finalized = self.wallet.borrow().sign(psbt, SignOptions::default())?;
+        Ok(finalized)
+    }
+
+    /// Prepare a PSBT to be signed by an offline signer, adding the derivations, previous transactions and
+    /// extended public keys it needs to verify the transaction.
+    pub fn export_psbt(&self, psbt: &Psbt) -> JsResult<Psbt> {
+        let psbt = super::export_psbt(&self.wallet.borrow(), psbt)?;
+        Ok(psbt.into())
+    }
+
+    /// Finalize a PSBT signed by an offline signer, after checking that it matches the exported PSBT.
+    ///
+    /// Errors if the signed PSBT is for a different transaction, changes or adds previous outputs, or signs with
+    /// sighash types other than `ALL` that the exported PSBT did not ask for, if it is missing signatures, or if
+    /// its signatures are invalid. Returns the finalized PSBT.
+    pub fn import_signed_psbt(&self, exported: &Psbt, signed: &Psbt) -> JsResult<Psbt> {
+        let psbt = super::import_signed_psbt(&self.wallet.borrow(), exported, signed)?;
+        Ok(psbt.into())
+    }
+
+    /// Sign a message with the key of one of the wallet's addresses, proving ownership of the address.
+    ///
+    /// BIP322 simple and full signatures are supported for P2WPKH and P2TR addresses, and full signatures
+    /// for P2PKH addresses. Legacy BIP137 signatures are only supported for P2PKH addresses. Other address
+    /// types, like P2SH-P2WPKH or P2WSH, fail with [`BdkErrorCode::UnsupportedAddressType`]. Returns the base64
+    /// encoded signature.
+    pub fn sign_message(&self, address: &Address, message: &str, format: SignatureFormat) -> JsResult<String> {
+        let signature = super::sign_message(&self.wallet.borrow(), address, message, format.try_into()?)?;
+        Ok(signature)
+    }
+
+    /// Register a signer for the keys of the wallet derived from the master key with `fingerprint`.
+    ///
+    /// Replaces the signer previously registered for the same fingerprint. Errors if no key of the wallet's
+    /// descriptors has this fingerprint.
+    pub fn add_external_signer(&mut self, fingerprint: &str, signer: ExternalSigner) -> JsResult<()> {
+        let fingerprint = Fingerprint::from_str(fingerprint).map_err(|e| BdkError::new(BdkErrorCode::InvalidKey, e))?;
+        let wallet = self.wallet.borrow();
+        let has_key = [BdkKeychainKind::External, BdkKeychainKind::Internal]
+            .into_iter()
+            .any(|keychain| {
+                wallet
+                    .public_descriptor(keychain)
+                    .for_any_key(|key| key.master_fingerprint() == fingerprint)
+            });
+        if !has_key {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidKey,
+                format!("the wallet has no key with fingerprint {fingerprint}"),
+            ));
+        }
+
+        self.external_signers
+            .retain(|(registered, _)| *registered != fingerprint);
+        self.external_signers.push((fingerprint, signer));
+        Ok(())
+    }
+
+    /// Sign a transaction with the wallet's signers and its external signers, then finalize the inputs that
+    /// are complete.
+    ///
+    /// External signers are only called if an input that is not complete yet is missing the signature of
+    /// their key, in the order they were registered. Inputs whose number of required signatures is unknown,
+    /// like `and_v(v:pk(A),pk(B))`, are complete once finalized. Returns the signed PSBT, use
+    /// [`Psbt::signature_status`] to know whether it is finalized.
+    ///
+    /// The PSBT returned by an external signer must be for the same transaction and must not change the
+    /// sighash types nor sign with other sighash types than requested, otherwise the signer's error is thrown.
+    pub async fn sign_with_external_signers(&self, psbt: &Psbt) -> JsResult<Psbt> {
+        let mut psbt = psbt.clone();
+        let sign_options = SignOptions {
+            try_finalize: false,
+            ..Default::default()
+        };
+        self.wallet.borrow().sign(&mut psbt, sign_options)?;
+
+        for (fingerprint, signer) in &self.external_signers {
+            let fingerprint = fingerprint.to_string();
+            let needs_signature = psbt.signature_status().iter().any(|status| {
+                status.remaining() != Some(0)
+                    && status
+                        .missing()
+                        .iter()
+                        .any(|key| key.fingerprint().as_ref() == Some(&fingerprint))
+            });
+            if needs_signature {
+                let signer_error = |e: BdkError| {
+                    BdkError::new(BdkErrorCode::ExternalSigner, e.message())
+                        .with_data(json!({ "fingerprint": fingerprint }))
+                };
+                let signed = signer.sign(&psbt).await.map_err(signer_error)?;
+                super::check_sighashes(&psbt, &signed)
+                    .and_then(|()| psbt.combine(&signed))
+                    .map_err(signer_error)?;
+            }
+        }
+
+        self.wallet.borrow().finalize_psbt(&mut psbt, SignOptions::default())?;
+        Ok(psbt)
+    }
+}
+
+impl Wallet {
+    fn address_info(&self, address: BdkAddressInfo) -> AddressInfo {
+        let label = self
+            .labels
+            .get(LabelKind::Addr, &address.address.to_string())
+            .and_then(|record| record.label.clone());
+        AddressInfo::from(address).with_label(label)
+    }
+
+    fn birthday_height(&self) -> Option<u32> {
+        self.birthday.map(|birthday| birthday.block_id.height)
+    }
+
+    fn stage_labels(&mut self, labels: Labels) {
+        self.labels.apply(labels.clone());
+        self.staged_labels.merge(labels);
+    }
+}
+
+#[cfg(feature = "esplora")]
+#[wasm_bindgen]
+impl Wallet {
+    /// Sweep the funds of a private key, like a paper wallet, to a fresh internal address of the wallet.
+    ///
+    /// `wif_or_descriptor` is a private key in the WIF format, whose P2PKH, P2WPKH, P2SH-P2WPKH and P2TR
+    /// scripts are scanned, or a descriptor with private keys. The scripts are scanned through `client` and
+    /// all their funds are spent to the wallet at `fee_rate`.
+    ///
+    /// Returns the signed and finalized [`Psbt`], ready to be extracted and broadcast. The private keys are
+    /// only kept in memory for the duration of the call, they are never added to the wallet.
+    pub async fn build_sweep(
+        &mut self,
+        wif_or_descriptor: &str,
+        client: &EsploraClient,
+        fee_rate: FeeRate,
+    ) -> JsResult<Psbt> {
+        let network = self.wallet.borrow().network();
+        let mut sweep = SweepWallets::new(wif_or_descriptor, network)?;
+        sweep.scan(client, (Date::now() / 1000.0) as u64).await?;
+
+        let utxos = sweep.utxos()?;
+        if utxos.is_empty() {
+            return Err(BdkError::new(BdkErrorCode::NothingToSweep, "no funds found to sweep"));
+        }
+
+        let mut wallet = self.wallet.borrow_mut();
+        let address = wallet.next_unused_address(BdkKeychainKind::Internal);
+        let mut builder = wallet.build_tx();
+        builder
+            .manually_selected_only()
+            .drain_to(address.script_pubkey())
+            .fee_rate(fee_rate.into());
+        for utxo in utxos {
+            // Signal RBF, a final sequence on all the inputs would disable the anti-fee-sniping lock time.
+            builder.add_foreign_utxo_with_sequence(
+                utxo.outpoint,
+                utxo.psbt_input,
+                utxo.satisfaction_weight,
+                Sequence::ENABLE_RBF_NO_LOCKTIME,
+            )?;
+        }
+        let mut psbt = builder.finish()?;
+
+        if !sweep.sign(&mut psbt)? {
+            return Err(BdkError::new(
+                BdkErrorCode::Sign,
+                "the swept inputs could not all be signed and finalized",
+            ));
+        }
+        Ok(psbt.into())
     }
 }
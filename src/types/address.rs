@@ -1,15 +1,21 @@
-use std::ops::Deref;
+use std::{ops::Deref, str::FromStr};
 
-use bdk_wallet::{bitcoin::AddressType as BdkAddressType, AddressInfo as BdkAddressInfo};
+use bdk_wallet::{
+    bitcoin::{Address as BdkAddress, AddressType as BdkAddressType},
+    AddressInfo as BdkAddressInfo,
+};
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use super::KeychainKind;
+use crate::result::JsResult;
+
+use super::{BdkError, BdkErrorCode, KeychainKind, Network, ScriptBuf};
 
 /// A derived address and the index it was found at.
 #[wasm_bindgen]
 #[derive(Debug)]
 pub struct AddressInfo {
     address: BdkAddressInfo,
+    label: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -39,7 +45,20 @@ impl AddressInfo {
     /// None if unknown, non-standard or related to the future witness version.
     #[wasm_bindgen(getter)]
     pub fn address_type(&self) -> Option<AddressType> {
-        self.address.address_type().map(Into::into)
+        self.address.address_type().and_then(|t| t.try_into().ok())
+    }
+
+    /// The BIP329 label of the address, when the address is returned by a [`Wallet`].
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+}
+
+impl AddressInfo {
+    pub(crate) fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
     }
 }
 
@@ -53,13 +72,71 @@ impl Deref for AddressInfo {
 
 impl From<BdkAddressInfo> for AddressInfo {
     fn from(address: BdkAddressInfo) -> Self {
-        AddressInfo { address }
+        AddressInfo { address, label: None }
+    }
+}
+
+/// A Bitcoin address, validated for a network.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    address: BdkAddress,
+}
+
+#[wasm_bindgen]
+impl Address {
+    /// Parse an address and check that it is valid for the given network.
+    pub fn from_string(address: &str, network: Network) -> JsResult<Address> {
+        let address = BdkAddress::from_str(address)?.require_network(network.try_into()?)?;
+        Ok(Address { address })
+    }
+
+    /// Gets the address type of the address.
+    ///
+    /// # Returns
+    ///
+    /// None if unknown, non-standard or related to the future witness version.
+    #[wasm_bindgen(getter)]
+    pub fn address_type(&self) -> Option<AddressType> {
+        self.address.address_type().and_then(|t| t.try_into().ok())
+    }
+
+    /// The script pubkey paying to this address.
+    #[wasm_bindgen(getter)]
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        self.address.script_pubkey().into()
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string(&self) -> String {
+        self.address.to_string()
+    }
+}
+
+impl Deref for Address {
+    type Target = BdkAddress;
+
+    fn deref(&self) -> &Self::Target {
+        &self.address
+    }
+}
+
+impl From<BdkAddress> for Address {
+    fn from(address: BdkAddress) -> Self {
+        Address { address }
+    }
+}
+
+impl From<Address> for BdkAddress {
+    fn from(address: Address) -> Self {
+        address.address
     }
 }
 
 /// The different types of addresses.
 #[wasm_bindgen]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressType {
     /// Pay to pubkey hash.
     P2pkh = "p2pkh",
@@ -73,28 +150,35 @@ pub enum AddressType {
     P2tr = "p2tr",
 }
 
-impl From<BdkAddressType> for AddressType {
-    fn from(address_type: BdkAddressType) -> Self {
+impl TryFrom<BdkAddressType> for AddressType {
+    type Error = BdkError;
+
+    fn try_from(address_type: BdkAddressType) -> Result<Self, Self::Error> {
         match address_type {
-            BdkAddressType::P2pkh => AddressType::P2pkh,
-            BdkAddressType::P2sh => AddressType::P2sh,
-            BdkAddressType::P2wpkh => AddressType::P2wpkh,
-            BdkAddressType::P2wsh => AddressType::P2wsh,
-            BdkAddressType::P2tr => AddressType::P2tr,
-            _ => panic!("Unsupported address type"),
+            BdkAddressType::P2pkh => Ok(AddressType::P2pkh),
+            BdkAddressType::P2sh => Ok(AddressType::P2sh),
+            BdkAddressType::P2wpkh => Ok(AddressType::P2wpkh),
+            BdkAddressType::P2wsh => Ok(AddressType::P2wsh),
+            BdkAddressType::P2tr => Ok(AddressType::P2tr),
+            _ => Err(BdkError::new(
+                BdkErrorCode::UnsupportedAddressType,
+                format!("Unsupported address type {address_type}"),
+            )),
         }
     }
 }
 
-impl From<AddressType> for BdkAddressType {
-    fn from(address_type: AddressType) -> Self {
+impl TryFrom<AddressType> for BdkAddressType {
+    type Error = BdkError;
+
+    fn try_from(address_type: AddressType) -> Result<Self, Self::Error> {
         match address_type {
-            AddressType::P2pkh => BdkAddressType::P2pkh,
-            AddressType::P2sh => BdkAddressType::P2sh,
-            AddressType::P2wpkh => BdkAddressType::P2wpkh,
-            AddressType::P2wsh => BdkAddressType::P2wsh,
-            AddressType::P2tr => BdkAddressType::P2tr,
-            _ => panic!("Unsupported address type"),
+            AddressType::P2pkh => Ok(BdkAddressType::P2pkh),
+            AddressType::P2sh => Ok(BdkAddressType::P2sh),
+            AddressType::P2wpkh => Ok(BdkAddressType::P2wpkh),
+            AddressType::P2wsh => Ok(BdkAddressType::P2wsh),
+            AddressType::P2tr => Ok(BdkAddressType::P2tr),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown address type")),
         }
     }
 }
@@ -1,20 +1,41 @@
-use std::ops::Deref;
+use std::{cmp::Ordering, ops::Deref};
 
 use bitcoin::{Amount as BdkAmount, Denomination as BdkDenomination};
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use crate::result::JsResult;
+
+use super::{BdkError, BdkErrorCode};
+
 /// Amount
 ///
 /// The [Amount] type can be used to express Bitcoin amounts that support
 /// arithmetic and conversion to various denominations.
 #[wasm_bindgen]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Amount {
     amount: BdkAmount,
 }
 
 #[wasm_bindgen]
 impl Amount {
+    /// Create an [`Amount`] with satoshi precision and the given number of satoshis.
+    pub fn from_sat(satoshi: u64) -> Self {
+        BdkAmount::from_sat(satoshi).into()
+    }
+
+    /// Parse a decimal string as a value in Bitcoin, e.g. `"0.00021"`.
+    pub fn from_btc_str(btc: &str) -> JsResult<Amount> {
+        Amount::from_str_in(btc, Denomination::Bitcoin)
+    }
+
+    /// Parse a decimal string as a value in the given denomination.
+    ///
+    /// The string must not contain the unit, e.g. `"21000"` in [`Denomination::Satoshi`].
+    pub fn from_str_in(s: &str, denom: Denomination) -> JsResult<Amount> {
+        Ok(BdkAmount::from_str_in(s, denom.try_into()?)?.into())
+    }
+
     /// Gets the number of satoshis in this [`Amount`].
     pub fn to_sat(&self) -> u64 {
         self.amount.to_sat()
@@ -30,8 +51,65 @@ impl Amount {
     /// Express this [Amount] as a floating-point value in the given denomination.
     ///
     /// Please be aware of the risk of using floating-point numbers.
-    pub fn to_float_in(&self, denom: Denomination) -> f64 {
-        self.amount.to_float_in(denom.into())
+    pub fn to_float_in(&self, denom: Denomination) -> JsResult<f64> {
+        Ok(self.amount.to_float_in(denom.try_into()?))
+    }
+
+    /// Format the value of this [`Amount`] in the given denomination, with or without its unit.
+    ///
+    /// Does not include a thousands separator and never uses floating-point numbers.
+    pub fn display_in(&self, denom: Denomination, with_unit: bool) -> JsResult<String> {
+        let display = self.amount.display_in(denom.try_into()?);
+        Ok(match with_unit {
+            true => display.show_denomination().to_string(),
+            false => display.to_string(),
+        })
+    }
+
+    /// Checked addition, errors if overflow occurred.
+    pub fn checked_add(&self, other: &Amount) -> JsResult<Amount> {
+        self.amount
+            .checked_add(other.amount)
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::AmountOverflow, "Amount addition overflowed"))
+    }
+
+    /// Checked subtraction, errors if the result would be negative.
+    pub fn checked_sub(&self, other: &Amount) -> JsResult<Amount> {
+        self.amount
+            .checked_sub(other.amount)
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::AmountOverflow, "Amount subtraction underflowed"))
+    }
+
+    /// Checked multiplication, errors if overflow occurred.
+    pub fn checked_mul(&self, rhs: u64) -> JsResult<Amount> {
+        self.amount
+            .checked_mul(rhs)
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::AmountOverflow, "Amount multiplication overflowed"))
+    }
+
+    /// Checked integer division, rounding towards zero. Errors if `rhs` is zero.
+    pub fn checked_div(&self, rhs: u64) -> JsResult<Amount> {
+        self.amount
+            .checked_div(rhs)
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::AmountOverflow, "Amount division by zero"))
+    }
+
+    /// Compare with another [`Amount`], returns -1, 0 or 1 so it can be used to sort amounts.
+    pub fn compare(&self, other: &Amount) -> i8 {
+        match self.amount.cmp(&other.amount) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        }
+    }
+
+    /// Whether this [`Amount`] is equal to another one.
+    pub fn equals(&self, other: &Amount) -> bool {
+        self.amount == other.amount
     }
 }
 
@@ -49,9 +127,15 @@ impl From<BdkAmount> for Amount {
     }
 }
 
+impl From<Amount> for BdkAmount {
+    fn from(amount: Amount) -> Self {
+        amount.amount
+    }
+}
+
 /// A set of denominations in which amounts can be expressed.
 #[wasm_bindgen]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Denomination {
     /// BTC
     Bitcoin = "BTC",
@@ -73,36 +157,40 @@ pub enum Denomination {
     MilliSatoshi = "msat",
 }
 
-impl From<BdkDenomination> for Denomination {
-    fn from(denom: BdkDenomination) -> Self {
+impl TryFrom<BdkDenomination> for Denomination {
+    type Error = BdkError;
+
+    fn try_from(denom: BdkDenomination) -> Result<Self, Self::Error> {
         match denom {
-            BdkDenomination::Bitcoin => Denomination::Bitcoin,
-            BdkDenomination::CentiBitcoin => Denomination::CentiBitcoin,
-            BdkDenomination::MilliBitcoin => Denomination::MilliBitcoin,
-            BdkDenomination::MicroBitcoin => Denomination::MicroBitcoin,
-            BdkDenomination::NanoBitcoin => Denomination::NanoBitcoin,
-            BdkDenomination::PicoBitcoin => Denomination::PicoBitcoin,
-            BdkDenomination::Bit => Denomination::Bit,
-            BdkDenomination::Satoshi => Denomination::Satoshi,
-            BdkDenomination::MilliSatoshi => Denomination::MilliSatoshi,
-            _ => panic!("Unsupported denomination"),
+            BdkDenomination::Bitcoin => Ok(Denomination::Bitcoin),
+            BdkDenomination::CentiBitcoin => Ok(Denomination::CentiBitcoin),
+            BdkDenomination::MilliBitcoin => Ok(Denomination::MilliBitcoin),
+            BdkDenomination::MicroBitcoin => Ok(Denomination::MicroBitcoin),
+            BdkDenomination::NanoBitcoin => Ok(Denomination::NanoBitcoin),
+            BdkDenomination::PicoBitcoin => Ok(Denomination::PicoBitcoin),
+            BdkDenomination::Bit => Ok(Denomination::Bit),
+            BdkDenomination::Satoshi => Ok(Denomination::Satoshi),
+            BdkDenomination::MilliSatoshi => Ok(Denomination::MilliSatoshi),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unsupported denomination")),
         }
     }
 }
 
-impl From<Denomination> for BdkDenomination {
-    fn from(denom: Denomination) -> Self {
+impl TryFrom<Denomination> for BdkDenomination {
+    type Error = BdkError;
+
+    fn try_from(denom: Denomination) -> Result<Self, Self::Error> {
         match denom {
-            Denomination::Bitcoin => BdkDenomination::Bitcoin,
-            Denomination::CentiBitcoin => BdkDenomination::CentiBitcoin,
-            Denomination::MilliBitcoin => BdkDenomination::MilliBitcoin,
-            Denomination::MicroBitcoin => BdkDenomination::MicroBitcoin,
-            Denomination::NanoBitcoin => BdkDenomination::NanoBitcoin,
-            Denomination::PicoBitcoin => BdkDenomination::PicoBitcoin,
-            Denomination::Bit => BdkDenomination::Bit,
-            Denomination::Satoshi => BdkDenomination::Satoshi,
-            Denomination::MilliSatoshi => BdkDenomination::MilliSatoshi,
-            _ => panic!("Unsupported denomination"),
+            Denomination::Bitcoin => Ok(BdkDenomination::Bitcoin),
+            Denomination::CentiBitcoin => Ok(BdkDenomination::CentiBitcoin),
+            Denomination::MilliBitcoin => Ok(BdkDenomination::MilliBitcoin),
+            Denomination::MicroBitcoin => Ok(BdkDenomination::MicroBitcoin),
+            Denomination::NanoBitcoin => Ok(BdkDenomination::NanoBitcoin),
+            Denomination::PicoBitcoin => Ok(BdkDenomination::PicoBitcoin),
+            Denomination::Bit => Ok(BdkDenomination::Bit),
+            Denomination::Satoshi => Ok(BdkDenomination::Satoshi),
+            Denomination::MilliSatoshi => Ok(BdkDenomination::MilliSatoshi),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unsupported denomination")),
         }
     }
 }
@@ -1,6 +1,13 @@
-use bdk_core::BlockId as BdkBlockId;
+use std::{ops::Deref, str::FromStr};
+
+use bdk_core::{BlockId as BdkBlockId, ConfirmationBlockTime};
+use bdk_wallet::bitcoin::BlockHash;
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use crate::result::JsResult;
+
+use super::{BdkError, BdkErrorCode};
+
 /// A reference to a block in the canonical chain.
 #[wasm_bindgen]
 #[derive(Debug)]
@@ -28,3 +35,59 @@ impl From<BdkBlockId> for BlockId {
         BlockId { block_id }
     }
 }
+
+/// The birthday of a wallet, the last block that cannot hold any of its transactions.
+///
+/// The block seeds the checkpoint of the wallet, so chain sources skip the history before it.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Birthday {
+    block: ConfirmationBlockTime,
+}
+
+#[wasm_bindgen]
+impl Birthday {
+    /// Create a birthday from a block of the chain of the wallet and its timestamp.
+    #[wasm_bindgen(constructor)]
+    pub fn new(height: u32, hash: &str, time: u64) -> JsResult<Birthday> {
+        let hash = BlockHash::from_str(hash).map_err(|e| BdkError::new(BdkErrorCode::InvalidValue, e))?;
+        Ok(Birthday {
+            block: ConfirmationBlockTime {
+                block_id: BdkBlockId { height, hash },
+                confirmation_time: time,
+            },
+        })
+    }
+
+    /// The block of the birthday.
+    #[wasm_bindgen(getter)]
+    pub fn block_id(&self) -> BlockId {
+        self.block.block_id.into()
+    }
+
+    /// The timestamp of the block, in seconds since the Unix epoch.
+    #[wasm_bindgen(getter)]
+    pub fn time(&self) -> u64 {
+        self.block.confirmation_time
+    }
+}
+
+impl Deref for Birthday {
+    type Target = ConfirmationBlockTime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.block
+    }
+}
+
+impl From<ConfirmationBlockTime> for Birthday {
+    fn from(block: ConfirmationBlockTime) -> Self {
+        Birthday { block }
+    }
+}
+
+impl From<Birthday> for ConfirmationBlockTime {
+    fn from(birthday: Birthday) -> Self {
+        birthday.block
+    }
+}
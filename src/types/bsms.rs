@@ -0,0 +1,87 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::DescriptorPair;
+
+/// The encryption of the records of a BSMS session.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BsmsEncryption {
+    /// Records are not encrypted, the token is `00`.
+    None = "none",
+    /// Records are encrypted with a 64 bits token shared by all the signers.
+    Standard = "standard",
+    /// Records are encrypted with a 128 bits token, the coordinator can use a different token per signer.
+    Extended = "extended",
+}
+
+/// A key record of a signer, checked to be signed by its key.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct BsmsKeyRecord {
+    key: String,
+    fingerprint: String,
+    description: String,
+}
+
+#[wasm_bindgen]
+impl BsmsKeyRecord {
+    /// The extended public key with its origin, e.g. `[fingerprint/48'/0'/0'/2']xpub...`.
+    #[wasm_bindgen(getter)]
+    pub fn key(&self) -> String {
+        self.key.clone()
+    }
+
+    /// The fingerprint of the master key of the signer.
+    #[wasm_bindgen(getter)]
+    pub fn fingerprint(&self) -> String {
+        self.fingerprint.clone()
+    }
+
+    /// The description of the signer, chosen by its owner.
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+}
+
+impl BsmsKeyRecord {
+    pub(crate) fn new(key: String, fingerprint: String, description: String) -> Self {
+        BsmsKeyRecord {
+            key,
+            fingerprint,
+            description,
+        }
+    }
+}
+
+/// The descriptor record of the coordinator, checked to derive its first address.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct BsmsDescriptorRecord {
+    descriptors: DescriptorPair,
+    first_address: String,
+}
+
+#[wasm_bindgen]
+impl BsmsDescriptorRecord {
+    /// The public descriptors of the wallet.
+    #[wasm_bindgen(getter)]
+    pub fn descriptors(&self) -> DescriptorPair {
+        DescriptorPair::new(self.descriptors.external(), self.descriptors.internal())
+    }
+
+    /// The first receive address of the wallet, to be compared with the address displayed by the signers.
+    #[wasm_bindgen(getter)]
+    pub fn first_address(&self) -> String {
+        self.first_address.clone()
+    }
+}
+
+impl BsmsDescriptorRecord {
+    pub(crate) fn new(descriptors: DescriptorPair, first_address: String) -> Self {
+        BsmsDescriptorRecord {
+            descriptors,
+            first_address,
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::ops::Deref;
 
 use bdk_core::spk_client::{
-    FullScanRequest as BdkFullScanRequest, FullScanResponse as BdkFullScanResponse, SyncRequest as BdkSyncRequest,
-    SyncResponse as BdkSyncResponse,
+    FullScanRequest as BdkFullScanRequest, FullScanResponse as BdkFullScanResponse, SyncItem, SyncProgress,
+    SyncRequest as BdkSyncRequest, SyncResponse as BdkSyncResponse,
 };
-use bdk_wallet::{KeychainKind, Update as BdkUpdate};
-use wasm_bindgen::prelude::wasm_bindgen;
+use bdk_wallet::{bitcoin::Script, KeychainKind, Update as BdkUpdate};
+use js_sys::Function;
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+
+use crate::utils::SendSyncWrapper;
 
 /// Data required to perform a spk-based blockchain client sync.
 ///
@@ -45,6 +48,23 @@ impl From<SyncRequest> for BdkSyncRequest<(KeychainKind, u32)> {
 #[wasm_bindgen]
 pub struct FullScanRequest {
     request: BdkFullScanRequest<KeychainKind>,
+    birthday: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl FullScanRequest {
+    /// The height of the birthday of the wallet, the history confirmed at or before it is skipped.
+    #[wasm_bindgen(getter)]
+    pub fn birthday(&self) -> Option<u32> {
+        self.birthday
+    }
+}
+
+impl FullScanRequest {
+    pub(crate) fn with_birthday(mut self, birthday: Option<u32>) -> Self {
+        self.birthday = birthday;
+        self
+    }
 }
 
 impl Deref for FullScanRequest {
@@ -57,7 +77,10 @@ impl Deref for FullScanRequest {
 
 impl From<BdkFullScanRequest<KeychainKind>> for FullScanRequest {
     fn from(request: BdkFullScanRequest<KeychainKind>) -> Self {
-        FullScanRequest { request }
+        FullScanRequest {
+            request,
+            birthday: None,
+        }
     }
 }
 
@@ -105,3 +128,124 @@ impl From<BdkSyncResponse> for Update {
         Update { update: result.into() }
     }
 }
+
+#[wasm_bindgen]
+extern "C" {
+    /// A function called with the [`ScanProgress`] of a full scan or sync, for every item checked.
+    #[wasm_bindgen(typescript_type = "(progress: ScanProgress) => void")]
+    pub type ScanProgressCallback;
+}
+
+impl ScanProgressCallback {
+    /// Report the progress, ignoring the errors thrown by the callback as the scan cannot be interrupted.
+    fn report(&self, progress: ScanProgress) {
+        let _ = self.unchecked_ref::<Function>().call1(&JsValue::NULL, &progress.into());
+    }
+
+    /// The closure inspecting the scripts of a full scan, counting them as the number of scripts remaining is
+    /// unknown until the stop gap is reached.
+    pub(crate) fn full_scan_inspector(self) -> impl FnMut(KeychainKind, u32, &Script) + Send + 'static {
+        let callback = SendSyncWrapper(self);
+        let mut spks_checked = 0;
+        move |keychain, index, _| {
+            // Capture the whole wrapper, its field alone is not `Send`.
+            let callback = &callback;
+            spks_checked += 1;
+            callback.0.report(ScanProgress {
+                keychain: Some(keychain.into()),
+                index: Some(index),
+                spks_checked,
+                spks_remaining: None,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// The closure inspecting the scripts, txids and outpoints of a sync.
+    pub(crate) fn sync_inspector(self) -> impl FnMut(SyncItem<(KeychainKind, u32)>, SyncProgress) + Send + 'static {
+        let callback = SendSyncWrapper(self);
+        move |item, progress| {
+            let callback = &callback;
+            let (keychain, index) = match item {
+                SyncItem::Spk((keychain, index), _) => (Some(keychain.into()), Some(index)),
+                SyncItem::Txid(_) | SyncItem::OutPoint(_) => (None, None),
+            };
+            callback.0.report(ScanProgress {
+                keychain,
+                index,
+                spks_checked: progress.spks_consumed,
+                spks_remaining: Some(progress.spks_remaining),
+                txids_checked: progress.txids_consumed,
+                txids_remaining: progress.txids_remaining,
+                outpoints_checked: progress.outpoints_consumed,
+                outpoints_remaining: progress.outpoints_remaining,
+            });
+        }
+    }
+}
+
+/// The progress of a full scan or sync, reported when the chain source checks a script, txid or outpoint.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanProgress {
+    keychain: Option<super::KeychainKind>,
+    index: Option<u32>,
+    spks_checked: usize,
+    spks_remaining: Option<usize>,
+    txids_checked: usize,
+    txids_remaining: usize,
+    outpoints_checked: usize,
+    outpoints_remaining: usize,
+}
+
+#[wasm_bindgen]
+impl ScanProgress {
+    /// The keychain of the script being checked, `undefined` for txids and outpoints.
+    #[wasm_bindgen(getter)]
+    pub fn keychain(&self) -> Option<super::KeychainKind> {
+        self.keychain
+    }
+
+    /// The derivation index of the script being checked, `undefined` for txids and outpoints.
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    /// The number of scripts checked, including the current one.
+    #[wasm_bindgen(getter)]
+    pub fn spks_checked(&self) -> usize {
+        self.spks_checked
+    }
+
+    /// The number of scripts left to check, `undefined` for a full scan, which stops after a gap of unused
+    /// scripts.
+    #[wasm_bindgen(getter)]
+    pub fn spks_remaining(&self) -> Option<usize> {
+        self.spks_remaining
+    }
+
+    /// The number of txids whose status was fetched, always 0 for a full scan.
+    #[wasm_bindgen(getter)]
+    pub fn txids_checked(&self) -> usize {
+        self.txids_checked
+    }
+
+    /// The number of txids left to fetch.
+    #[wasm_bindgen(getter)]
+    pub fn txids_remaining(&self) -> usize {
+        self.txids_remaining
+    }
+
+    /// The number of outpoints checked, always 0 for a full scan.
+    #[wasm_bindgen(getter)]
+    pub fn outpoints_checked(&self) -> usize {
+        self.outpoints_checked
+    }
+
+    /// The number of outpoints left to check.
+    #[wasm_bindgen(getter)]
+    pub fn outpoints_remaining(&self) -> usize {
+        self.outpoints_remaining
+    }
+}
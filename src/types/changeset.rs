@@ -1,60 +1,131 @@
 use std::ops::Deref;
 
-use bdk_core::Merge;
+use bdk_core::{ConfirmationBlockTime, Merge};
 use bdk_wallet::{
-    serde_json::{from_str, to_string},
+    serde_json::{from_str, from_value as from_json_value, to_string, to_value as to_json_value, Value},
     ChangeSet as BdkChangeSet,
 };
+use js_sys::Reflect;
 use serde_wasm_bindgen::{from_value, to_value};
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
-use crate::result::JsResult;
+use crate::{bitcoin::Labels, result::JsResult};
+
+use super::{BdkError, BdkErrorCode};
+
+/// Key of the BIP329 labels, next to the fields of the wallet changeset.
+const LABELS_KEY: &str = "labels";
+
+/// Key of the birthday of the wallet, next to the fields of the wallet changeset.
+const BIRTHDAY_KEY: &str = "birthday";
 
 /// A changeset for [`Wallet`].
 #[wasm_bindgen]
 #[derive(Debug, PartialEq)]
 pub struct ChangeSet {
     changeset: BdkChangeSet,
+    labels: Labels,
+    birthday: Option<ConfirmationBlockTime>,
 }
 
 #[wasm_bindgen]
 impl ChangeSet {
     /// Merge another [`ChangeSet`] into itself.
     pub fn merge(&mut self, other: ChangeSet) {
-        self.changeset.merge(other.into());
+        self.changeset.merge(other.changeset);
+        self.labels.merge(other.labels);
+        if other.birthday.is_some() {
+            self.birthday = other.birthday;
+        }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.changeset.is_empty()
+        self.changeset.is_empty() && self.labels.is_empty() && self.birthday.is_none()
     }
 
     /// Serialize `ChangeSet` to JSON.
-    pub fn to_json(&self) -> String {
-        to_string(&self.changeset).expect("Serialization should not fail")
+    pub fn to_json(&self) -> JsResult<String> {
+        let mut value = to_json_value(&self.changeset)?;
+        if !self.labels.is_empty() {
+            value[LABELS_KEY] = to_json_value(&self.labels)?;
+        }
+        if let Some(birthday) = &self.birthday {
+            value[BIRTHDAY_KEY] = to_json_value(birthday)?;
+        }
+        Ok(to_string(&value)?)
     }
 
     /// Serialize `ChangeSet` to JSON compatible with WASM.
-    pub fn to_js(&self) -> JsValue {
-        to_value(&self.changeset).expect("Serialization should not fail")
+    pub fn to_js(&self) -> JsResult<JsValue> {
+        let value = to_value(&self.changeset)?;
+        if !self.labels.is_empty() {
+            Reflect::set(&value, &LABELS_KEY.into(), &to_value(&self.labels)?).map_err(not_an_object)?;
+        }
+        if let Some(birthday) = &self.birthday {
+            Reflect::set(&value, &BIRTHDAY_KEY.into(), &to_value(birthday)?).map_err(not_an_object)?;
+        }
+        Ok(value)
     }
 
     /// Create a new `ChangeSet` from a JSON string.
     #[wasm_bindgen]
     pub fn from_json(val: &str) -> JsResult<ChangeSet> {
+        let mut value: Value = from_str(val)?;
+        let labels = match value.as_object_mut().and_then(|object| object.remove(LABELS_KEY)) {
+            Some(labels) => from_json_value(labels)?,
+            None => Labels::default(),
+        };
+        let birthday = match value.as_object_mut().and_then(|object| object.remove(BIRTHDAY_KEY)) {
+            Some(birthday) => from_json_value(birthday)?,
+            None => None,
+        };
         Ok(ChangeSet {
-            changeset: from_str(val)?,
+            changeset: from_json_value(value)?,
+            labels,
+            birthday,
         })
     }
 
     /// Create a new `ChangeSet` from a JS object.
     #[wasm_bindgen]
     pub fn from_js(js_value: JsValue) -> JsResult<ChangeSet> {
+        let labels = Reflect::get(&js_value, &LABELS_KEY.into()).map_err(not_an_object)?;
+        let labels = match labels {
+            labels if labels.is_undefined() => Labels::default(),
+            labels => from_value(labels)?,
+        };
+        let birthday = Reflect::get(&js_value, &BIRTHDAY_KEY.into()).map_err(not_an_object)?;
+        let birthday = match birthday {
+            birthday if birthday.is_undefined() => None,
+            birthday => from_value(birthday)?,
+        };
         Ok(ChangeSet {
             changeset: from_value(js_value)?,
+            labels,
+            birthday,
         })
     }
 }
 
+impl ChangeSet {
+    pub(crate) fn new(changeset: BdkChangeSet, labels: Labels, birthday: Option<ConfirmationBlockTime>) -> Self {
+        ChangeSet {
+            changeset,
+            labels,
+            birthday,
+        }
+    }
+
+    /// The changes to the wallet, to its BIP329 labels and its birthday.
+    pub(crate) fn into_parts(self) -> (BdkChangeSet, Labels, Option<ConfirmationBlockTime>) {
+        (self.changeset, self.labels, self.birthday)
+    }
+}
+
+fn not_an_object(_: JsValue) -> BdkError {
+    BdkError::new(BdkErrorCode::Serialization, "the changeset is not an object")
+}
+
 impl Deref for ChangeSet {
     type Target = BdkChangeSet;
 
@@ -65,7 +136,7 @@ impl Deref for ChangeSet {
 
 impl From<BdkChangeSet> for ChangeSet {
     fn from(changeset: BdkChangeSet) -> Self {
-        ChangeSet { changeset }
+        ChangeSet::new(changeset, Labels::default(), None)
     }
 }
 
@@ -1,5 +1,22 @@
+use std::{ops::Deref, str::FromStr};
+
+use bdk_wallet::{
+    descriptor::IntoWalletDescriptor,
+    miniscript::{
+        descriptor::{checksum::desc_checksum, DescriptorType as BdkDescriptorType, KeyMap},
+        Descriptor as BdkDescriptor, DescriptorPublicKey, ForEachKey,
+    },
+};
+use bitcoin::secp256k1::Secp256k1;
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use crate::{
+    bitcoin::{join_multipath_descriptor, split_multipath_descriptor},
+    result::JsResult,
+};
+
+use super::{BdkError, Network};
+
 /// Pair of descriptors for external and internal keychains
 #[wasm_bindgen]
 #[derive(Debug)]
@@ -17,6 +34,17 @@ impl DescriptorPair {
         DescriptorPair { external, internal }
     }
 
+    /// Split a BIP389 multipath descriptor (e.g. `.../<0;1>/*`) into external and internal descriptors.
+    pub fn from_multipath(descriptor: &str) -> JsResult<DescriptorPair> {
+        let (external, internal) = split_multipath_descriptor(descriptor)?;
+        Ok(DescriptorPair::new(external, internal))
+    }
+
+    /// Join the external and internal descriptors into a single BIP389 multipath descriptor.
+    pub fn to_multipath(&self) -> JsResult<String> {
+        join_multipath_descriptor(&self.external, &self.internal)
+    }
+
     #[wasm_bindgen(getter)]
     pub fn internal(&self) -> String {
         self.internal.clone()
@@ -27,3 +55,225 @@ impl DescriptorPair {
         self.external.clone()
     }
 }
+
+impl DescriptorPair {
+    /// The public multipath descriptor joining both descriptors, without their private keys.
+    pub(crate) fn to_public_multipath(&self) -> Result<BdkDescriptor<DescriptorPublicKey>, BdkError> {
+        let secp = Secp256k1::new();
+        let (external, _) = BdkDescriptor::parse_descriptor(&secp, &self.external)?;
+        let (internal, _) = BdkDescriptor::parse_descriptor(&secp, &self.internal)?;
+        let multipath = join_multipath_descriptor(&external.to_string(), &internal.to_string())?;
+        let (descriptor, _) = BdkDescriptor::parse_descriptor(&secp, &multipath)?;
+        Ok(descriptor)
+    }
+
+    /// Split a public multipath descriptor into external and internal descriptors.
+    pub(crate) fn from_public_multipath(descriptor: &BdkDescriptor<DescriptorPublicKey>) -> Result<Self, BdkError> {
+        let (external, internal) = split_multipath_descriptor(&descriptor.to_string())?;
+        Ok(DescriptorPair::new(external, internal))
+    }
+}
+
+/// A parsed and validated output script descriptor.
+///
+/// Secret keys found while parsing are kept aside so the descriptor can be
+/// exported either in its public form or with its secrets.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct Descriptor {
+    descriptor: BdkDescriptor<DescriptorPublicKey>,
+    keymap: KeyMap,
+}
+
+#[wasm_bindgen]
+impl Descriptor {
+    /// Parse a descriptor string, verifying its checksum if one is present.
+    #[wasm_bindgen(constructor)]
+    pub fn new(descriptor: &str) -> JsResult<Descriptor> {
+        Ok(Descriptor::from_str(descriptor)?)
+    }
+
+    /// Check that the keys of the descriptor are valid for the given network.
+    pub fn check_network(&self, network: Network) -> JsResult<()> {
+        self.to_string_with_secret()
+            .into_wallet_descriptor(&Secp256k1::new(), network.try_into()?)?;
+        Ok(())
+    }
+
+    /// The checksum of the public form of the descriptor.
+    #[wasm_bindgen(getter)]
+    pub fn checksum(&self) -> JsResult<String> {
+        Ok(desc_checksum(&format!("{:#}", self.descriptor))?)
+    }
+
+    /// The type of script produced by the descriptor.
+    #[wasm_bindgen(getter)]
+    pub fn descriptor_type(&self) -> DescriptorType {
+        self.descriptor.desc_type().into()
+    }
+
+    /// Whether the descriptor contains private keys.
+    #[wasm_bindgen(getter)]
+    pub fn has_secrets(&self) -> bool {
+        !self.keymap.is_empty()
+    }
+
+    /// Whether the descriptor contains a wildcard and can derive multiple scripts.
+    #[wasm_bindgen(getter)]
+    pub fn is_ranged(&self) -> bool {
+        self.descriptor.has_wildcard()
+    }
+
+    /// Whether the descriptor contains BIP389 multipath key expressions.
+    #[wasm_bindgen(getter)]
+    pub fn is_multipath(&self) -> bool {
+        self.descriptor.is_multipath()
+    }
+
+    /// The keys of the descriptor, in order of appearance.
+    pub fn keys(&self) -> Vec<DescriptorKey> {
+        let mut keys = Vec::new();
+        self.descriptor.for_each_key(|key| {
+            keys.push(DescriptorKey {
+                key: key.clone(),
+                is_private: self.keymap.contains_key(key),
+            });
+            true
+        });
+        keys
+    }
+
+    /// The descriptor with its private keys replaced by their public counterparts.
+    pub fn to_public_string(&self) -> String {
+        self.descriptor.to_string()
+    }
+
+    /// The descriptor including its private keys, if any.
+    pub fn to_string_with_secret(&self) -> String {
+        self.descriptor.to_string_with_secret(&self.keymap)
+    }
+}
+
+impl Deref for Descriptor {
+    type Target = BdkDescriptor<DescriptorPublicKey>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.descriptor
+    }
+}
+
+impl FromStr for Descriptor {
+    type Err = bdk_wallet::miniscript::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (descriptor, keymap) = BdkDescriptor::parse_descriptor(&Secp256k1::new(), s)?;
+        descriptor.sanity_check()?;
+
+        Ok(Descriptor { descriptor, keymap })
+    }
+}
+
+/// A key found in a descriptor, along with its origin.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct DescriptorKey {
+    key: DescriptorPublicKey,
+    is_private: bool,
+}
+
+#[wasm_bindgen]
+impl DescriptorKey {
+    /// The public key expression, including origin and derivation steps.
+    #[wasm_bindgen(getter)]
+    pub fn key(&self) -> String {
+        self.key.to_string()
+    }
+
+    /// Fingerprint of the master key this key was derived from.
+    #[wasm_bindgen(getter)]
+    pub fn master_fingerprint(&self) -> String {
+        self.key.master_fingerprint().to_string()
+    }
+
+    /// Full derivation paths from the master key, one per multipath branch.
+    ///
+    /// For ranged keys the paths stop before the wildcard step.
+    #[wasm_bindgen(getter)]
+    pub fn derivation_paths(&self) -> Vec<String> {
+        self.key
+            .full_derivation_paths()
+            .iter()
+            .map(|path| format!("m/{path}"))
+            .collect()
+    }
+
+    /// Whether the private key for this key is part of the descriptor.
+    #[wasm_bindgen(getter)]
+    pub fn is_private(&self) -> bool {
+        self.is_private
+    }
+
+    /// Whether the key is an extended key.
+    #[wasm_bindgen(getter)]
+    pub fn is_extended(&self) -> bool {
+        !matches!(self.key, DescriptorPublicKey::Single(_))
+    }
+
+    /// Whether the key ends with a wildcard.
+    #[wasm_bindgen(getter)]
+    pub fn has_wildcard(&self) -> bool {
+        self.key.has_wildcard()
+    }
+
+    /// Whether the key is a BIP389 multipath key.
+    #[wasm_bindgen(getter)]
+    pub fn is_multipath(&self) -> bool {
+        self.key.is_multipath()
+    }
+}
+
+/// The type of script a descriptor produces.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptorType {
+    /// Bare descriptor (contains the native P2pk).
+    Bare = "bare",
+    /// Pure Sh descriptor. Does not contain nested Wsh/Wpkh.
+    Sh = "sh",
+    /// Pkh descriptor.
+    Pkh = "pkh",
+    /// Wpkh descriptor.
+    Wpkh = "wpkh",
+    /// Wsh descriptor.
+    Wsh = "wsh",
+    /// Sh wrapped Wsh.
+    ShWsh = "sh-wsh",
+    /// Sh wrapped Wpkh.
+    ShWpkh = "sh-wpkh",
+    /// Sh sorted multi.
+    ShSortedMulti = "sh-sortedmulti",
+    /// Wsh sorted multi.
+    WshSortedMulti = "wsh-sortedmulti",
+    /// Sh wrapped Wsh sorted multi.
+    ShWshSortedMulti = "sh-wsh-sortedmulti",
+    /// Tr descriptor.
+    Tr = "tr",
+}
+
+impl From<BdkDescriptorType> for DescriptorType {
+    fn from(descriptor_type: BdkDescriptorType) -> Self {
+        match descriptor_type {
+            BdkDescriptorType::Bare => DescriptorType::Bare,
+            BdkDescriptorType::Sh => DescriptorType::Sh,
+            BdkDescriptorType::Pkh => DescriptorType::Pkh,
+            BdkDescriptorType::Wpkh => DescriptorType::Wpkh,
+            BdkDescriptorType::Wsh => DescriptorType::Wsh,
+            BdkDescriptorType::ShWsh => DescriptorType::ShWsh,
+            BdkDescriptorType::ShWpkh => DescriptorType::ShWpkh,
+            BdkDescriptorType::ShSortedMulti => DescriptorType::ShSortedMulti,
+            BdkDescriptorType::WshSortedMulti => DescriptorType::WshSortedMulti,
+            BdkDescriptorType::ShWshSortedMulti => DescriptorType::ShWshSortedMulti,
+            BdkDescriptorType::Tr => DescriptorType::Tr,
+        }
+    }
+}
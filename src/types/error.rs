@@ -0,0 +1,376 @@
+use std::fmt;
+
+use bdk_wallet::{
+    chain::{local_chain::CannotConnectError, tx_graph::CalculateFeeError},
+    descriptor::DescriptorError,
+    error::{BuildFeeBumpError, CreateTxError},
+    keys::KeyError,
+    miniscript,
+    serde_json::{json, Value},
+    signer::SignerError,
+    tx_builder::{AddForeignUtxoError, AddUtxoError},
+    LoadError, LoadMismatch,
+};
+use bitcoin::{
+    address::{FromScriptError, ParseError as AddressParseError},
+    amount::ParseAmountError,
+    bip32,
+    consensus::encode::{self, FromHexError},
+    psbt::{self, ExtractTxError, PsbtParseError},
+};
+use js_sys::Reflect;
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+#[wasm_bindgen(typescript_custom_section)]
+const BDK_ERROR: &'static str = r#"
+/**
+ * Error thrown by the library, a JavaScript `Error` named `BdkError`.
+ *
+ * The `code` is stable and can be used to branch on the kind of error or to localize the `message`. Some errors
+ * carry additional structured `data`, documented on each `BdkErrorCode`.
+ */
+export interface BdkError extends Error {
+  name: "BdkError";
+  code: BdkErrorCode;
+  data?: any;
+}
+"#;
+
+/// Error thrown by the library.
+///
+/// It is thrown as a JavaScript `Error` named `BdkError`, with the `code` and `data` of the error as properties.
+/// The `code` is stable and can be used to branch on the kind of error or to localize the message. Some errors
+/// carry additional structured `data`, documented on each [`BdkErrorCode`].
+#[derive(Debug)]
+pub struct BdkError {
+    code: BdkErrorCode,
+    message: String,
+    data: Option<Value>,
+}
+
+impl BdkError {
+    /// The stable code identifying the error.
+    pub fn code(&self) -> BdkErrorCode {
+        self.code
+    }
+
+    /// A human-readable description of the error, in English.
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// Additional data attached to the error, `undefined` if none.
+    pub fn data(&self) -> JsValue {
+        match &self.data {
+            Some(data) => data
+                .serialize(&Serializer::json_compatible())
+                .unwrap_or(JsValue::UNDEFINED),
+            None => JsValue::UNDEFINED,
+        }
+    }
+}
+
+impl BdkError {
+    pub fn new(code: BdkErrorCode, message: impl fmt::Display) -> Self {
+        BdkError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+impl fmt::Display for BdkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for BdkError {}
+
+impl From<BdkError> for JsValue {
+    fn from(error: BdkError) -> Self {
+        let js_error = js_sys::Error::new(&error.message);
+        js_error.set_name("BdkError");
+        // Setting a property of a new `Error` cannot fail.
+        let _ = Reflect::set(&js_error, &"code".into(), &error.code.into());
+        let _ = Reflect::set(&js_error, &"data".into(), &error.data());
+        js_error.into()
+    }
+}
+
+/// Stable codes of the errors thrown by the library.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BdkErrorCode {
+    /// An unexpected error, please report it.
+    Unexpected = "Unexpected",
+    /// A value received from JavaScript is not one of the accepted values.
+    InvalidValue = "InvalidValue",
+    /// The amount could not be parsed.
+    InvalidAmount = "InvalidAmount",
+    /// An arithmetic operation on amounts overflowed, underflowed or divided by zero.
+    AmountOverflow = "AmountOverflow",
+    /// The address could not be parsed, or a script has no address form.
+    InvalidAddress = "InvalidAddress",
+    /// The PSBT could not be parsed or is inconsistent.
+    InvalidPsbt = "InvalidPsbt",
+    /// The PSBTs could not be combined, they are for different transactions or have conflicting data.
+    ///
+    /// `data`: `{ input }`, the index of the conflicting input, when the conflict is in an input.
+    CombinePsbt = "CombinePsbt",
+    /// The PSBT returned by a signer does not match the PSBT that was exported.
+    ///
+    /// `data`: `{ input }`, the index of the input, when the previous output or the sighash type of an input was
+    /// changed, or one of its signatures uses a sighash type that was not requested.
+    PsbtMismatch = "PsbtMismatch",
+    /// The transaction could not be decoded.
+    InvalidTransaction = "InvalidTransaction",
+    /// A QR code part could not be decoded, belongs to different data than the previous parts, or the data
+    /// is not complete yet.
+    InvalidQr = "InvalidQr",
+    /// A BSMS record could not be decrypted or parsed, is not signed by its key, or its first address does not
+    /// match its descriptor.
+    InvalidBsms = "InvalidBsms",
+    /// A BIP329 label record or the reference of a label is invalid.
+    ///
+    /// `data`: `{ line }`, the line of the invalid record, when importing labels.
+    InvalidLabel = "InvalidLabel",
+    /// The descriptor could not be parsed or is invalid.
+    InvalidDescriptor = "InvalidDescriptor",
+    /// An extended key, private key or fingerprint is invalid.
+    InvalidKey = "InvalidKey",
+    /// The network of a key or changeset does not match the expected network.
+    NetworkMismatch = "NetworkMismatch",
+    /// The address type is not supported by the operation.
+    UnsupportedAddressType = "UnsupportedAddressType",
+    /// A value could not be serialized or deserialized.
+    Serialization = "Serialization",
+    /// The wallet could not be loaded from the changeset.
+    LoadWallet = "LoadWallet",
+    /// The update does not connect to the chain of the wallet.
+    CannotConnect = "CannotConnect",
+    /// The wallet does not have enough funds.
+    ///
+    /// `data`: `{ needed, available }` in satoshis.
+    InsufficientFunds = "InsufficientFunds",
+    /// The keys to sweep do not hold any funds.
+    NothingToSweep = "NothingToSweep",
+    /// The transaction could not be created.
+    CreateTx = "CreateTx",
+    /// The lock time or sequences of the transaction are invalid, or incompatible with the wallet's descriptors.
+    ///
+    /// `data`: `{ height, tip }` when the current height is below the wallet's latest checkpoint.
+    InvalidTimelock = "InvalidTimelock",
+    /// The OP_RETURN outputs of the transaction exceed the standardness limits.
+    ///
+    /// `data`: `{ size, limit }` in bytes, when the data of an output is too large.
+    NonStandardData = "NonStandardData",
+    /// The fee of the transaction could not be calculated, some of its previous outputs are unknown.
+    CalculateFee = "CalculateFee",
+    /// The fee of the transaction could not be bumped.
+    BuildFeeBump = "BuildFeeBump",
+    /// The transaction could not be signed.
+    Sign = "Sign",
+    /// An external signer failed or returned an invalid PSBT.
+    ///
+    /// `data`: `{ fingerprint }`, the fingerprint of the signer.
+    ExternalSigner = "ExternalSigner",
+    /// The Esplora server responded with an HTTP error.
+    ///
+    /// `data`: `{ status }`, the HTTP status code.
+    EsploraHttp = "EsploraHttp",
+    /// The request to the Esplora server failed or returned invalid data.
+    Esplora = "Esplora",
+    /// The operation was aborted by its `AbortSignal`.
+    Aborted = "Aborted",
+    /// The operation did not complete in time.
+    ///
+    /// `data`: `{ timeout }` in milliseconds, when the timeout was given to the operation.
+    Timeout = "Timeout",
+}
+
+impl From<miniscript::Error> for BdkError {
+    fn from(e: miniscript::Error) -> Self {
+        BdkError::new(BdkErrorCode::InvalidDescriptor, e)
+    }
+}
+
+impl From<bip32::Error> for BdkError {
+    fn from(e: bip32::Error) -> Self {
+        BdkError::new(BdkErrorCode::InvalidKey, e)
+    }
+}
+
+impl From<ParseAmountError> for BdkError {
+    fn from(e: ParseAmountError) -> Self {
+        BdkError::new(BdkErrorCode::InvalidAmount, e)
+    }
+}
+
+impl From<KeyError> for BdkError {
+    fn from(e: KeyError) -> Self {
+        match e {
+            KeyError::InvalidNetwork => BdkError::new(BdkErrorCode::NetworkMismatch, e),
+            KeyError::Miniscript(e) => e.into(),
+            _ => BdkError::new(BdkErrorCode::InvalidKey, e),
+        }
+    }
+}
+
+impl From<DescriptorError> for BdkError {
+    fn from(e: DescriptorError) -> Self {
+        match e {
+            DescriptorError::Key(e) => e.into(),
+            DescriptorError::Bip32(e) => e.into(),
+            _ => BdkError::new(BdkErrorCode::InvalidDescriptor, e),
+        }
+    }
+}
+
+impl From<LoadError> for BdkError {
+    fn from(e: LoadError) -> Self {
+        match e {
+            LoadError::Descriptor(e) => e.into(),
+            LoadError::Mismatch(LoadMismatch::Network { .. }) => BdkError::new(BdkErrorCode::NetworkMismatch, e),
+            _ => BdkError::new(BdkErrorCode::LoadWallet, e),
+        }
+    }
+}
+
+impl From<CannotConnectError> for BdkError {
+    fn from(e: CannotConnectError) -> Self {
+        BdkError::new(BdkErrorCode::CannotConnect, e)
+    }
+}
+
+impl From<CreateTxError> for BdkError {
+    fn from(e: CreateTxError) -> Self {
+        match e {
+            CreateTxError::CoinSelection(e) => BdkError::new(BdkErrorCode::InsufficientFunds, &e).with_data(json!({
+                "needed": e.needed.to_sat(),
+                "available": e.available.to_sat(),
+            })),
+            CreateTxError::Descriptor(e) => e.into(),
+            CreateTxError::LockTime { .. } | CreateTxError::RbfSequenceCsv { .. } | CreateTxError::Version1Csv => {
+                BdkError::new(BdkErrorCode::InvalidTimelock, e)
+            }
+            _ => BdkError::new(BdkErrorCode::CreateTx, e),
+        }
+    }
+}
+
+impl From<AddForeignUtxoError> for BdkError {
+    fn from(e: AddForeignUtxoError) -> Self {
+        BdkError::new(BdkErrorCode::InvalidPsbt, e)
+    }
+}
+
+impl From<AddUtxoError> for BdkError {
+    fn from(e: AddUtxoError) -> Self {
+        BdkError::new(BdkErrorCode::CreateTx, e)
+    }
+}
+
+impl From<BuildFeeBumpError> for BdkError {
+    fn from(e: BuildFeeBumpError) -> Self {
+        BdkError::new(BdkErrorCode::BuildFeeBump, e)
+    }
+}
+
+impl From<SignerError> for BdkError {
+    fn from(e: SignerError) -> Self {
+        BdkError::new(BdkErrorCode::Sign, e)
+    }
+}
+
+impl From<AddressParseError> for BdkError {
+    fn from(e: AddressParseError) -> Self {
+        match e {
+            AddressParseError::NetworkValidation(_) => BdkError::new(BdkErrorCode::NetworkMismatch, e),
+            _ => BdkError::new(BdkErrorCode::InvalidAddress, e),
+        }
+    }
+}
+
+impl From<FromScriptError> for BdkError {
+    fn from(e: FromScriptError) -> Self {
+        BdkError::new(BdkErrorCode::InvalidAddress, e)
+    }
+}
+
+impl From<PsbtParseError> for BdkError {
+    fn from(e: PsbtParseError) -> Self {
+        BdkError::new(BdkErrorCode::InvalidPsbt, e)
+    }
+}
+
+impl From<psbt::Error> for BdkError {
+    fn from(e: psbt::Error) -> Self {
+        BdkError::new(BdkErrorCode::InvalidPsbt, e)
+    }
+}
+
+impl From<ExtractTxError> for BdkError {
+    fn from(e: ExtractTxError) -> Self {
+        BdkError::new(BdkErrorCode::InvalidPsbt, e)
+    }
+}
+
+impl From<encode::Error> for BdkError {
+    fn from(e: encode::Error) -> Self {
+        BdkError::new(BdkErrorCode::InvalidTransaction, e)
+    }
+}
+
+impl From<FromHexError> for BdkError {
+    fn from(e: FromHexError) -> Self {
+        BdkError::new(BdkErrorCode::InvalidTransaction, e)
+    }
+}
+
+impl From<CalculateFeeError> for BdkError {
+    fn from(e: CalculateFeeError) -> Self {
+        BdkError::new(BdkErrorCode::CalculateFee, e)
+    }
+}
+
+impl From<serde_wasm_bindgen::Error> for BdkError {
+    fn from(e: serde_wasm_bindgen::Error) -> Self {
+        BdkError::new(BdkErrorCode::Serialization, e)
+    }
+}
+
+impl From<bdk_wallet::serde_json::Error> for BdkError {
+    fn from(e: bdk_wallet::serde_json::Error) -> Self {
+        BdkError::new(BdkErrorCode::Serialization, e)
+    }
+}
+
+#[cfg(feature = "esplora")]
+impl From<bdk_esplora::esplora_client::Error> for BdkError {
+    fn from(e: bdk_esplora::esplora_client::Error) -> Self {
+        use bdk_esplora::esplora_client::Error;
+
+        match e {
+            Error::HttpResponse { status, message } => {
+                BdkError::new(BdkErrorCode::EsploraHttp, message).with_data(json!({ "status": status }))
+            }
+            _ => BdkError::new(BdkErrorCode::Esplora, e),
+        }
+    }
+}
+
+#[cfg(feature = "esplora")]
+impl From<Box<bdk_esplora::esplora_client::Error>> for BdkError {
+    fn from(e: Box<bdk_esplora::esplora_client::Error>) -> Self {
+        (*e).into()
+    }
+}
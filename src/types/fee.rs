@@ -0,0 +1,170 @@
+use std::{cmp::Ordering, collections::HashMap, ops::Deref};
+
+use bitcoin::{FeeRate as BdkFeeRate, Weight};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::result::JsResult;
+
+use super::{Amount, BdkError, BdkErrorCode};
+
+/// Represents fee rate.
+///
+/// This is an integer newtype representing fee rate in `sat/kwu`. It provides protection against mixing
+/// up the types as well as basic formatting features.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FeeRate {
+    fee_rate: BdkFeeRate,
+}
+
+#[wasm_bindgen]
+impl FeeRate {
+    /// Constructs [`FeeRate`] from satoshis per virtual byte.
+    pub fn from_sat_per_vb(sat_vb: u64) -> JsResult<FeeRate> {
+        BdkFeeRate::from_sat_per_vb(sat_vb)
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::InvalidValue, "Fee rate is too high"))
+    }
+
+    /// Constructs [`FeeRate`] from satoshis per 1000 weight units.
+    pub fn from_sat_per_kwu(sat_kwu: u64) -> FeeRate {
+        BdkFeeRate::from_sat_per_kwu(sat_kwu).into()
+    }
+
+    /// Constructs [`FeeRate`] from bitcoins per 1000 virtual bytes, as returned by Bitcoin Core's
+    /// `estimatesmartfee`.
+    ///
+    /// The fee rate is rounded up to the next `sat/kwu` so that fees are never underestimated.
+    pub fn from_btc_per_kvb(btc_kvb: f64) -> JsResult<FeeRate> {
+        let sat_kvb = (btc_kvb * 100_000_000.0).round();
+        if !sat_kvb.is_finite() || sat_kvb < 0.0 || sat_kvb >= u64::MAX as f64 {
+            return Err(BdkError::new(
+                BdkErrorCode::InvalidValue,
+                format!("Invalid fee rate {btc_kvb} BTC/kvB"),
+            ));
+        }
+        Ok(FeeRate::from_sat_per_kwu((sat_kvb as u64).div_ceil(4)))
+    }
+
+    /// Returns the fee rate in satoshis per 1000 weight units.
+    pub fn to_sat_per_kwu(&self) -> u64 {
+        self.fee_rate.to_sat_per_kwu()
+    }
+
+    /// Returns the fee rate in satoshis per virtual byte, rounding down.
+    pub fn to_sat_per_vb_floor(&self) -> u64 {
+        self.fee_rate.to_sat_per_vb_floor()
+    }
+
+    /// Returns the fee rate in satoshis per virtual byte, rounding up.
+    pub fn to_sat_per_vb_ceil(&self) -> u64 {
+        self.fee_rate.to_sat_per_vb_ceil()
+    }
+
+    /// Calculates the fee of a transaction of the given weight, in weight units, rounding up.
+    pub fn fee_for_weight(&self, weight: u64) -> JsResult<Amount> {
+        self.fee_rate
+            .fee_wu(Weight::from_wu(weight))
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::AmountOverflow, "Fee calculation overflowed"))
+    }
+
+    /// Calculates the fee of a transaction of the given size, in virtual bytes, rounding up.
+    pub fn fee_for_vsize(&self, vsize: u64) -> JsResult<Amount> {
+        self.fee_rate
+            .fee_vb(vsize)
+            .map(Into::into)
+            .ok_or_else(|| BdkError::new(BdkErrorCode::AmountOverflow, "Fee calculation overflowed"))
+    }
+
+    /// Compare with another [`FeeRate`], returns -1, 0 or 1 so it can be used to sort fee rates.
+    pub fn compare(&self, other: &FeeRate) -> i8 {
+        match self.fee_rate.cmp(&other.fee_rate) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        }
+    }
+
+    /// Whether this [`FeeRate`] is equal to another one.
+    pub fn equals(&self, other: &FeeRate) -> bool {
+        self.fee_rate == other.fee_rate
+    }
+
+    /// Format the fee rate in satoshis per virtual byte, e.g. `"2.5 sat/vB"`.
+    ///
+    /// The value is exact, with up to 3 decimals.
+    pub fn display(&self, with_unit: bool) -> String {
+        let sat_kwu = self.fee_rate.to_sat_per_kwu();
+        let mut display = (sat_kwu / 250).to_string();
+        let millis = (sat_kwu % 250) * 4;
+        if millis > 0 {
+            let decimals = format!("{millis:03}");
+            display.push('.');
+            display.push_str(decimals.trim_end_matches('0'));
+        }
+        if with_unit {
+            display.push_str(" sat/vB");
+        }
+        display
+    }
+}
+
+impl Deref for FeeRate {
+    type Target = BdkFeeRate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.fee_rate
+    }
+}
+
+impl From<BdkFeeRate> for FeeRate {
+    fn from(fee_rate: BdkFeeRate) -> Self {
+        FeeRate { fee_rate }
+    }
+}
+
+impl From<FeeRate> for BdkFeeRate {
+    fn from(fee_rate: FeeRate) -> Self {
+        fee_rate.fee_rate
+    }
+}
+
+/// Fee rate estimates, indexed by confirmation target in blocks.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct FeeEstimates {
+    estimates: HashMap<u16, f64>,
+}
+
+#[wasm_bindgen]
+impl FeeEstimates {
+    /// The confirmation targets for which an estimate is available, in ascending order.
+    pub fn targets(&self) -> Vec<u16> {
+        let mut targets: Vec<u16> = self.estimates.keys().copied().collect();
+        targets.sort_unstable();
+        targets
+    }
+
+    /// The fee rate to get confirmed within `target` blocks.
+    ///
+    /// Uses the estimate of the largest available target that is not above `target`, or `undefined`
+    /// if there is none. Estimates are rounded up to the next `sat/kwu`.
+    pub fn fee_rate(&self, target: u16) -> Option<FeeRate> {
+        self.estimates
+            .iter()
+            .filter(|(t, _)| **t <= target)
+            .max_by_key(|(t, _)| **t)
+            .and_then(|(_, sat_vb)| {
+                let sat_kwu = (sat_vb * 250.0).ceil();
+                (sat_kwu.is_finite() && sat_kwu >= 0.0 && sat_kwu < u64::MAX as f64)
+                    .then(|| FeeRate::from_sat_per_kwu(sat_kwu as u64))
+            })
+    }
+}
+
+impl From<HashMap<u16, f64>> for FeeEstimates {
+    fn from(estimates: HashMap<u16, f64>) -> Self {
+        FeeEstimates { estimates }
+    }
+}
@@ -1,6 +1,8 @@
 use bdk_wallet::KeychainKind as BdkKeychainKind;
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use super::{BdkError, BdkErrorCode};
+
 /// Types of keychains
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,12 +22,14 @@ impl From<BdkKeychainKind> for KeychainKind {
     }
 }
 
-impl From<KeychainKind> for BdkKeychainKind {
-    fn from(keychain_kind: KeychainKind) -> Self {
+impl TryFrom<KeychainKind> for BdkKeychainKind {
+    type Error = BdkError;
+
+    fn try_from(keychain_kind: KeychainKind) -> Result<Self, Self::Error> {
         match keychain_kind {
-            KeychainKind::External => BdkKeychainKind::External,
-            KeychainKind::Internal => BdkKeychainKind::Internal,
-            _ => BdkKeychainKind::External,
+            KeychainKind::External => Ok(BdkKeychainKind::External),
+            KeychainKind::Internal => Ok(BdkKeychainKind::Internal),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown keychain kind")),
         }
     }
 }
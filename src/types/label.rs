@@ -0,0 +1,52 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::bitcoin::LabelKind;
+
+use super::{BdkError, BdkErrorCode};
+
+/// The type of the object a BIP329 label is attached to, which decides the format of its reference.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelType {
+    /// A transaction, referenced by its txid.
+    Tx = "tx",
+    /// An address.
+    Addr = "addr",
+    /// A public key, referenced by its hex encoding.
+    Pubkey = "pubkey",
+    /// A transaction input, referenced by the outpoint it spends as `txid:vout`.
+    Input = "input",
+    /// A transaction output, referenced by its outpoint as `txid:vout`.
+    Output = "output",
+    /// An extended public key.
+    Xpub = "xpub",
+}
+
+impl From<LabelKind> for LabelType {
+    fn from(kind: LabelKind) -> Self {
+        match kind {
+            LabelKind::Tx => LabelType::Tx,
+            LabelKind::Addr => LabelType::Addr,
+            LabelKind::Pubkey => LabelType::Pubkey,
+            LabelKind::Input => LabelType::Input,
+            LabelKind::Output => LabelType::Output,
+            LabelKind::Xpub => LabelType::Xpub,
+        }
+    }
+}
+
+impl TryFrom<LabelType> for LabelKind {
+    type Error = BdkError;
+
+    fn try_from(label_type: LabelType) -> Result<Self, Self::Error> {
+        match label_type {
+            LabelType::Tx => Ok(LabelKind::Tx),
+            LabelType::Addr => Ok(LabelKind::Addr),
+            LabelType::Pubkey => Ok(LabelKind::Pubkey),
+            LabelType::Input => Ok(LabelKind::Input),
+            LabelType::Output => Ok(LabelKind::Output),
+            LabelType::Xpub => Ok(LabelKind::Xpub),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown label type")),
+        }
+    }
+}
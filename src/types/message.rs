@@ -0,0 +1,40 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::bitcoin::MessageSignatureFormat;
+
+use super::{BdkError, BdkErrorCode};
+
+/// The format of a signed message.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureFormat {
+    /// BIP322 simple signature, for segwit addresses.
+    Simple = "simple",
+    /// BIP322 full signature, a serialized transaction.
+    Full = "full",
+    /// BIP137 signature, for P2PKH addresses.
+    Legacy = "legacy",
+}
+
+impl From<MessageSignatureFormat> for SignatureFormat {
+    fn from(format: MessageSignatureFormat) -> Self {
+        match format {
+            MessageSignatureFormat::Simple => SignatureFormat::Simple,
+            MessageSignatureFormat::Full => SignatureFormat::Full,
+            MessageSignatureFormat::Legacy => SignatureFormat::Legacy,
+        }
+    }
+}
+
+impl TryFrom<SignatureFormat> for MessageSignatureFormat {
+    type Error = BdkError;
+
+    fn try_from(format: SignatureFormat) -> Result<Self, Self::Error> {
+        match format {
+            SignatureFormat::Simple => Ok(MessageSignatureFormat::Simple),
+            SignatureFormat::Full => Ok(MessageSignatureFormat::Full),
+            SignatureFormat::Legacy => Ok(MessageSignatureFormat::Legacy),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown signature format")),
+        }
+    }
+}
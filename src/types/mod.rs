@@ -2,22 +2,44 @@ mod address;
 mod amount;
 mod balance;
 mod block;
+mod bsms;
 mod chain;
 mod changeset;
 mod checkpoint;
 mod descriptor;
+mod error;
+mod fee;
 mod keychain;
+mod label;
+mod message;
 mod network;
+mod psbt;
+mod qr;
+mod script;
+mod signer;
 mod slip10;
+mod slip132;
+mod transaction;
 
 pub use address::*;
 pub use amount::*;
 pub use balance::*;
 pub use block::*;
+pub use bsms::*;
 pub use chain::*;
 pub use changeset::*;
 pub use checkpoint::*;
 pub use descriptor::*;
+pub use error::*;
+pub use fee::*;
 pub use keychain::*;
+pub use label::*;
+pub use message::*;
 pub use network::*;
+pub use psbt::*;
+pub use qr::*;
+pub use script::*;
+pub use signer::*;
 pub use slip10::*;
+pub use slip132::*;
+pub use transaction::*;
@@ -1,6 +1,8 @@
 use bitcoin::{Network as BdkNetwork, NetworkKind};
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use super::{BdkError, BdkErrorCode};
+
 /// The cryptocurrency network to act on.
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,24 +31,25 @@ impl From<BdkNetwork> for Network {
     }
 }
 
-impl From<Network> for BdkNetwork {
-    fn from(network: Network) -> Self {
+impl TryFrom<Network> for BdkNetwork {
+    type Error = BdkError;
+
+    fn try_from(network: Network) -> Result<Self, Self::Error> {
         match network {
-            Network::Bitcoin => BdkNetwork::Bitcoin,
-            Network::Testnet => BdkNetwork::Testnet,
-            Network::Testnet4 => BdkNetwork::Testnet4,
-            Network::Signet => BdkNetwork::Signet,
-            Network::Regtest => BdkNetwork::Regtest,
-            _ => BdkNetwork::Bitcoin,
+            Network::Bitcoin => Ok(BdkNetwork::Bitcoin),
+            Network::Testnet => Ok(BdkNetwork::Testnet),
+            Network::Testnet4 => Ok(BdkNetwork::Testnet4),
+            Network::Signet => Ok(BdkNetwork::Signet),
+            Network::Regtest => Ok(BdkNetwork::Regtest),
+            _ => Err(BdkError::new(BdkErrorCode::InvalidValue, "Unknown network")),
         }
     }
 }
 
-impl From<Network> for NetworkKind {
-    fn from(network: Network) -> Self {
-        match network {
-            Network::Bitcoin => NetworkKind::Main,
-            _ => NetworkKind::Test,
-        }
+impl TryFrom<Network> for NetworkKind {
+    type Error = BdkError;
+
+    fn try_from(network: Network) -> Result<Self, Self::Error> {
+        BdkNetwork::try_from(network).map(Into::into)
     }
 }
@@ -0,0 +1,427 @@
+use std::{
+    collections::BTreeSet,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use bdk_wallet::{
+    bitcoin::{
+        opcodes::all::{OP_CHECKMULTISIG, OP_PUSHNUM_1, OP_PUSHNUM_16},
+        psbt::Input as BdkInput,
+        script::Instruction,
+        Psbt as BdkPsbt, PublicKey, Script, XOnlyPublicKey,
+    },
+    psbt::PsbtUtils,
+    serde_json::json,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::result::JsResult;
+
+use super::{Amount, BdkError, BdkErrorCode, FeeRate, Transaction, TxOut};
+
+/// A Partially Signed Transaction.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Psbt {
+    psbt: BdkPsbt,
+}
+
+#[wasm_bindgen]
+impl Psbt {
+    /// Parse a PSBT from its base64 encoding.
+    pub fn from_string(psbt: &str) -> JsResult<Psbt> {
+        Ok(Psbt {
+            psbt: BdkPsbt::from_str(psbt)?,
+        })
+    }
+
+    /// Encode the PSBT in base64.
+    #[allow(clippy::inherent_to_string)]
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string(&self) -> String {
+        self.psbt.to_string()
+    }
+
+    /// The total transaction fee amount, sum of input amounts minus sum of output amounts.
+    pub fn fee(&self) -> JsResult<Amount> {
+        Ok(self.psbt.fee()?.into())
+    }
+
+    /// The transaction's fee rate, `undefined` if some inputs are missing their previous output.
+    pub fn fee_rate(&self) -> Option<FeeRate> {
+        self.psbt.fee_rate().map(Into::into)
+    }
+
+    /// The unsigned transaction, scriptSigs and witnesses for each input must be empty.
+    #[wasm_bindgen(getter)]
+    pub fn unsigned_tx(&self) -> Transaction {
+        self.psbt.unsigned_tx.clone().into()
+    }
+
+    /// The inputs of the PSBT, with their metadata.
+    #[wasm_bindgen(getter)]
+    pub fn inputs(&self) -> Vec<PsbtInput> {
+        self.psbt.inputs.iter().cloned().map(Into::into).collect()
+    }
+
+    /// The OP_RETURN outputs of the transaction.
+    pub fn op_return_outputs(&self) -> Vec<TxOut> {
+        self.unsigned_tx().op_return_outputs()
+    }
+
+    /// Combine the signatures and metadata of another PSBT of the same transaction into this one, per BIP 174.
+    ///
+    /// Errors if `other` is for a different transaction, or if both PSBTs have different signatures from the
+    /// same key or different final scripts for an input. This PSBT is left unchanged on error.
+    pub fn combine(&mut self, other: &Psbt) -> JsResult<()> {
+        if self.psbt.unsigned_tx != other.psbt.unsigned_tx {
+            return Err(BdkError::new(
+                BdkErrorCode::CombinePsbt,
+                "the PSBTs are for different transactions",
+            ));
+        }
+        for (index, (ours, theirs)) in self.psbt.inputs.iter().zip(&other.psbt.inputs).enumerate() {
+            if let Some(conflict) = input_conflict(ours, theirs) {
+                return Err(BdkError::new(
+                    BdkErrorCode::CombinePsbt,
+                    format!("input {index} has conflicting {conflict}"),
+                )
+                .with_data(json!({ "input": index })));
+            }
+        }
+
+        let mut combined = self.psbt.clone();
+        combined
+            .combine(other.psbt.clone())
+            .map_err(|e| BdkError::new(BdkErrorCode::CombinePsbt, e))?;
+        self.psbt = combined;
+        Ok(())
+    }
+
+    /// The signature status of each input: which keys have signed and how many more signatures are needed.
+    pub fn signature_status(&self) -> Vec<InputSignatureStatus> {
+        self.psbt.inputs.iter().map(InputSignatureStatus::new).collect()
+    }
+
+    /// Extracts the finalized transaction, ready to be broadcast.
+    ///
+    /// Errors if the fee rate is absurdly high or if some inputs are missing their previous output.
+    pub fn extract_tx(&self) -> JsResult<Transaction> {
+        let tx = self.psbt.clone().extract_tx()?;
+        Ok(tx.into())
+    }
+}
+
+impl Deref for Psbt {
+    type Target = BdkPsbt;
+
+    fn deref(&self) -> &Self::Target {
+        &self.psbt
+    }
+}
+
+impl DerefMut for Psbt {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.psbt
+    }
+}
+
+impl From<BdkPsbt> for Psbt {
+    fn from(psbt: BdkPsbt) -> Self {
+        Psbt { psbt }
+    }
+}
+
+impl From<Psbt> for BdkPsbt {
+    fn from(psbt: Psbt) -> Self {
+        psbt.psbt
+    }
+}
+
+/// The metadata of a PSBT input, like the output it spends and the derivation paths of its keys.
+///
+/// Foreign UTXOs are added to a transaction with their PSBT input, taken from a counterparty's [`Psbt`] or
+/// created from the output they spend.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PsbtInput {
+    input: BdkInput,
+}
+
+#[wasm_bindgen]
+impl PsbtInput {
+    /// A PSBT input spending a segwit output, with only the output itself.
+    ///
+    /// Non-taproot inputs need the full previous transaction unless the transaction builder allows
+    /// `only_witness_utxo`.
+    pub fn from_witness_utxo(txout: TxOut) -> PsbtInput {
+        BdkInput {
+            witness_utxo: Some(txout.into()),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    /// A PSBT input spending an output of `tx`.
+    pub fn from_non_witness_utxo(tx: Transaction) -> PsbtInput {
+        BdkInput {
+            non_witness_utxo: Some(tx.into()),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    /// The output spent by the input, for segwit inputs.
+    #[wasm_bindgen(getter)]
+    pub fn witness_utxo(&self) -> Option<TxOut> {
+        self.input.witness_utxo.clone().map(Into::into)
+    }
+
+    /// The transaction of the output spent by the input.
+    #[wasm_bindgen(getter)]
+    pub fn non_witness_utxo(&self) -> Option<Transaction> {
+        self.input.non_witness_utxo.clone().map(Into::into)
+    }
+
+    /// Whether the input has a final scriptSig or witness.
+    pub fn is_finalized(&self) -> bool {
+        self.input.final_script_sig.is_some() || self.input.final_script_witness.is_some()
+    }
+}
+
+impl Deref for PsbtInput {
+    type Target = BdkInput;
+
+    fn deref(&self) -> &Self::Target {
+        &self.input
+    }
+}
+
+impl From<BdkInput> for PsbtInput {
+    fn from(input: BdkInput) -> Self {
+        PsbtInput { input }
+    }
+}
+
+impl From<PsbtInput> for BdkInput {
+    fn from(input: PsbtInput) -> Self {
+        input.input
+    }
+}
+
+/// Returns the name of the field for which two versions of an input have conflicting values.
+fn input_conflict(ours: &BdkInput, theirs: &BdkInput) -> Option<&'static str> {
+    if ours
+        .partial_sigs
+        .iter()
+        .any(|(key, sig)| theirs.partial_sigs.get(key).is_some_and(|other| other != sig))
+    {
+        return Some("signatures");
+    }
+    if ours
+        .tap_script_sigs
+        .iter()
+        .any(|(key, sig)| theirs.tap_script_sigs.get(key).is_some_and(|other| other != sig))
+    {
+        return Some("taproot script signatures");
+    }
+    if matches!((&ours.tap_key_sig, &theirs.tap_key_sig), (Some(a), Some(b)) if a != b) {
+        return Some("taproot key signatures");
+    }
+    if matches!((&ours.final_script_sig, &theirs.final_script_sig), (Some(a), Some(b)) if a != b)
+        || matches!((&ours.final_script_witness, &theirs.final_script_witness), (Some(a), Some(b)) if a != b)
+    {
+        return Some("final scripts");
+    }
+    None
+}
+
+/// The threshold and public keys of a bare `OP_CHECKMULTISIG` script, like the witness script of a multisig
+/// descriptor.
+fn multisig_keys(script: &Script) -> Option<(usize, Vec<PublicKey>)> {
+    let pushnum = |instruction: Option<Instruction>| match instruction? {
+        Instruction::Op(op) if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) => {
+            Some((op.to_u8() - OP_PUSHNUM_1.to_u8() + 1) as usize)
+        }
+        _ => None,
+    };
+
+    let instructions = script.instructions().collect::<Result<Vec<_>, _>>().ok()?;
+    let [first, keys @ .., n, last] = instructions.as_slice() else {
+        return None;
+    };
+    if *last != Instruction::Op(OP_CHECKMULTISIG) {
+        return None;
+    }
+    let threshold = pushnum(Some(*first))?;
+    let keys = keys
+        .iter()
+        .map(|instruction| PublicKey::from_slice(instruction.push_bytes()?.as_bytes()).ok())
+        .collect::<Option<Vec<_>>>()?;
+    (pushnum(Some(*n))? == keys.len() && threshold <= keys.len()).then_some((threshold, keys))
+}
+
+/// Whether a redeem or witness script is spent with the signature of a single key, like the P2WPKH program of a
+/// P2SH-P2WPKH output or the script of a `wsh(pk())` descriptor.
+fn is_single_key(script: &Script) -> bool {
+    script.is_p2wpkh() || script.is_p2pk() || script.is_p2pkh()
+}
+
+/// The signature status of a PSBT input.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputSignatureStatus {
+    finalized: bool,
+    required: Option<usize>,
+    signed: Vec<SignerKey>,
+    missing: Vec<SignerKey>,
+}
+
+impl InputSignatureStatus {
+    fn new(input: &BdkInput) -> Self {
+        if input.final_script_sig.is_some() || input.final_script_witness.is_some() {
+            return InputSignatureStatus {
+                finalized: true,
+                required: Some(0),
+                signed: vec![],
+                missing: vec![],
+            };
+        }
+
+        let (required, keys) = if input.tap_internal_key.is_some() {
+            // Only the key path is known to need a single signature, script paths have their own policies.
+            let required = input.tap_scripts.is_empty().then_some(1);
+            let keys = input
+                .tap_key_origins
+                .keys()
+                .map(|key| {
+                    let signed = (input.tap_internal_key == Some(*key) && input.tap_key_sig.is_some())
+                        || input.tap_script_sigs.keys().any(|(signer, _)| signer == key);
+                    (SignerKey::from_x_only(input, key), signed)
+                })
+                .collect::<Vec<_>>();
+            (required, keys)
+        } else {
+            let script = input.witness_script.as_ref().or(input.redeem_script.as_ref());
+            let (required, public_keys) = match script.and_then(|script| multisig_keys(script)) {
+                Some((threshold, keys)) => (Some(threshold), keys),
+                None => (
+                    // Other scripts, like `and_v(v:pk(A),pk(B))`, can need any number of signatures.
+                    script.map_or(true, |script| is_single_key(script)).then_some(1),
+                    input
+                        .bip32_derivation
+                        .keys()
+                        .map(|key| PublicKey::new(*key))
+                        .chain(input.partial_sigs.keys().copied())
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect(),
+                ),
+            };
+            let mut keys = public_keys
+                .into_iter()
+                .map(|key| {
+                    (
+                        SignerKey::from_ecdsa(input, &key),
+                        input.partial_sigs.contains_key(&key),
+                    )
+                })
+                .collect::<Vec<_>>();
+            keys.dedup_by(|a, b| a.0 == b.0);
+            (required, keys)
+        };
+
+        let (signed, missing) = keys.into_iter().partition::<Vec<_>, _>(|(_, signed)| *signed);
+        InputSignatureStatus {
+            finalized: false,
+            required,
+            signed: signed.into_iter().map(|(key, _)| key).collect(),
+            missing: missing.into_iter().map(|(key, _)| key).collect(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl InputSignatureStatus {
+    /// Whether the input has a final scriptSig or witness, no more signatures are needed.
+    #[wasm_bindgen(getter)]
+    pub fn finalized(&self) -> bool {
+        self.finalized
+    }
+
+    /// The number of signatures needed to spend the input, `undefined` if the policy is not a single key or
+    /// a multisig.
+    #[wasm_bindgen(getter)]
+    pub fn required(&self) -> Option<usize> {
+        self.required
+    }
+
+    /// The number of signatures still needed, `undefined` if the policy is not a single key or a multisig.
+    #[wasm_bindgen(getter)]
+    pub fn remaining(&self) -> Option<usize> {
+        self.required.map(|required| required.saturating_sub(self.signed.len()))
+    }
+
+    /// The keys that have signed the input.
+    #[wasm_bindgen(getter)]
+    pub fn signed(&self) -> Vec<SignerKey> {
+        self.signed.clone()
+    }
+
+    /// The keys that can sign the input and have not signed yet.
+    #[wasm_bindgen(getter)]
+    pub fn missing(&self) -> Vec<SignerKey> {
+        self.missing.clone()
+    }
+}
+
+/// A key that can sign a PSBT input, with its origin when the PSBT has it.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerKey {
+    public_key: String,
+    fingerprint: Option<String>,
+    derivation_path: Option<String>,
+}
+
+impl SignerKey {
+    fn from_ecdsa(input: &BdkInput, key: &PublicKey) -> Self {
+        let origin = input.bip32_derivation.get(&key.inner);
+        SignerKey {
+            public_key: key.to_string(),
+            fingerprint: origin.map(|(fingerprint, _)| fingerprint.to_string()),
+            derivation_path: origin.map(|(_, path)| path.to_string()),
+        }
+    }
+
+    fn from_x_only(input: &BdkInput, key: &XOnlyPublicKey) -> Self {
+        let origin = input.tap_key_origins.get(key).map(|(_, origin)| origin);
+        SignerKey {
+            public_key: key.to_string(),
+            fingerprint: origin.map(|(fingerprint, _)| fingerprint.to_string()),
+            derivation_path: origin.map(|(_, path)| path.to_string()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SignerKey {
+    /// The public key, in hex.
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> String {
+        self.public_key.clone()
+    }
+
+    /// The fingerprint of the master key the key is derived from.
+    #[wasm_bindgen(getter)]
+    pub fn fingerprint(&self) -> Option<String> {
+        self.fingerprint.clone()
+    }
+
+    /// The derivation path of the key from the master key, like `84'/1'/0'/0/3`.
+    #[wasm_bindgen(getter)]
+    pub fn derivation_path(&self) -> Option<String> {
+        self.derivation_path.clone()
+    }
+}
@@ -0,0 +1,32 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The encoding of the data in BBQr parts.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BbqrEncoding {
+    /// Upper case hexadecimal.
+    Hex = "hex",
+    /// Base32, denser than hexadecimal in the alphanumeric mode of QR codes.
+    Base32 = "base32",
+    /// Deflate compressed then base32, the default of Coldcard. Falls back to base32 if the data does not
+    /// compress.
+    Zlib = "zlib",
+}
+
+/// The type of the data in BBQr parts.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BbqrFileType {
+    /// A binary PSBT.
+    Psbt = "psbt",
+    /// A serialized transaction.
+    Transaction = "transaction",
+    /// JSON text.
+    Json = "json",
+    /// CBOR data.
+    Cbor = "cbor",
+    /// Unicode text, such as a descriptor.
+    Unicode = "unicode",
+    /// Arbitrary binary data.
+    Binary = "binary",
+}
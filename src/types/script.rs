@@ -0,0 +1,158 @@
+use std::ops::Deref;
+
+use bdk_wallet::bitcoin::{
+    script::Instruction, Address as BdkAddress, Network as BdkNetwork, ScriptBuf as BdkScriptBuf,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::result::JsResult;
+
+use super::{Address, BdkError, BdkErrorCode, Network};
+
+/// A Bitcoin script, like the script pubkey of an output.
+///
+/// Scripts can be inspected: disassembled to ASM, classified with [`ScriptBuf::script_type`] and
+/// converted to an address when they have one.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScriptBuf {
+    script: BdkScriptBuf,
+}
+
+#[wasm_bindgen]
+impl ScriptBuf {
+    /// Parse a script from its hex encoding.
+    pub fn from_hex(hex: &str) -> JsResult<ScriptBuf> {
+        let script = BdkScriptBuf::from_hex(hex).map_err(|e| BdkError::new(BdkErrorCode::InvalidValue, e))?;
+        Ok(ScriptBuf { script })
+    }
+
+    /// The script pubkey paying to an address.
+    pub fn from_address(address: &Address) -> ScriptBuf {
+        address.script_pubkey()
+    }
+
+    /// Create a script from its raw bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> ScriptBuf {
+        BdkScriptBuf::from_bytes(bytes).into()
+    }
+
+    /// Encode the script in hex.
+    pub fn to_hex(&self) -> String {
+        self.script.to_hex_string()
+    }
+
+    /// Returns the raw bytes of the script.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.script.to_bytes()
+    }
+
+    /// Returns the length in bytes of the script.
+    pub fn len(&self) -> usize {
+        self.script.len()
+    }
+
+    /// Returns whether the script is the empty script.
+    pub fn is_empty(&self) -> bool {
+        self.script.is_empty()
+    }
+
+    /// Disassemble the script in the ASM format, e.g. `OP_0 OP_PUSHBYTES_20 d28e...`.
+    pub fn to_asm(&self) -> String {
+        self.script.to_asm_string()
+    }
+
+    /// Classify the script by its standard template.
+    pub fn script_type(&self) -> ScriptType {
+        let script = &self.script;
+        if script.is_p2pkh() {
+            ScriptType::P2pkh
+        } else if script.is_p2sh() {
+            ScriptType::P2sh
+        } else if script.is_p2wpkh() {
+            ScriptType::P2wpkh
+        } else if script.is_p2wsh() {
+            ScriptType::P2wsh
+        } else if script.is_p2tr() {
+            ScriptType::P2tr
+        } else if script.is_p2pk() {
+            ScriptType::P2pk
+        } else if script.is_multisig() {
+            ScriptType::Multisig
+        } else if script.is_op_return() {
+            ScriptType::OpReturn
+        } else {
+            ScriptType::Unknown
+        }
+    }
+
+    /// The data carried by an OP_RETURN script, the concatenation of its pushes.
+    ///
+    /// `undefined` if the script is not an OP_RETURN script or contains non-push opcodes after it.
+    pub fn op_return_data(&self) -> Option<Vec<u8>> {
+        if !self.script.is_op_return() {
+            return None;
+        }
+        let mut data = vec![];
+        for instruction in self.script.instructions().skip(1) {
+            match instruction.ok()? {
+                Instruction::PushBytes(bytes) => data.extend_from_slice(bytes.as_bytes()),
+                Instruction::Op(_) => return None,
+            }
+        }
+        Some(data)
+    }
+
+    /// The address of the script on the given network.
+    ///
+    /// Errors for scripts that have no address form, like OP_RETURN, P2PK or bare multisig scripts.
+    pub fn to_address(&self, network: Network) -> JsResult<Address> {
+        let network: BdkNetwork = network.try_into()?;
+        let address = BdkAddress::from_script(&self.script, network)?;
+        Ok(address.into())
+    }
+}
+
+impl Deref for ScriptBuf {
+    type Target = BdkScriptBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.script
+    }
+}
+
+impl From<BdkScriptBuf> for ScriptBuf {
+    fn from(script: BdkScriptBuf) -> Self {
+        ScriptBuf { script }
+    }
+}
+
+impl From<ScriptBuf> for BdkScriptBuf {
+    fn from(script: ScriptBuf) -> Self {
+        script.script
+    }
+}
+
+/// The standard templates a script can match.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptType {
+    /// Pay to pubkey hash.
+    P2pkh = "p2pkh",
+    /// Pay to script hash.
+    P2sh = "p2sh",
+    /// Pay to witness pubkey hash.
+    P2wpkh = "p2wpkh",
+    /// Pay to witness script hash.
+    P2wsh = "p2wsh",
+    /// Pay to taproot.
+    P2tr = "p2tr",
+    /// Pay to a bare public key.
+    P2pk = "p2pk",
+    /// Bare multisig, `OP_CHECKMULTISIG` without a script hash.
+    Multisig = "multisig",
+    /// Provably unspendable data carrier output.
+    OpReturn = "op_return",
+    /// Any other script.
+    Unknown = "unknown",
+}
@@ -0,0 +1,55 @@
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+
+use crate::result::JsResult;
+
+use super::{BdkError, BdkErrorCode, Psbt};
+
+#[wasm_bindgen(typescript_custom_section)]
+const EXTERNAL_SIGNER: &'static str = r#"
+/**
+ * A signer whose private keys never enter the library, like a hardware wallet bridge, a snap or an MPC service.
+ */
+export interface ExternalSigner {
+  /**
+   * Sign the inputs of the PSBT that spend from the signer's keys.
+   *
+   * Receives and returns the PSBT encoded in base64, the returned PSBT must be for the same transaction. It
+   * does not need to be finalized, finalization is done by the wallet.
+   */
+  sign_psbt(psbt: string): Promise<string>;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A signer implemented in JavaScript, see the `ExternalSigner` interface.
+    #[wasm_bindgen(typescript_type = "ExternalSigner")]
+    pub type ExternalSigner;
+
+    #[wasm_bindgen(method, catch)]
+    async fn sign_psbt(this: &ExternalSigner, psbt: String) -> Result<JsValue, JsValue>;
+}
+
+impl ExternalSigner {
+    /// Have the signer sign a copy of the PSBT.
+    pub(crate) async fn sign(&self, psbt: &Psbt) -> JsResult<Psbt> {
+        let signed = self
+            .sign_psbt(psbt.to_string())
+            .await
+            .map_err(|e| BdkError::new(BdkErrorCode::ExternalSigner, js_error_message(&e)))?;
+        let signed = signed.as_string().ok_or_else(|| {
+            BdkError::new(
+                BdkErrorCode::ExternalSigner,
+                "the signer did not return a PSBT encoded in base64",
+            )
+        })?;
+        Psbt::from_string(&signed)
+    }
+}
+
+fn js_error_message(error: &JsValue) -> String {
+    match error.dyn_ref::<js_sys::Error>() {
+        Some(error) => error.message().into(),
+        None => error.as_string().unwrap_or_else(|| format!("{error:?}")),
+    }
+}
@@ -0,0 +1,20 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// SLIP-132 encodings of extended keys.
+///
+/// The version bytes of the encoding tell which script type the key is meant for.
+/// Private keys and testnet keys use the matching `prv` and `tpub`/`upub`/`vpub`/`Upub`/`Vpub` prefixes.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slip132Version {
+    /// Standard BIP32 encoding (`xpub`/`tpub`), P2PKH or P2SH.
+    Xpub = "xpub",
+    /// P2WPKH nested in P2SH (`ypub`/`upub`).
+    Ypub = "ypub",
+    /// P2WPKH (`zpub`/`vpub`).
+    Zpub = "zpub",
+    /// Multisig P2WSH nested in P2SH (`Ypub`/`Upub`).
+    MultisigYpub = "Ypub",
+    /// Multisig P2WSH (`Zpub`/`Vpub`).
+    MultisigZpub = "Zpub",
+}
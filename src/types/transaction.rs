@@ -0,0 +1,348 @@
+use std::{ops::Deref, str::FromStr};
+
+use bdk_wallet::bitcoin::{
+    consensus::{deserialize, encode::deserialize_hex, serialize},
+    hex::DisplayHex,
+    OutPoint as BdkOutPoint, Transaction as BdkTransaction, TxIn as BdkTxIn, TxOut as BdkTxOut, Txid,
+};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::result::JsResult;
+
+use super::{Amount, BdkError, BdkErrorCode, ScriptBuf};
+
+/// Bitcoin transaction.
+///
+/// An authenticated movement of coins.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    tx: BdkTransaction,
+    label: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Transaction {
+    /// Decode a transaction from its consensus serialization in hex.
+    pub fn from_hex(hex: &str) -> JsResult<Transaction> {
+        Ok(deserialize_hex::<BdkTransaction>(hex)?.into())
+    }
+
+    /// Decode a transaction from its consensus serialization.
+    pub fn from_bytes(bytes: &[u8]) -> JsResult<Transaction> {
+        Ok(deserialize::<BdkTransaction>(bytes)?.into())
+    }
+
+    /// Encode the transaction with its consensus serialization, in hex.
+    pub fn to_hex(&self) -> String {
+        serialize(&self.tx).to_lower_hex_string()
+    }
+
+    /// Encode the transaction with its consensus serialization.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.tx)
+    }
+
+    /// Computes the [`Txid`].
+    ///
+    /// Hashes the transaction **excluding** the segwit data (i.e. the marker, flag bytes, and the
+    /// witness fields themselves).
+    #[wasm_bindgen(getter)]
+    pub fn txid(&self) -> String {
+        self.tx.compute_txid().to_string()
+    }
+
+    /// Computes the segwit version of the transaction id.
+    ///
+    /// Hashes the transaction **including** all segwit data (i.e. the marker, flag bytes, and the
+    /// witness fields themselves). For non-segwit transactions which do not have any segwit data,
+    /// this will be equal to the txid.
+    #[wasm_bindgen(getter)]
+    pub fn wtxid(&self) -> String {
+        self.tx.compute_wtxid().to_string()
+    }
+
+    /// The protocol version, is currently expected to be 1 or 2 (BIP 68).
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> i32 {
+        self.tx.version.0
+    }
+
+    /// Block height or timestamp, in its consensus encoding. Transaction cannot be included in a
+    /// block until this height/time.
+    #[wasm_bindgen(getter)]
+    pub fn lock_time(&self) -> u32 {
+        self.tx.lock_time.to_consensus_u32()
+    }
+
+    /// List of transaction inputs.
+    #[wasm_bindgen(getter)]
+    pub fn input(&self) -> Vec<TxIn> {
+        self.tx.input.iter().cloned().map(Into::into).collect()
+    }
+
+    /// List of transaction outputs.
+    #[wasm_bindgen(getter)]
+    pub fn output(&self) -> Vec<TxOut> {
+        self.tx.output.iter().cloned().map(Into::into).collect()
+    }
+
+    /// The OP_RETURN outputs of the transaction, see [`ScriptBuf::op_return_data`] to read their data.
+    pub fn op_return_outputs(&self) -> Vec<TxOut> {
+        self.tx
+            .output
+            .iter()
+            .filter(|txout| txout.script_pubkey.is_op_return())
+            .cloned()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// Returns the weight of this transaction, as defined by BIP-141.
+    pub fn weight(&self) -> u64 {
+        self.tx.weight().to_wu()
+    }
+
+    /// Returns the "virtual size" (vsize) of this transaction, the weight divided by 4 and rounded up.
+    pub fn vsize(&self) -> usize {
+        self.tx.vsize()
+    }
+
+    /// Returns `true` if the transaction itself opted in to be BIP-125-replaceable (RBF).
+    ///
+    /// Replaceability can also be inherited from an unconfirmed ancestor, which this does not check.
+    pub fn is_rbf(&self) -> bool {
+        self.tx.is_explicitly_rbf()
+    }
+
+    /// Checks if this is a coinbase transaction.
+    pub fn is_coinbase(&self) -> bool {
+        self.tx.is_coinbase()
+    }
+
+    /// Returns `true` if this transaction's lock time is enabled, i.e. at least one input has a
+    /// non-final sequence.
+    pub fn is_lock_time_enabled(&self) -> bool {
+        self.tx.is_lock_time_enabled()
+    }
+
+    /// The BIP329 label of the transaction, when the transaction is returned by [`Wallet::transactions`].
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+}
+
+impl Transaction {
+    pub(crate) fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
+}
+
+/// Transactions are compared by their content, their labels are ignored.
+impl PartialEq for Transaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.tx == other.tx
+    }
+}
+
+impl Eq for Transaction {}
+
+impl Deref for Transaction {
+    type Target = BdkTransaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl From<BdkTransaction> for Transaction {
+    fn from(tx: BdkTransaction) -> Self {
+        Transaction { tx, label: None }
+    }
+}
+
+impl From<Transaction> for BdkTransaction {
+    fn from(tx: Transaction) -> Self {
+        tx.tx
+    }
+}
+
+/// Bitcoin transaction input.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxIn {
+    txin: BdkTxIn,
+}
+
+#[wasm_bindgen]
+impl TxIn {
+    /// The reference to the previous output that is being used as an input.
+    #[wasm_bindgen(getter)]
+    pub fn previous_output(&self) -> OutPoint {
+        self.txin.previous_output.into()
+    }
+
+    /// The script which pushes values on the stack which will cause
+    /// the referenced output's script to be accepted.
+    #[wasm_bindgen(getter)]
+    pub fn script_sig(&self) -> ScriptBuf {
+        self.txin.script_sig.clone().into()
+    }
+
+    /// The sequence number, which suggests to miners which of two
+    /// conflicting transactions should be preferred, or 0xFFFFFFFF
+    /// to ignore this feature.
+    #[wasm_bindgen(getter)]
+    pub fn sequence(&self) -> u32 {
+        self.txin.sequence.to_consensus_u32()
+    }
+
+    /// The witness stack of the input, each element encoded in hex.
+    #[wasm_bindgen(getter)]
+    pub fn witness(&self) -> Vec<String> {
+        self.txin
+            .witness
+            .iter()
+            .map(|element| element.to_lower_hex_string())
+            .collect()
+    }
+}
+
+impl Deref for TxIn {
+    type Target = BdkTxIn;
+
+    fn deref(&self) -> &Self::Target {
+        &self.txin
+    }
+}
+
+impl From<BdkTxIn> for TxIn {
+    fn from(txin: BdkTxIn) -> Self {
+        TxIn { txin }
+    }
+}
+
+impl From<TxIn> for BdkTxIn {
+    fn from(txin: TxIn) -> Self {
+        txin.txin
+    }
+}
+
+/// Bitcoin transaction output.
+///
+/// Defines new coins to be created as a result of the transaction,
+/// along with spending conditions ("script", aka "output script"),
+/// which an input spending it must satisfy.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxOut {
+    txout: BdkTxOut,
+}
+
+#[wasm_bindgen]
+impl TxOut {
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: Amount, script_pubkey: ScriptBuf) -> TxOut {
+        TxOut {
+            txout: BdkTxOut {
+                value: value.into(),
+                script_pubkey: script_pubkey.into(),
+            },
+        }
+    }
+
+    /// The value of the output.
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> Amount {
+        self.txout.value.into()
+    }
+
+    /// The script which must be satisfied for the output to be spent.
+    #[wasm_bindgen(getter)]
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        self.txout.script_pubkey.clone().into()
+    }
+}
+
+impl Deref for TxOut {
+    type Target = BdkTxOut;
+
+    fn deref(&self) -> &Self::Target {
+        &self.txout
+    }
+}
+
+impl From<BdkTxOut> for TxOut {
+    fn from(txout: BdkTxOut) -> Self {
+        TxOut { txout }
+    }
+}
+
+impl From<TxOut> for BdkTxOut {
+    fn from(txout: TxOut) -> Self {
+        txout.txout
+    }
+}
+
+/// A reference to a transaction output.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutPoint {
+    outpoint: BdkOutPoint,
+}
+
+#[wasm_bindgen]
+impl OutPoint {
+    #[wasm_bindgen(constructor)]
+    pub fn new(txid: &str, vout: u32) -> JsResult<OutPoint> {
+        let txid = Txid::from_str(txid).map_err(|e| BdkError::new(BdkErrorCode::InvalidValue, e))?;
+        Ok(BdkOutPoint::new(txid, vout).into())
+    }
+
+    /// Parse an outpoint in the `txid:vout` format.
+    pub fn from_string(outpoint: &str) -> JsResult<OutPoint> {
+        let outpoint = BdkOutPoint::from_str(outpoint).map_err(|e| BdkError::new(BdkErrorCode::InvalidValue, e))?;
+        Ok(outpoint.into())
+    }
+
+    /// The referenced transaction's txid.
+    #[wasm_bindgen(getter)]
+    pub fn txid(&self) -> String {
+        self.outpoint.txid.to_string()
+    }
+
+    /// The index of the referenced output in its transaction's vout.
+    #[wasm_bindgen(getter)]
+    pub fn vout(&self) -> u32 {
+        self.outpoint.vout
+    }
+
+    /// Format the outpoint as `txid:vout`.
+    #[allow(clippy::inherent_to_string)]
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string(&self) -> String {
+        self.outpoint.to_string()
+    }
+}
+
+impl Deref for OutPoint {
+    type Target = BdkOutPoint;
+
+    fn deref(&self) -> &Self::Target {
+        &self.outpoint
+    }
+}
+
+impl From<BdkOutPoint> for OutPoint {
+    fn from(outpoint: BdkOutPoint) -> Self {
+        OutPoint { outpoint }
+    }
+}
+
+impl From<OutPoint> for BdkOutPoint {
+    fn from(outpoint: OutPoint) -> Self {
+        outpoint.outpoint
+    }
+}
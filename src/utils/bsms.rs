@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+use bdk_wallet::miniscript::DescriptorPublicKey;
+use bitcoin::bip32::DerivationPath;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    bitcoin::Slip132Key,
+    types::{BdkError, BdkErrorCode, BsmsDescriptorRecord, BsmsEncryption, BsmsKeyRecord, DescriptorPair, Network},
+};
+
+use super::result::JsResult;
+
+/// Generate the token of a new BSMS session, to share with the signers.
+#[wasm_bindgen]
+pub fn bsms_token(encryption: BsmsEncryption) -> JsResult<String> {
+    crate::bitcoin::new_token(encryption)
+}
+
+/// Create the BSMS key record of a signer for the key at `path` of its master private key, signed by the key.
+///
+/// The record is encrypted with `token`, unless it is `00`. `description` is a single line of at most 80
+/// characters identifying the signer.
+#[wasm_bindgen]
+pub fn bsms_key_record(extended_privkey: &str, path: &str, token: &str, description: &str) -> JsResult<String> {
+    let master = Slip132Key::decode(extended_privkey)?.to_xpriv()?;
+    if master.depth != 0 {
+        return Err(BdkError::new(
+            BdkErrorCode::InvalidKey,
+            "the key record must be created with the master key",
+        ));
+    }
+    let path = DerivationPath::from_str(path)?;
+    crate::bitcoin::key_record(&master, &path, token, description)
+}
+
+/// Decrypt a BSMS key record received by the coordinator, and check that it is signed by its key.
+#[wasm_bindgen]
+pub fn bsms_read_key_record(record: &str, token: &str) -> JsResult<BsmsKeyRecord> {
+    let (key, description) = crate::bitcoin::read_key_record(record, token)?;
+    Ok(BsmsKeyRecord::new(
+        key.to_string(),
+        key.master_fingerprint().to_string(),
+        description,
+    ))
+}
+
+/// Create the BSMS descriptor record sent by the coordinator to a signer, with the first address of the
+/// descriptors.
+///
+/// The descriptors must derive their addresses with `<0;1>/*`.
+#[wasm_bindgen]
+pub fn bsms_descriptor_record(descriptors: &DescriptorPair, network: Network, token: &str) -> JsResult<String> {
+    crate::bitcoin::descriptor_record(descriptors, network.try_into()?, token)
+}
+
+/// Decrypt a BSMS descriptor record received by a signer, and check that its descriptor includes the key of
+/// the signer and derives its first address.
+///
+/// `signer_key` is the key of the key record of the signer, with its origin. The signer must still show the
+/// first address to its owner to compare it with the address shown by the other signers.
+#[wasm_bindgen]
+pub fn bsms_read_descriptor_record(
+    record: &str,
+    network: Network,
+    token: &str,
+    signer_key: &str,
+) -> JsResult<BsmsDescriptorRecord> {
+    let signer_key =
+        DescriptorPublicKey::from_str(signer_key).map_err(|e| BdkError::new(BdkErrorCode::InvalidKey, e))?;
+    let (descriptors, first_address) =
+        crate::bitcoin::read_descriptor_record(record, network.try_into()?, token, &signer_key)?;
+    Ok(BsmsDescriptorRecord::new(descriptors, first_address))
+}
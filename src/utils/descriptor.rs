@@ -1,18 +1,18 @@
-use std::str::FromStr;
-
-use bdk_wallet::keys::ExtendedKey;
-use bitcoin::bip32::{Fingerprint, Xpriv, Xpub};
+use bdk_wallet::{keys::ExtendedKey, miniscript::descriptor::checksum::desc_checksum};
+use bitcoin::bip32::Fingerprint;
 use serde_wasm_bindgen::from_value;
 
-use crate::types::{AddressType, DescriptorPair, Network, SLIP10Node};
-use wasm_bindgen::prelude::{wasm_bindgen, JsError, JsValue};
+use crate::{
+    bitcoin::Slip132Key,
+    types::{AddressType, BdkError, BdkErrorCode, DescriptorPair, Network, SLIP10Node, Slip132Version},
+};
+use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 
 use super::result::JsResult;
 
 #[wasm_bindgen]
 pub fn seed_to_descriptor(seed: &[u8], network: Network, address_type: AddressType) -> JsResult<DescriptorPair> {
-    let (external, internal) = crate::bitcoin::seed_to_descriptor(seed, network.into(), address_type.into())
-        .map_err(|e| JsError::new(&e.to_string()))?;
+    let (external, internal) = crate::bitcoin::seed_to_descriptor(seed, network.try_into()?, address_type.try_into()?)?;
 
     Ok(DescriptorPair::new(
         external.0.to_string_with_secret(&external.1),
@@ -27,12 +27,13 @@ pub fn xpriv_to_descriptor(
     network: Network,
     address_type: AddressType,
 ) -> JsResult<DescriptorPair> {
-    let xprv = Xpriv::from_str(extended_privkey)?;
-    let fingerprint = Fingerprint::from_hex(fingerprint)?;
+    let key = Slip132Key::decode(extended_privkey)?;
+    key.check_address_type(address_type.try_into()?)?;
+    let xprv = key.to_xpriv()?;
+    let fingerprint = Fingerprint::from_hex(fingerprint).map_err(|e| BdkError::new(BdkErrorCode::InvalidKey, e))?;
 
     let (external, internal) =
-        crate::bitcoin::xpriv_to_descriptor(xprv, fingerprint, network.into(), address_type.into())
-            .map_err(|e| JsError::new(&e.to_string()))?;
+        crate::bitcoin::xpriv_to_descriptor(xprv, fingerprint, network.try_into()?, address_type.try_into()?)?;
 
     Ok(DescriptorPair::new(
         external.0.to_string_with_secret(&external.1),
@@ -47,19 +48,20 @@ pub fn xpub_to_descriptor(
     network: Network,
     address_type: AddressType,
 ) -> JsResult<DescriptorPair> {
-    let xpub = Xpub::from_str(extended_pubkey)?;
-    let fingerprint = Fingerprint::from_hex(fingerprint)?;
+    let key = Slip132Key::decode(extended_pubkey)?;
+    key.check_address_type(address_type.try_into()?)?;
+    let xpub = key.to_xpub()?;
+    let fingerprint = Fingerprint::from_hex(fingerprint).map_err(|e| BdkError::new(BdkErrorCode::InvalidKey, e))?;
 
     let (external, internal) =
-        crate::bitcoin::xpub_to_descriptor(xpub, fingerprint, network.into(), address_type.into())
-            .map_err(|e| JsError::new(&e.to_string()))?;
+        crate::bitcoin::xpub_to_descriptor(xpub, fingerprint, network.try_into()?, address_type.try_into()?)?;
 
     Ok(DescriptorPair::new(external.0.to_string(), internal.0.to_string()))
 }
 
 #[wasm_bindgen]
 pub fn seed_to_xpriv(seed: &[u8], network: Network) -> JsResult<String> {
-    let xprv = crate::bitcoin::seed_to_xpriv(seed, network.into()).map_err(|e| JsError::new(&e.to_string()))?;
+    let xprv = crate::bitcoin::seed_to_xpriv(seed, network.try_into()?)?;
 
     Ok(xprv.to_string())
 }
@@ -67,11 +69,49 @@ pub fn seed_to_xpriv(seed: &[u8], network: Network) -> JsResult<String> {
 #[wasm_bindgen]
 pub fn slip10_to_extended(slip10: JsValue, network: Network) -> JsResult<String> {
     let node: SLIP10Node = from_value(slip10.clone())?;
-    let extended_key =
-        crate::bitcoin::slip10_to_extended(node, network.into()).map_err(|e| JsError::new(&e.to_string()))?;
+    let extended_key = crate::bitcoin::slip10_to_extended(node, network.try_into()?)?;
 
     match &extended_key {
         ExtendedKey::Private(xprv) => Ok(xprv.0.to_string()),
         ExtendedKey::Public(xpub) => Ok(xpub.0.to_string()),
     }
 }
+
+#[wasm_bindgen]
+pub fn slip10_to_descriptor(slip10: JsValue, network: Network, address_type: AddressType) -> JsResult<DescriptorPair> {
+    let node: SLIP10Node = from_value(slip10)?;
+    let (external, internal) =
+        crate::bitcoin::slip10_to_descriptor(node, network.try_into()?, address_type.try_into()?)?;
+
+    Ok(DescriptorPair::new(
+        external.0.to_string_with_secret(&external.1),
+        internal.0.to_string_with_secret(&internal.1),
+    ))
+}
+
+#[wasm_bindgen]
+pub fn slip132_address_type(extended_key: &str) -> JsResult<Option<AddressType>> {
+    let key = Slip132Key::decode(extended_key)?;
+    key.address_type().map(TryInto::try_into).transpose()
+}
+
+#[wasm_bindgen]
+pub fn convert_slip132(extended_key: &str, version: Slip132Version) -> JsResult<String> {
+    let key = Slip132Key::decode(extended_key)?;
+    key.encode(version)
+}
+
+#[wasm_bindgen]
+pub fn descriptor_checksum(descriptor: &str) -> JsResult<String> {
+    let descriptor = descriptor.split('#').next().unwrap_or_default();
+    Ok(desc_checksum(descriptor)?)
+}
+
+/// Build the single key descriptors of the accounts in the generic JSON export of a Coldcard or Passport.
+///
+/// The accounts are returned in the order P2PKH, P2SH-P2WPKH, P2WPKH and P2TR, skipping the ones missing
+/// from the export. Multisig keys are ignored.
+#[wasm_bindgen]
+pub fn generic_json_to_descriptors(json: &str, network: Network) -> JsResult<Vec<DescriptorPair>> {
+    crate::bitcoin::generic_json_to_descriptors(json, network.try_into()?)
+}
@@ -0,0 +1,14 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::types::Address;
+
+use super::result::JsResult;
+
+/// Verify a base64 encoded BIP322 simple or full signature, or a BIP137 signature for P2PKH addresses.
+///
+/// Returns `false` if the signature is well formed but was not made by the address for this message.
+#[wasm_bindgen]
+pub fn verify_message(address: &Address, message: &str, signature: &str) -> JsResult<bool> {
+    let valid = crate::bitcoin::verify_message(address, message, signature)?;
+    Ok(valid)
+}
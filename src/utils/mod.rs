@@ -1,8 +1,12 @@
+mod bsms;
 mod descriptor;
 mod future;
+mod message;
 mod panic_hook;
 pub mod result;
 
+pub use bsms::*;
 pub use descriptor::*;
 pub use future::SendSyncWrapper;
+pub use message::*;
 pub use panic_hook::set_panic_hook;
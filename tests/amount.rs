@@ -0,0 +1,101 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bitcoindevkit::{
+    set_panic_hook,
+    types::{Amount, BdkErrorCode, Denomination},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn test_amount_parsing() {
+    set_panic_hook();
+
+    assert_eq!(Amount::from_btc_str("0.00021").expect("btc").to_sat(), 21_000);
+    assert_eq!(Amount::from_btc_str("21").expect("btc").to_sat(), 2_100_000_000);
+    assert_eq!(
+        Amount::from_str_in("21000", Denomination::Satoshi)
+            .expect("sat")
+            .to_sat(),
+        21_000
+    );
+    assert_eq!(
+        Amount::from_str_in("1.5", Denomination::MilliBitcoin)
+            .expect("mBTC")
+            .to_sat(),
+        150_000
+    );
+
+    let error = Amount::from_btc_str("0.000000001").expect_err("too precise");
+    assert_eq!(error.code(), BdkErrorCode::InvalidAmount);
+    let error = Amount::from_btc_str("-1").expect_err("negative");
+    assert_eq!(error.code(), BdkErrorCode::InvalidAmount);
+    let error = Amount::from_str_in("1 BTC", Denomination::Bitcoin).expect_err("with unit");
+    assert_eq!(error.code(), BdkErrorCode::InvalidAmount);
+}
+
+#[wasm_bindgen_test]
+async fn test_amount_arithmetic() {
+    set_panic_hook();
+
+    let a = Amount::from_sat(1_000);
+    let b = Amount::from_sat(300);
+
+    assert_eq!(a.checked_add(&b).expect("add").to_sat(), 1_300);
+    assert_eq!(a.checked_sub(&b).expect("sub").to_sat(), 700);
+    assert_eq!(a.checked_mul(3).expect("mul").to_sat(), 3_000);
+    assert_eq!(a.checked_div(3).expect("div").to_sat(), 333);
+
+    let max = Amount::from_sat(u64::MAX);
+    assert_eq!(
+        max.checked_add(&b).expect_err("overflow").code(),
+        BdkErrorCode::AmountOverflow
+    );
+    assert_eq!(
+        b.checked_sub(&a).expect_err("underflow").code(),
+        BdkErrorCode::AmountOverflow
+    );
+    assert_eq!(
+        max.checked_mul(2).expect_err("overflow").code(),
+        BdkErrorCode::AmountOverflow
+    );
+    assert_eq!(
+        a.checked_div(0).expect_err("division by zero").code(),
+        BdkErrorCode::AmountOverflow
+    );
+
+    assert_eq!(a.compare(&b), 1);
+    assert_eq!(b.compare(&a), -1);
+    assert_eq!(a.compare(&Amount::from_sat(1_000)), 0);
+    assert!(a.equals(&Amount::from_sat(1_000)));
+    assert!(!a.equals(&b));
+}
+
+#[wasm_bindgen_test]
+async fn test_amount_display() {
+    set_panic_hook();
+
+    let amount = Amount::from_sat(2_100_000);
+
+    assert_eq!(
+        amount.display_in(Denomination::Bitcoin, false).expect("display"),
+        "0.021"
+    );
+    assert_eq!(
+        amount.display_in(Denomination::Bitcoin, true).expect("display"),
+        "0.021 BTC"
+    );
+    assert_eq!(
+        amount.display_in(Denomination::Satoshi, true).expect("display"),
+        "2100000 satoshi"
+    );
+    assert_eq!(
+        amount.display_in(Denomination::MilliBitcoin, false).expect("display"),
+        "21"
+    );
+}
@@ -0,0 +1,246 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    bsms_descriptor_record, bsms_key_record, bsms_read_descriptor_record, bsms_read_key_record, bsms_token,
+    seed_to_xpriv, set_panic_hook,
+    types::{BdkErrorCode, BsmsEncryption, DescriptorPair, KeychainKind, Network},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const NETWORK: Network = Network::Testnet;
+const PATH: &str = "m/48'/1'/0'/2'";
+
+fn master_key(seed: u8) -> String {
+    seed_to_xpriv(&[seed; 64], NETWORK).expect("xpriv")
+}
+
+/// The 2-of-3 multisig descriptors of the keys of the key records.
+fn multisig(keys: &[String]) -> DescriptorPair {
+    let keys = keys.iter().map(|key| format!("{key}/<0;1>/*")).collect::<Vec<_>>();
+    DescriptorPair::from_multipath(&format!("wsh(sortedmulti(2,{}))", keys.join(","))).expect("descriptors")
+}
+
+#[wasm_bindgen_test]
+async fn test_bsms_rounds() {
+    set_panic_hook();
+
+    assert_eq!(bsms_token(BsmsEncryption::None).expect("token"), "00");
+    assert_eq!(bsms_token(BsmsEncryption::Extended).expect("token").len(), 32);
+    let token = bsms_token(BsmsEncryption::Standard).expect("token");
+    assert_eq!(token.len(), 16);
+    assert_ne!(token, bsms_token(BsmsEncryption::Standard).expect("token"));
+
+    // Round 1, the signers send their key records to the coordinator.
+    let keys = (1..=3)
+        .map(|seed| {
+            let description = format!("Signer {seed}");
+            let record = bsms_key_record(&master_key(seed), PATH, &token, &description).expect("key record");
+            assert!(!record.contains("BSMS"));
+
+            let key_record = bsms_read_key_record(&record, &token).expect("read key record");
+            assert_eq!(key_record.description(), description);
+            assert!(key_record
+                .key()
+                .starts_with(&format!("[{}/48'/1'/0'/2']tpub", key_record.fingerprint())));
+            key_record.key()
+        })
+        .collect::<Vec<_>>();
+
+    // Round 2, the coordinator sends the descriptor record to the signers, who check its first address.
+    let descriptors = multisig(&keys);
+    let wallet = Wallet::create(
+        NETWORK,
+        DescriptorPair::new(descriptors.external(), descriptors.internal()),
+    )
+    .expect("wallet");
+    let record = bsms_descriptor_record(&descriptors, NETWORK, &token).expect("descriptor record");
+    let descriptor_record =
+        bsms_read_descriptor_record(&record, NETWORK, &token, &keys[0]).expect("read descriptor record");
+    assert_eq!(
+        descriptor_record.first_address(),
+        wallet
+            .peek_address(KeychainKind::External, 0)
+            .expect("address")
+            .address
+            .to_string()
+    );
+    let received = descriptor_record.descriptors();
+    assert_eq!(
+        received.external(),
+        wallet
+            .public_descriptor(KeychainKind::External)
+            .expect("public_descriptor")
+    );
+    assert_eq!(
+        received.internal(),
+        wallet
+            .public_descriptor(KeychainKind::Internal)
+            .expect("public_descriptor")
+    );
+
+    let other_token = bsms_token(BsmsEncryption::Standard).unwrap();
+    let error = bsms_read_descriptor_record(&record, NETWORK, &other_token, &keys[0]).expect_err("other token");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let mut tampered = record.clone();
+    tampered.replace_range(70..71, if &record[70..71] == "0" { "1" } else { "0" });
+    let error = bsms_read_descriptor_record(&tampered, NETWORK, &token, &keys[0]).expect_err("tampered");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+
+    // Each signer checks that its key is in the descriptors.
+    for key in &keys[1..] {
+        bsms_read_descriptor_record(&record, NETWORK, &token, key).expect("read descriptor record");
+    }
+    let other_record = bsms_key_record(&master_key(4), PATH, &token, "").expect("key record");
+    let other_key = bsms_read_key_record(&other_record, &token)
+        .expect("read key record")
+        .key();
+    let error = bsms_read_descriptor_record(&record, NETWORK, &token, &other_key).expect_err("other signer");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let unrelated_origin = format!("[00000000/48'/1'/0'/2']{}", keys[0].split_once(']').unwrap().1);
+    let error = bsms_read_descriptor_record(&record, NETWORK, &token, &unrelated_origin).expect_err("other origin");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let error = bsms_read_descriptor_record(&record, NETWORK, &token, "signer").expect_err("invalid key");
+    assert_eq!(error.code(), BdkErrorCode::InvalidKey);
+}
+
+#[wasm_bindgen_test]
+async fn test_bsms_records_without_encryption() {
+    set_panic_hook();
+
+    let record = bsms_key_record(&master_key(1), PATH, "00", "Coldcard").expect("key record");
+    let lines = record.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[..2], ["BSMS 1.0", "00"]);
+    assert_eq!(lines[3], "Coldcard");
+    let key_record = bsms_read_key_record(&format!("{}\r\n", record.replace('\n', "\r\n")), "00").expect("read");
+    assert_eq!(key_record.key(), lines[2]);
+
+    let forged = record.replace("Coldcard", "Jade");
+    let error = bsms_read_key_record(&forged, "00").expect_err("forged description");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let other_key = bsms_key_record(&master_key(2), PATH, "00", "Coldcard").expect("key record");
+    let forged = record.replace(lines[2], other_key.lines().nth(2).unwrap());
+    let error = bsms_read_key_record(&forged, "00").expect_err("forged key");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let error = bsms_read_key_record(&record.replace("BSMS 1.0", "BSMS 2.0"), "00").expect_err("version");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let error = bsms_read_key_record(&record, "0011223344556677").expect_err("not encrypted");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+
+    let keys = (1..=3)
+        .map(|seed| {
+            let record = bsms_key_record(&master_key(seed), PATH, "00", "").expect("key record");
+            bsms_read_key_record(&record, "00").expect("read").key()
+        })
+        .collect::<Vec<_>>();
+    let record = bsms_descriptor_record(&multisig(&keys), NETWORK, "00").expect("descriptor record");
+    let lines = record.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "BSMS 1.0");
+    assert!(lines[1].starts_with("wsh(sortedmulti(2,[") && lines[1].contains("/**,"));
+    assert_eq!(lines[2], "/0/*,/1/*");
+    assert!(lines[3].starts_with("tb1q"));
+
+    let checksum = bitcoindevkit::descriptor_checksum(lines[1]).expect("checksum");
+    let with_checksum = record.replace(lines[1], &format!("{}#{checksum}", lines[1]));
+    bsms_read_descriptor_record(&with_checksum, NETWORK, "00", &keys[0]).expect("template with checksum");
+    let error = bsms_read_descriptor_record(
+        &record.replace(lines[1], &format!("{}#qqqqqqqq", lines[1])),
+        NETWORK,
+        "00",
+        &keys[0],
+    )
+    .expect_err("invalid checksum");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+
+    let other_address = "tb1qd28npep0s8frcm3y7dxqajkcy2m40eysplyr9v";
+    let error = bsms_read_descriptor_record(&record.replace(lines[3], other_address), NETWORK, "00", &keys[0])
+        .expect_err("address");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+    let error = bsms_read_descriptor_record(
+        &record.replace("/0/*,/1/*", "No path restrictions"),
+        NETWORK,
+        "00",
+        &keys[0],
+    )
+    .expect_err("path restrictions");
+    assert_eq!(error.code(), BdkErrorCode::InvalidBsms);
+}
+
+#[wasm_bindgen_test]
+async fn test_bsms_invalid_inputs() {
+    set_panic_hook();
+
+    for token in ["", "0", "001122", "zz11223344556677"] {
+        let error = bsms_key_record(&master_key(1), PATH, token, "").expect_err(token);
+        assert_eq!(error.code(), BdkErrorCode::InvalidValue, "{token}");
+    }
+    let error = bsms_key_record(&master_key(1), PATH, "00", &"a".repeat(81)).expect_err("long description");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+    let error = bsms_key_record(&master_key(1), PATH, "00", "two\nlines").expect_err("multiline description");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+    let error = bsms_token(BsmsEncryption::__Invalid).expect_err("invalid encryption");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let keys = (1..=3)
+        .map(|seed| {
+            let record = bsms_key_record(&master_key(seed), PATH, "00", "").expect("key record");
+            bsms_read_key_record(&record, "00").expect("read").key()
+        })
+        .collect::<Vec<_>>();
+    let descriptors = DescriptorPair::from_multipath(&format!(
+        "wsh(sortedmulti(2,{}/<2;3>/*,{}/<2;3>/*,{}/<2;3>/*))",
+        keys[0], keys[1], keys[2]
+    ))
+    .expect("descriptors");
+    let error = bsms_descriptor_record(&descriptors, NETWORK, "00").expect_err("unsupported paths");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+}
+
+/// A session token and the 1-of-2 descriptor record of the keys of `master_key(1)` and `master_key(2)` encrypted
+/// with it. The first 32 bytes are the HMAC-SHA256 of the token and the record, keyed with the SHA256 of the
+/// PBKDF2-HMAC-SHA512 encryption key, and their first 16 bytes are the IV of the AES-256-CTR ciphertext.
+const VECTOR_TOKEN: &str = "a54044308ceac9b7";
+const VECTOR_MAC: &str = "cde2c953014025a8520686b1f1fd88a906e15f028548420d6b883b64e8e432f2";
+const VECTOR_DESCRIPTOR_RECORD: &str = "cde2c953014025a8520686b1f1fd88a906e15f028548420d6b883b64e8e432f2f0ba4370da1969cc4fbf046eabafe338d94cd028d33b44caa0a61f319927f8d1267b894aa94f9e9317b254ee96fb3cd0e69a5262dadc6ce47a6f2a52f75620df1bb9bc8659edae954bdbbc9e17ccde3b1f3db16f93c5778f0bf758242ab7b68ea8f49a4e1e79d023f1a0605dbf8f7f335c8105852b312fb93eaf5331465f7a2e1bb440fc2fad5f74538b8cb4785f7e59ee5a2186c5edb70acb027f2e245b69838552ad28ade8c4324c93f3446142b3d940db82ec0442ab9868c67196dd9a0716dd5cd75a7685f0b1b40ec71f3fc24b0d5db22c08347f93a61776c9a42cd957bbe4ec653cb35419b0223197764945391b234a60c18132d76c99b51e0cca1100951ed060e803a489b2009345d368b92f6226769a6067d7b36023119f0708c86e45f2478a9446d18bff3cf0b27c40064bb6923d3846691851484697b50ff32b81b908791126b4ff647f8eba7dd5fd945905a83921a10c5fb973da62742940e69ddd775b9b7caf9c54230c646504ba4b68065a773c306d3ee52310";
+const VECTOR_KEYS: [&str; 2] = [
+    "[932f8b09/48'/1'/0'/2']tpubDEsbA1udG8nhN15SFQoL5D7rHCv83QQuekE7W2Bx1vPpzKmbopoNF9n1Si8CkMVhTgzpFQ7zAo8m7Pc4TNAefQpf8obtwZ24CsBbSnKXgHQ",
+    "[1c2583e0/48'/1'/0'/2']tpubDEWYDtAyPrDKrh9KoiDvg4qvJ1f8dug3UuigXQmsJLcn7u4AFkXGemNBacBFkN5MkpWnaUk5LR7kdedY1s1QztwyQGRv9D1rJsQp8zAGcqH",
+];
+const VECTOR_ADDRESS: &str = "tb1qjqpx9f44xlv24znxnnqeuyc90gcvuf337c7wefx8cpsp0q5zku0sgsy897";
+
+#[wasm_bindgen_test]
+async fn test_bsms_vectors() {
+    set_panic_hook();
+
+    let record = bsms_key_record(&master_key(1), PATH, "00", "Signer 1").expect("key record");
+    assert_eq!(
+        record,
+        format!(
+            "BSMS 1.0\n00\n{}\nSigner 1\nH9gKtvZrwuxUH3YUG2qy7be2szsx6ykSAQi87PdJ2KMIKuWNQPVONk0muWOqxlLkRRakyfqDAHX0Ca54xTKRf6A=",
+            VECTOR_KEYS[0]
+        )
+    );
+
+    let descriptors = DescriptorPair::from_multipath(&format!(
+        "wsh(sortedmulti(1,{}/<0;1>/*,{}/<0;1>/*))",
+        VECTOR_KEYS[0], VECTOR_KEYS[1]
+    ))
+    .expect("descriptors");
+    let record = bsms_descriptor_record(&descriptors, NETWORK, VECTOR_TOKEN).expect("descriptor record");
+    assert_eq!(record, VECTOR_DESCRIPTOR_RECORD);
+    assert!(record.starts_with(VECTOR_MAC));
+
+    let descriptor_record =
+        bsms_read_descriptor_record(VECTOR_DESCRIPTOR_RECORD, NETWORK, VECTOR_TOKEN, VECTOR_KEYS[1])
+            .expect("read descriptor record");
+    assert_eq!(descriptor_record.first_address(), VECTOR_ADDRESS);
+    assert_eq!(descriptor_record.descriptors().external(), descriptors.external());
+}
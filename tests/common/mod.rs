@@ -0,0 +1,44 @@
+//! Fixtures shared by the test suites, each using a subset of them.
+
+#![allow(dead_code)]
+
+use bdk_wallet::bitcoin::{
+    absolute, hashes::Hash, transaction, Amount as BdkAmount, OutPoint, Transaction as BdkTransaction, TxIn, TxOut,
+    Txid,
+};
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    types::{Descriptor, DescriptorPair, KeychainKind},
+};
+
+/// A transaction paying `amount` satoshis to the next external address of the wallet.
+pub fn funding_transaction(wallet: &mut Wallet, amount: u64) -> BdkTransaction {
+    let address = wallet.reveal_next_address(KeychainKind::External).expect("address");
+    BdkTransaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            // A distinct previous output per address, so that funding transactions do not conflict.
+            previous_output: OutPoint::new(Txid::from_byte_array([1; 32]), address.index()),
+            ..Default::default()
+        }],
+        output: vec![TxOut {
+            value: BdkAmount::from_sat(amount),
+            script_pubkey: address.address.script_pubkey(),
+        }],
+    }
+}
+
+/// Receive `amount` satoshis to the next external address of the wallet, returning the received output.
+pub fn receive(wallet: &mut Wallet, amount: u64) -> OutPoint {
+    let tx = funding_transaction(wallet, amount);
+    let outpoint = OutPoint::new(tx.compute_txid(), 0);
+    wallet.insert_tx_at(tx.into(), 1);
+    outpoint
+}
+
+/// The public descriptors of a descriptor pair.
+pub fn public(descriptors: &DescriptorPair) -> (String, String) {
+    let public = |descriptor: &str| Descriptor::new(descriptor).expect("descriptor").to_public_string();
+    (public(&descriptors.external()), public(&descriptors.internal()))
+}
@@ -0,0 +1,186 @@
+//! Test suite for the Web and headless browsers.
+//!
+//! Fuzzes the boundaries between JavaScript and Rust with invalid enum values, random strings and
+//! mutations of valid inputs. A panic aborts the whole wasm instance, so every call must return.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bdk_wallet::bitcoin::Amount as BdkAmount;
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    convert_slip132, descriptor_checksum, seed_to_descriptor, seed_to_xpriv, set_panic_hook, slip132_address_type,
+    types::{
+        AddressType, Amount, BdkErrorCode, ChangeSet, Denomination, Descriptor, DescriptorPair, KeychainKind, Network,
+        OutPoint, ScriptBuf, Slip132Version, Transaction,
+    },
+    xpriv_to_descriptor, xpub_to_descriptor,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const ITERATIONS: usize = 256;
+const SEED: [u8; 64] = [7; 64];
+const ALPHABET: &[char] = &[
+    'a', 'h', 'k', 'p', 'r', 's', 't', 'w', 'x', 'z', '0', '1', '9', '(', ')', '[', ']', '<', '>', ';', ',', '/', '*',
+    '\'', 'h', '#', '{', '}', '"', ':', ' ', 'é', '€', '\0',
+];
+const PUBLIC_DESC: &str = "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#wle7e0wp";
+const MULTIPATH_DESC: &str = "tr([27f9035f/86'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/<0;1>/*)";
+const LABELS: &str = "{\"type\":\"tx\",\"ref\":\"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd\",\"label\":\"Rent\"}\n{\"type\":\"output\",\"ref\":\"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd:1\",\"spendable\":false}";
+const VPUB: &str =
+    "vpub5Yiku67hekdfinXW8vjj3HCM7oe1GDbrx2aL3VEokGkNSPBNB97LVA4aNpTgPreyQX2nY12gWUa5if7pXWRB1r9W637jjDvuAVcwDnNEfDs";
+
+/// Deterministic xorshift generator, so that failures can be reproduced.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.below(64);
+        (0..len).map(|_| ALPHABET[self.below(ALPHABET.len())]).collect()
+    }
+
+    /// Replaces, inserts or removes a few characters of `input`, or truncates it.
+    fn mutate(&mut self, input: &str) -> String {
+        let mut chars: Vec<char> = input.chars().collect();
+        for _ in 0..=self.below(4) {
+            let at = self.below(chars.len() + 1);
+            let c = ALPHABET[self.below(ALPHABET.len())];
+            match self.below(4) {
+                0 if at < chars.len() => chars[at] = c,
+                1 => chars.insert(at, c),
+                2 if at < chars.len() => {
+                    chars.remove(at);
+                }
+                _ => chars.truncate(at),
+            }
+        }
+        chars.into_iter().collect()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_invalid_enum_values() {
+    set_panic_hook();
+
+    let error = seed_to_descriptor(&SEED, Network::__Invalid, AddressType::P2wpkh).expect_err("invalid network");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let error = seed_to_descriptor(&SEED, Network::Testnet, AddressType::__Invalid).expect_err("invalid address type");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let error = seed_to_xpriv(&SEED, Network::__Invalid).expect_err("invalid network");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let amount = Amount::from(BdkAmount::from_sat(1000));
+    let error = amount
+        .to_float_in(Denomination::__Invalid)
+        .expect_err("invalid denomination");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let error = convert_slip132(VPUB, Slip132Version::__Invalid).expect_err("invalid version");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let descriptor = Descriptor::new(PUBLIC_DESC).expect("descriptor");
+    let error = descriptor
+        .check_network(Network::__Invalid)
+        .expect_err("invalid network");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let descriptors = seed_to_descriptor(&SEED, Network::Testnet, AddressType::P2wpkh).expect("descriptors");
+    let error = Wallet::create(Network::__Invalid, descriptors)
+        .map(|_| ())
+        .expect_err("invalid network");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let descriptors = seed_to_descriptor(&SEED, Network::Testnet, AddressType::P2wpkh).expect("descriptors");
+    let mut wallet = Wallet::create(Network::Testnet, descriptors).expect("wallet");
+    let errors = [
+        wallet.next_unused_address(KeychainKind::__Invalid).map(|_| ()),
+        wallet.peek_address(KeychainKind::__Invalid, 0).map(|_| ()),
+        wallet.reveal_next_address(KeychainKind::__Invalid).map(|_| ()),
+        wallet.reveal_addresses_to(KeychainKind::__Invalid, 5).map(|_| ()),
+        wallet.list_unused_addresses(KeychainKind::__Invalid).map(|_| ()),
+        wallet.public_descriptor(KeychainKind::__Invalid).map(|_| ()),
+    ];
+    for error in errors {
+        assert_eq!(error.expect_err("invalid keychain").code(), BdkErrorCode::InvalidValue);
+    }
+    // Nothing was revealed on the external keychain in place of the invalid one.
+    assert_eq!(
+        wallet
+            .list_unused_addresses(KeychainKind::External)
+            .expect("addresses")
+            .len(),
+        0
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_fuzz_strings() {
+    set_panic_hook();
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..ITERATIONS {
+        let input = rng.string();
+
+        let _ = Descriptor::new(&input);
+        let _ = DescriptorPair::from_multipath(&input);
+        let _ = DescriptorPair::new(input.clone(), input.clone()).to_multipath();
+        let _ = descriptor_checksum(&input);
+        let _ = slip132_address_type(&input);
+        let _ = convert_slip132(&input, Slip132Version::Zpub);
+        let _ = xpub_to_descriptor(&input, &input, Network::Testnet, AddressType::P2wpkh);
+        let _ = xpriv_to_descriptor(&input, "27f9035f", Network::Testnet, AddressType::P2tr);
+        let _ = ChangeSet::from_json(&input);
+        let _ = Transaction::from_hex(&input);
+        let _ = Transaction::from_bytes(input.as_bytes());
+        let _ = ScriptBuf::from_hex(&input);
+        let _ = OutPoint::from_string(&input);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_fuzz_mutations() {
+    set_panic_hook();
+
+    let descriptors = seed_to_descriptor(&SEED, Network::Testnet, AddressType::P2wpkh).expect("descriptors");
+    let mut wallet = Wallet::create(Network::Testnet, descriptors).expect("wallet");
+    let changeset = wallet.take_staged().expect("changeset").to_json().expect("to_json");
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..ITERATIONS {
+        if let Ok(descriptor) = Descriptor::new(&rng.mutate(PUBLIC_DESC)) {
+            let _ = descriptor.checksum();
+            let _ = descriptor.check_network(Network::Bitcoin);
+            for key in descriptor.keys() {
+                let _ = key.derivation_paths();
+            }
+        }
+        if let Ok(pair) = DescriptorPair::from_multipath(&rng.mutate(MULTIPATH_DESC)) {
+            let _ = pair.to_multipath();
+        }
+        let _ = DescriptorPair::new(rng.mutate(PUBLIC_DESC), rng.mutate(PUBLIC_DESC)).to_multipath();
+        let _ = descriptor_checksum(&rng.mutate(MULTIPATH_DESC));
+        let _ = slip132_address_type(&rng.mutate(VPUB));
+        let _ = convert_slip132(&rng.mutate(VPUB), Slip132Version::Xpub);
+        let _ = xpub_to_descriptor(&rng.mutate(VPUB), "27f9035f", Network::Testnet, AddressType::P2wpkh);
+        let _ = wallet.import_labels(&rng.mutate(LABELS));
+        if let Ok(changeset) = ChangeSet::from_json(&rng.mutate(&changeset)) {
+            let _ = Wallet::load(changeset);
+        }
+    }
+}
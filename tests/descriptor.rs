@@ -0,0 +1,118 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    descriptor_checksum, set_panic_hook,
+    types::{Descriptor, DescriptorPair, DescriptorType, KeychainKind, Network},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const PRIVATE_DESC: &str = "wpkh([27f9035f/84'/1'/0']tprv8g4stFEyX1zQoi4oNBdUFy4cDqWcyWu1kacHgK3RRvTdTPDm8HTxhERpV9JLTct69h4479xKJXm85SYkFZ4eMUsru5MdUNkeouuzbivKAJp/0/*)#sx5quhf7";
+const PUBLIC_DESC: &str = "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#wle7e0wp";
+const MULTIPATH_DESC: &str = "tr([27f9035f/86'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/<0;1>/*)";
+
+#[wasm_bindgen_test]
+async fn test_descriptor_analysis() {
+    set_panic_hook();
+
+    let descriptor = Descriptor::new(PRIVATE_DESC).expect("descriptor");
+
+    assert_eq!(descriptor.descriptor_type(), DescriptorType::Wpkh);
+    assert!(descriptor.has_secrets());
+    assert!(descriptor.is_ranged());
+    assert!(!descriptor.is_multipath());
+    assert_eq!(descriptor.checksum().expect("checksum"), "wle7e0wp");
+    assert_eq!(descriptor.to_public_string(), PUBLIC_DESC);
+    assert_eq!(descriptor.to_string_with_secret(), PRIVATE_DESC);
+
+    let keys = descriptor.keys();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].master_fingerprint(), "27f9035f");
+    assert_eq!(keys[0].derivation_paths(), vec!["m/84'/1'/0'/0".to_string()]);
+    assert!(keys[0].is_private());
+    assert!(keys[0].is_extended());
+    assert!(keys[0].has_wildcard());
+
+    descriptor.check_network(Network::Testnet).expect("testnet keys");
+    assert!(descriptor.check_network(Network::Bitcoin).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_descriptor_multipath() {
+    set_panic_hook();
+
+    let descriptor = Descriptor::new(MULTIPATH_DESC).expect("descriptor");
+
+    assert_eq!(descriptor.descriptor_type(), DescriptorType::Tr);
+    assert!(!descriptor.has_secrets());
+    assert!(descriptor.is_multipath());
+    assert_eq!(
+        descriptor.keys()[0].derivation_paths(),
+        vec!["m/86'/1'/0'/0".to_string(), "m/86'/1'/0'/1".to_string()]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_descriptor_checksum() {
+    set_panic_hook();
+
+    let unchecked = PUBLIC_DESC.split('#').next().unwrap();
+    assert_eq!(descriptor_checksum(unchecked).expect("checksum"), "wle7e0wp");
+    assert_eq!(descriptor_checksum(PUBLIC_DESC).expect("checksum"), "wle7e0wp");
+
+    assert!(Descriptor::new(unchecked).is_ok());
+    assert!(Descriptor::new(&format!("{unchecked}#wle7e0wq")).is_err());
+    assert!(Descriptor::new("wpkh(not_a_key)").is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_descriptor_pair_multipath() {
+    set_panic_hook();
+
+    let descriptors = DescriptorPair::from_multipath(MULTIPATH_DESC).expect("from_multipath");
+    assert_eq!(
+        descriptors.external(),
+        "tr([27f9035f/86'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#a90pw3ku"
+    );
+    assert_eq!(
+        descriptors.internal(),
+        "tr([27f9035f/86'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/1/*)#v32qnyxy"
+    );
+
+    let multipath = descriptors.to_multipath().expect("to_multipath");
+    assert_eq!(multipath, format!("{MULTIPATH_DESC}#fawzlw3t"));
+
+    let wallet = Wallet::create_from_multipath(Network::Testnet, MULTIPATH_DESC).expect("wallet");
+    assert_eq!(
+        wallet
+            .public_descriptor(KeychainKind::External)
+            .expect("public_descriptor"),
+        descriptors.external()
+    );
+    assert_eq!(wallet.public_multipath_descriptor().expect("multipath"), multipath);
+}
+
+#[wasm_bindgen_test]
+async fn test_descriptor_pair_multipath_with_secrets() {
+    set_panic_hook();
+
+    let internal = PRIVATE_DESC.split('#').next().unwrap().replace("/0/*", "/1/*");
+    let descriptors = DescriptorPair::new(PRIVATE_DESC.into(), internal);
+
+    let multipath = descriptors.to_multipath().expect("to_multipath");
+    assert_eq!(multipath, "wpkh([27f9035f/84'/1'/0']tprv8g4stFEyX1zQoi4oNBdUFy4cDqWcyWu1kacHgK3RRvTdTPDm8HTxhERpV9JLTct69h4479xKJXm85SYkFZ4eMUsru5MdUNkeouuzbivKAJp/<0;1>/*)#qhurvpr2");
+
+    let split = DescriptorPair::from_multipath(&multipath).expect("from_multipath");
+    assert_eq!(split.external(), PRIVATE_DESC);
+    assert_eq!(split.internal(), "wpkh([27f9035f/84'/1'/0']tprv8g4stFEyX1zQoi4oNBdUFy4cDqWcyWu1kacHgK3RRvTdTPDm8HTxhERpV9JLTct69h4479xKJXm85SYkFZ4eMUsru5MdUNkeouuzbivKAJp/1/*)#pj3ppzex");
+
+    let mismatched = DescriptorPair::new(PRIVATE_DESC.into(), PUBLIC_DESC.replace("wpkh", "pkh"));
+    assert!(mismatched.to_multipath().is_err());
+    assert!(DescriptorPair::from_multipath(PUBLIC_DESC).is_err());
+}
@@ -0,0 +1,68 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bdk_wallet::serde_json::json;
+use bitcoindevkit::{
+    set_panic_hook,
+    types::{AddressType, BdkError, BdkErrorCode, Descriptor, Network},
+    xpub_to_descriptor,
+};
+use js_sys::{Error, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const TPUB: &str =
+    "tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq";
+const PUBLIC_DESC: &str = "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#wle7e0wp";
+
+#[wasm_bindgen_test]
+async fn test_error_codes() {
+    set_panic_hook();
+
+    let error = Descriptor::new("wpkh(not_a_key)").expect_err("invalid descriptor");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+
+    let descriptor = Descriptor::new(PUBLIC_DESC).expect("descriptor");
+    let error = descriptor
+        .check_network(Network::Bitcoin)
+        .expect_err("network mismatch");
+    assert_eq!(error.code(), BdkErrorCode::NetworkMismatch);
+
+    let error = xpub_to_descriptor(TPUB, "27f9035f", Network::Testnet, AddressType::P2wsh).expect_err("unsupported");
+    assert_eq!(error.code(), BdkErrorCode::UnsupportedAddressType);
+
+    let error = xpub_to_descriptor(TPUB, "not_hex", Network::Testnet, AddressType::P2wpkh).expect_err("invalid key");
+    assert_eq!(error.code(), BdkErrorCode::InvalidKey);
+
+    let error = xpub_to_descriptor("tpub_not_a_key", "27f9035f", Network::Testnet, AddressType::P2wpkh)
+        .expect_err("invalid key");
+    assert_eq!(error.code(), BdkErrorCode::InvalidKey);
+}
+
+#[wasm_bindgen_test]
+async fn test_thrown_error() {
+    set_panic_hook();
+
+    let property = |value: &JsValue, name: &str| Reflect::get(value, &name.into()).expect("property");
+
+    // Errors are thrown as JavaScript errors, recognized by generic error reporters.
+    let error = Descriptor::new("wpkh(not_a_key)").expect_err("invalid descriptor");
+    let message = error.message();
+    let thrown = JsValue::from(error);
+    let js_error = thrown.dyn_ref::<Error>().expect("instanceof Error");
+    assert_eq!(js_error.name(), "BdkError");
+    assert_eq!(js_error.message(), message);
+    assert!(property(&thrown, "stack").is_string());
+    assert_eq!(property(&thrown, "code"), "InvalidDescriptor");
+    assert!(property(&thrown, "data").is_undefined());
+
+    let error = BdkError::new(BdkErrorCode::PsbtMismatch, "mismatch").with_data(json!({ "input": 1 }));
+    let thrown = JsValue::from(error);
+    assert_eq!(property(&thrown, "code"), "PsbtMismatch");
+    assert_eq!(property(&property(&thrown, "data"), "input"), 1);
+}
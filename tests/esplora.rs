@@ -4,12 +4,18 @@
 
 extern crate wasm_bindgen_test;
 
+use std::{cell::Cell, rc::Rc};
+
+use bdk_wallet::serde_json::{from_str, Value};
+
 use bitcoindevkit::{
-    bitcoin::{EsploraClient, Wallet},
+    bitcoin::{EsploraClient, Recipient, Wallet},
     set_panic_hook,
-    types::{DescriptorPair, KeychainKind, Network},
+    types::{Address, Amount, BdkErrorCode, DescriptorPair, FeeRate, KeychainKind, Network, ScanProgress},
 };
+use wasm_bindgen::{closure::Closure, JsCast};
 use wasm_bindgen_test::*;
+use web_sys::AbortSignal;
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -19,18 +25,25 @@ const NETWORK: Network = Network::Signet;
 const EXTERNAL_DESC: &str = "wpkh([aafa6322/84'/1'/0']tpubDCfvzhCuifJtWDVdrBcPvZU7U5uyixL7QULk8hXA7KjqiNnry9Te1nwm7yStqenPCQhy5MwzxKkLBD2GmKNgvMYqXgo53iYqQ7Vu4vQbN2N/0/*)#mlua264t";
 const INTERNAL_DESC: &str = "wpkh([aafa6322/84'/1'/0']tpubDCfvzhCuifJtWDVdrBcPvZU7U5uyixL7QULk8hXA7KjqiNnry9Te1nwm7yStqenPCQhy5MwzxKkLBD2GmKNgvMYqXgo53iYqQ7Vu4vQbN2N/1/*)#2teuh09n";
 
-#[wasm_bindgen_test]
-async fn test_esplora_client() {
-    set_panic_hook();
+/// A key that never received any funds.
+const EMPTY_WIF: &str = "cQdMpJYK865CRmCjKL4RQj5fnwg4q8P7cLcUnKWSQWi8VccYpGAB";
 
-    let esplora_url = match NETWORK {
+fn esplora_url() -> &'static str {
+    match NETWORK {
         Network::Bitcoin => "https://blockstream.info/api",
         Network::Testnet => "https://blockstream.info/testnet/api",
         Network::Testnet4 => "https://blockstream.info/testnet/api",
         Network::Signet => "https://mutinynet.com/api",
         Network::Regtest => "https://localhost:3000",
         _ => panic!("unsupported network"),
-    };
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_esplora_client() {
+    set_panic_hook();
+
+    let esplora_url = esplora_url();
 
     let mut wallet =
         Wallet::create(NETWORK, DescriptorPair::new(EXTERNAL_DESC.into(), INTERNAL_DESC.into())).expect("wallet");
@@ -39,11 +52,13 @@ async fn test_esplora_client() {
     let block_height = wallet.latest_checkpoint().height();
     assert_eq!(block_height, 0);
 
-    wallet.reveal_addresses_to(KeychainKind::External, 5);
+    wallet
+        .reveal_addresses_to(KeychainKind::External, 5)
+        .expect("addresses");
 
     let sync_request = wallet.start_sync_with_revealed_spks();
     let update = blockchain_client
-        .sync(sync_request, PARALLEL_REQUESTS)
+        .sync(sync_request, PARALLEL_REQUESTS, None, None)
         .await
         .expect("sync");
     wallet.apply_update(update).expect("sync apply_update");
@@ -53,7 +68,7 @@ async fn test_esplora_client() {
 
     let full_scan_request = wallet.start_full_scan();
     let update = blockchain_client
-        .full_scan(full_scan_request, STOP_GAP, PARALLEL_REQUESTS)
+        .full_scan(full_scan_request, STOP_GAP, PARALLEL_REQUESTS, None, None)
         .await
         .expect("full_scan");
     wallet.apply_update(update).expect("full_scan apply_update");
@@ -61,6 +76,207 @@ async fn test_esplora_client() {
     let balance = wallet.balance();
     assert!(balance.total().to_sat() > 0);
 
+    let fee_rate = blockchain_client
+        .get_fee_estimates()
+        .await
+        .expect("fee_estimates")
+        .fee_rate(6)
+        .expect("fee_rate");
+    let address = wallet.peek_address(KeychainKind::External, 0).expect("address");
+    let psbt = wallet
+        .build_tx()
+        .add_recipient(Recipient::new(
+            Address::from_string(&address.address(), NETWORK).expect("address"),
+            Amount::from_sat(1_000),
+        ))
+        .fee_rate(fee_rate)
+        .finish()
+        .expect("finish");
+    assert!(psbt.fee_rate().expect("fee_rate") >= fee_rate);
+
     let loaded_wallet = Wallet::load(wallet.take_staged().unwrap()).expect("load");
     assert_eq!(loaded_wallet.balance(), wallet.balance());
 }
+
+#[wasm_bindgen_test]
+async fn test_sweep() {
+    set_panic_hook();
+
+    let mut wallet =
+        Wallet::create(NETWORK, DescriptorPair::new(EXTERNAL_DESC.into(), INTERNAL_DESC.into())).expect("wallet");
+    let client = EsploraClient::new(esplora_url()).expect("esplora_client");
+    let fee_rate = FeeRate::from_sat_per_vb(2).expect("fee_rate");
+
+    let error = wallet
+        .build_sweep(EMPTY_WIF, &client, fee_rate)
+        .await
+        .expect_err("empty key");
+    assert_eq!(error.code(), BdkErrorCode::NothingToSweep);
+
+    let mainnet_wif = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+    let error = wallet
+        .build_sweep(mainnet_wif, &client, fee_rate)
+        .await
+        .expect_err("mainnet key");
+    assert_eq!(error.code(), BdkErrorCode::NetworkMismatch);
+
+    let error = wallet
+        .build_sweep(EXTERNAL_DESC, &client, fee_rate)
+        .await
+        .expect_err("public descriptor");
+    assert_eq!(error.code(), BdkErrorCode::InvalidKey);
+}
+
+#[wasm_bindgen_test]
+async fn test_birthday() {
+    set_panic_hook();
+
+    let client = EsploraClient::new(esplora_url()).expect("esplora_client");
+
+    let birthday = client.get_birthday_at_height(1_000).await.expect("birthday at height");
+    assert_eq!(birthday.block_id().height(), 999);
+    let genesis = client.get_birthday_at_height(0).await.expect("birthday at genesis");
+    assert_eq!(genesis.block_id().height(), 0);
+    let error = client
+        .get_birthday_at_height(u32::MAX)
+        .await
+        .expect_err("height above the tip");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    // The last block timestamped more than two hours before the time of a block is a few blocks before it.
+    let block = client.get_birthday_at_height(100_001).await.expect("block");
+    let birthday = client
+        .get_birthday_at_time(block.time())
+        .await
+        .expect("birthday at time");
+    assert!(birthday.time() < block.time() - 2 * 60 * 60);
+    assert!(birthday.block_id().height() < block.block_id().height());
+
+    let mut wallet = Wallet::create_with_birthday(
+        NETWORK,
+        DescriptorPair::new(EXTERNAL_DESC.into(), INTERNAL_DESC.into()),
+        birthday,
+    )
+    .expect("wallet");
+    let spks_checked = Rc::new(Cell::new(0));
+    let progress = Closure::<dyn FnMut(ScanProgress)>::new({
+        let spks_checked = spks_checked.clone();
+        move |progress: ScanProgress| spks_checked.set(progress.spks_checked())
+    });
+    let request = wallet.start_full_scan_with_progress(progress.as_ref().clone().unchecked_into());
+    let mut blockchain_client = EsploraClient::new(esplora_url()).expect("esplora_client");
+    let update = blockchain_client
+        .full_scan(request, STOP_GAP, PARALLEL_REQUESTS, None, None)
+        .await
+        .expect("full_scan");
+    wallet.apply_update(update).expect("full_scan apply_update");
+    // Both keychains are scanned up to the stop gap at least.
+    assert!(spks_checked.get() >= 2 * STOP_GAP);
+
+    // The update connected to the birthday block, which stays in the chain of the wallet and starts the exports.
+    let height = birthday.block_id().height();
+    let checkpoint = wallet.latest_checkpoint().get(height).expect("birthday checkpoint");
+    assert_eq!(checkpoint.hash(), birthday.block_id().hash());
+    let export: Value = from_str(&wallet.export_fully_noded("Birthday").expect("export")).expect("json");
+    assert_eq!(export["blockheight"], height);
+
+    // Without a birthday the same scan leaves no checkpoint at that height, and the export rescans from genesis.
+    let mut full =
+        Wallet::create(NETWORK, DescriptorPair::new(EXTERNAL_DESC.into(), INTERNAL_DESC.into())).expect("wallet");
+    let request = full.start_full_scan();
+    assert_eq!(request.birthday(), None);
+    let update = blockchain_client
+        .full_scan(request, STOP_GAP, PARALLEL_REQUESTS, None, None)
+        .await
+        .expect("full_scan");
+    full.apply_update(update).expect("full_scan apply_update");
+    assert!(full.latest_checkpoint().get(height).is_none());
+    let export: Value = from_str(&full.export_fully_noded("Birthday").expect("export")).expect("json");
+    assert_eq!(export["blockheight"], 0);
+    // The history of the wallet is after the birthday, nothing was skipped.
+    assert_eq!(wallet.transactions().len(), full.transactions().len());
+
+    // With a birthday at the tip, the confirmed history is skipped but the scripts it used are not reused.
+    let birthday = client
+        .get_birthday_at_height(full.latest_checkpoint().height())
+        .await
+        .expect("birthday at tip");
+    let mut recent = Wallet::create_with_birthday(
+        NETWORK,
+        DescriptorPair::new(EXTERNAL_DESC.into(), INTERNAL_DESC.into()),
+        birthday,
+    )
+    .expect("wallet");
+    let request = recent.start_full_scan();
+    assert_eq!(request.birthday(), Some(birthday.block_id().height()));
+    let update = blockchain_client
+        .full_scan(request, STOP_GAP, PARALLEL_REQUESTS, None, None)
+        .await
+        .expect("full_scan");
+    recent.apply_update(update).expect("full_scan apply_update");
+    assert!(recent.transactions().len() < full.transactions().len());
+    for keychain in [KeychainKind::External, KeychainKind::Internal] {
+        assert_eq!(
+            recent.reveal_next_address(keychain).expect("address").index(),
+            full.reveal_next_address(keychain).expect("address").index()
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_cancellation() {
+    set_panic_hook();
+
+    let mut wallet =
+        Wallet::create(NETWORK, DescriptorPair::new(EXTERNAL_DESC.into(), INTERNAL_DESC.into())).expect("wallet");
+    let mut client = EsploraClient::new(esplora_url()).expect("esplora_client");
+
+    let error = client
+        .full_scan(
+            wallet.start_full_scan(),
+            STOP_GAP,
+            PARALLEL_REQUESTS,
+            Some(AbortSignal::abort()),
+            None,
+        )
+        .await
+        .expect_err("aborted signal");
+    assert_eq!(error.code(), BdkErrorCode::Aborted);
+
+    let error = client
+        .full_scan(
+            wallet.start_full_scan(),
+            STOP_GAP,
+            PARALLEL_REQUESTS,
+            Some(AbortSignal::timeout_with_u32(1)),
+            None,
+        )
+        .await
+        .expect_err("signal aborted during the scan");
+    assert_eq!(error.code(), BdkErrorCode::Timeout);
+
+    wallet
+        .reveal_addresses_to(KeychainKind::External, 5)
+        .expect("addresses");
+    let error = client
+        .sync(wallet.start_sync_with_revealed_spks(), PARALLEL_REQUESTS, None, Some(1))
+        .await
+        .expect_err("timeout");
+    assert_eq!(error.code(), BdkErrorCode::Timeout);
+
+    // Nothing of the cancelled scans reached the wallet.
+    assert_eq!(wallet.latest_checkpoint().height(), 0);
+    assert!(wallet.transactions().is_empty());
+
+    let update = client
+        .sync(
+            wallet.start_sync_with_revealed_spks(),
+            PARALLEL_REQUESTS,
+            Some(AbortSignal::timeout_with_u32(60_000)),
+            Some(60_000),
+        )
+        .await
+        .expect("sync");
+    wallet.apply_update(update).expect("sync apply_update");
+    assert!(wallet.latest_checkpoint().height() > 0);
+}
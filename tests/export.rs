@@ -0,0 +1,288 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use std::{collections::BTreeSet, str::FromStr, sync::Arc};
+
+use bdk_wallet::{
+    bitcoin::{
+        bip32::{DerivationPath, Xpriv, Xpub},
+        constants::genesis_block,
+        hashes::Hash,
+        key::Secp256k1,
+        BlockHash, Network as BdkNetwork,
+    },
+    chain::{local_chain::CheckPoint, BlockId, ConfirmationBlockTime, TxUpdate},
+    serde_json::{from_str, json, Value},
+    Update as BdkUpdate,
+};
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    generic_json_to_descriptors, seed_to_descriptor, set_panic_hook,
+    types::{AddressType, BdkErrorCode, DescriptorPair, KeychainKind, Network},
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+use common::{funding_transaction, public};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const NETWORK: Network = Network::Testnet;
+const SEED: [u8; 64] = [7; 64];
+const BIRTH_HEIGHT: u32 = 100;
+const BIRTH_TIME: u64 = 1_700_000_000;
+
+fn new_wallet(address_type: AddressType) -> Wallet {
+    let descriptors = seed_to_descriptor(&SEED, NETWORK, address_type).expect("descriptors");
+    Wallet::create(NETWORK, descriptors).expect("wallet")
+}
+
+/// Confirm a transaction paying to the next external address of the wallet at `BIRTH_HEIGHT`.
+fn confirm_transaction(wallet: &mut Wallet) {
+    let tx = funding_transaction(wallet, 100_000);
+
+    let genesis = BlockId {
+        height: 0,
+        hash: genesis_block(BdkNetwork::Testnet).block_hash(),
+    };
+    let block = BlockId {
+        height: BIRTH_HEIGHT,
+        hash: BlockHash::from_byte_array([2; 32]),
+    };
+    let anchor = ConfirmationBlockTime {
+        block_id: block,
+        confirmation_time: BIRTH_TIME,
+    };
+    let update = BdkUpdate {
+        tx_update: TxUpdate {
+            anchors: BTreeSet::from([(anchor, tx.compute_txid())]),
+            txs: vec![Arc::new(tx)],
+            ..Default::default()
+        },
+        chain: Some(CheckPoint::from_block_ids([genesis, block]).expect("checkpoint")),
+        ..Default::default()
+    };
+    wallet.apply_update_at(update.into(), BIRTH_TIME).expect("apply_update");
+}
+
+#[wasm_bindgen_test]
+async fn test_export_fully_noded() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet(AddressType::P2wpkh);
+    let export: Value = from_str(&wallet.export_fully_noded("Savings").expect("export")).expect("json");
+    assert_eq!(export["blockheight"], 0);
+
+    confirm_transaction(&mut wallet);
+    let export: Value = from_str(&wallet.export_fully_noded("Savings").expect("export")).expect("json");
+    let descriptor = wallet
+        .public_descriptor(KeychainKind::External)
+        .expect("public_descriptor");
+    assert_eq!(
+        export,
+        json!({
+            "descriptor": descriptor.split('#').next().unwrap(),
+            "blockheight": BIRTH_HEIGHT,
+            "label": "Savings",
+        })
+    );
+
+    let key = descriptor.split_once('(').unwrap().1.split_once("/0/*").unwrap().0;
+    let descriptors = DescriptorPair::new(format!("wpkh({key}/0/*)"), format!("wpkh({key}/2/*)"));
+    let wallet = Wallet::create(NETWORK, descriptors).expect("wallet");
+    let error = wallet
+        .export_fully_noded("Savings")
+        .expect_err("unrelated change descriptor");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+
+    let descriptors = DescriptorPair::new(
+        format!("wsh(and_v(v:pk({key}/0/*),older(144)))"),
+        format!("wsh(and_v(v:pk({key}/1/*),older(144)))"),
+    );
+    let wallet = Wallet::create(NETWORK, descriptors).expect("wallet");
+    let error = wallet.export_fully_noded("Savings").expect_err("unsupported by Core");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+}
+
+#[wasm_bindgen_test]
+async fn test_export_core_descriptors() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet(AddressType::P2tr);
+    confirm_transaction(&mut wallet);
+    wallet
+        .reveal_addresses_to(KeychainKind::External, 4)
+        .expect("addresses");
+
+    let request: Value = from_str(&wallet.export_core_descriptors()).expect("json");
+    assert_eq!(
+        request,
+        json!([
+            {
+                "desc": wallet.public_descriptor(KeychainKind::External).expect("public_descriptor"),
+                "timestamp": BIRTH_TIME,
+                "active": true,
+                "internal": false,
+                "range": [0, 1004],
+                "next_index": 5,
+            },
+            {
+                "desc": wallet.public_descriptor(KeychainKind::Internal).expect("public_descriptor"),
+                "timestamp": BIRTH_TIME,
+                "active": true,
+                "internal": true,
+                "range": [0, 999],
+                "next_index": 0,
+            },
+        ])
+    );
+
+    let descriptor = wallet
+        .public_descriptor(KeychainKind::External)
+        .expect("public_descriptor");
+    let key = descriptor.split_once('(').unwrap().1.split_once("/0/*").unwrap().0;
+    let descriptors = DescriptorPair::new(format!("tr({key}/0/0)"), format!("tr({key}/1/0)"));
+    let wallet = Wallet::create(NETWORK, descriptors).expect("wallet");
+    let request: Value = from_str(&wallet.export_core_descriptors()).expect("json");
+    assert_eq!(
+        request,
+        json!([
+            {
+                "desc": wallet.public_descriptor(KeychainKind::External).expect("public_descriptor"),
+                "timestamp": 0,
+                "internal": false,
+            },
+            {
+                "desc": wallet.public_descriptor(KeychainKind::Internal).expect("public_descriptor"),
+                "timestamp": 0,
+                "internal": true,
+            },
+        ])
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_export_electrum() {
+    set_panic_hook();
+
+    let secp = Secp256k1::new();
+    let master = Xpriv::new_master(BdkNetwork::Testnet, &SEED).expect("master");
+    let fingerprint = master.fingerprint(&secp).to_string();
+
+    let wallet = new_wallet(AddressType::P2wpkh);
+    let file: Value = from_str(&wallet.export_electrum().expect("export")).expect("json");
+    assert_eq!(file["wallet_type"], "standard");
+    assert_eq!(file["seed_version"], 17);
+    let keystore = &file["keystore"];
+    assert_eq!(keystore["type"], "bip32");
+    assert_eq!(keystore["derivation"], "m/84'/1'/0'");
+    assert_eq!(keystore["root_fingerprint"], fingerprint);
+    assert!(keystore["xpub"].as_str().unwrap().starts_with("vpub"));
+
+    let wallet = new_wallet(AddressType::P2sh);
+    let file: Value = from_str(&wallet.export_electrum().expect("export")).expect("json");
+    assert!(file["keystore"]["xpub"].as_str().unwrap().starts_with("upub"));
+
+    let keys = (1..=3u8)
+        .map(|seed| {
+            let master = Xpriv::new_master(BdkNetwork::Testnet, &[seed; 64]).expect("master");
+            let path = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+            let xpub = Xpub::from_priv(&secp, &master.derive_priv(&secp, &path).unwrap());
+            format!("[{}/48'/1'/0'/2']{xpub}/<0;1>/*", master.fingerprint(&secp))
+        })
+        .collect::<Vec<_>>();
+    let multisig =
+        Wallet::create_from_multipath(NETWORK, &format!("wsh(sortedmulti(2,{}))", keys.join(","))).expect("multisig");
+    let file: Value = from_str(&multisig.export_electrum().expect("export")).expect("json");
+    assert_eq!(file["wallet_type"], "2of3");
+    for index in 1..=3 {
+        let keystore = &file[format!("x{index}/")];
+        assert_eq!(keystore["derivation"], "m/48'/1'/0'/2'");
+        assert!(keystore["xpub"].as_str().unwrap().starts_with("Vpub"));
+    }
+
+    let wallet = new_wallet(AddressType::P2tr);
+    let error = wallet.export_electrum().expect_err("taproot");
+    assert_eq!(error.code(), BdkErrorCode::UnsupportedAddressType);
+
+    let multi = Wallet::create_from_multipath(NETWORK, &format!("wsh(multi(2,{}))", keys.join(","))).expect("multi");
+    let error = multi.export_electrum().expect_err("unsorted multisig");
+    assert_eq!(error.code(), BdkErrorCode::UnsupportedAddressType);
+}
+
+/// A generic JSON export of a Coldcard for `SEED`.
+fn generic_json() -> Value {
+    let secp = Secp256k1::new();
+    let master = Xpriv::new_master(BdkNetwork::Testnet, &SEED).expect("master");
+    let account = |deriv: &str, name: &str| {
+        let path = DerivationPath::from_str(deriv).unwrap();
+        let xpriv = master.derive_priv(&secp, &path).unwrap();
+        json!({
+            "name": name,
+            "xfp": xpriv.fingerprint(&secp).to_string().to_uppercase(),
+            "deriv": deriv,
+            "xpub": Xpub::from_priv(&secp, &xpriv).to_string(),
+        })
+    };
+
+    json!({
+        "chain": "XTN",
+        "xfp": master.fingerprint(&secp).to_string().to_uppercase(),
+        "account": 0,
+        "xpub": Xpub::from_priv(&secp, &master).to_string(),
+        "bip44": account("m/44h/1h/0h", "p2pkh"),
+        "bip49": account("m/49h/1h/0h", "p2sh-p2wpkh"),
+        "bip84": account("m/84h/1h/0h", "p2wpkh"),
+        "bip86": account("m/86h/1h/0h", "p2tr"),
+        "bip48_2": account("m/48h/1h/0h/2h", "p2wsh"),
+    })
+}
+
+#[wasm_bindgen_test]
+async fn test_generic_json_to_descriptors() {
+    set_panic_hook();
+
+    let export = generic_json();
+    let descriptors = generic_json_to_descriptors(&export.to_string(), NETWORK).expect("descriptors");
+    let address_types = [
+        AddressType::P2pkh,
+        AddressType::P2sh,
+        AddressType::P2wpkh,
+        AddressType::P2tr,
+    ];
+    assert_eq!(descriptors.len(), address_types.len());
+    for (descriptors, address_type) in descriptors.iter().zip(address_types) {
+        let expected = seed_to_descriptor(&SEED, NETWORK, address_type).expect("descriptors");
+        assert_eq!((descriptors.external(), descriptors.internal()), public(&expected));
+    }
+
+    let mut single = export.clone();
+    for section in ["bip44", "bip49", "bip86"] {
+        single.as_object_mut().unwrap().remove(section);
+    }
+    let descriptors = generic_json_to_descriptors(&single.to_string(), NETWORK).expect("descriptors");
+    assert_eq!(descriptors.len(), 1);
+    assert!(descriptors[0].external().starts_with("wpkh("));
+
+    let error = generic_json_to_descriptors(&export.to_string(), Network::Bitcoin).expect_err("network");
+    assert_eq!(error.code(), BdkErrorCode::NetworkMismatch);
+
+    let mut mainnet = export.clone();
+    mainnet["chain"] = json!("BTC");
+    let error = generic_json_to_descriptors(&mainnet.to_string(), Network::Bitcoin).expect_err("testnet keys");
+    assert_eq!(error.code(), BdkErrorCode::NetworkMismatch);
+
+    let mut multisig = export.clone();
+    for section in ["bip44", "bip49", "bip84", "bip86"] {
+        multisig.as_object_mut().unwrap().remove(section);
+    }
+    let error = generic_json_to_descriptors(&multisig.to_string(), NETWORK).expect_err("no single key account");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+
+    let error = generic_json_to_descriptors("{\"chain\": \"XTN\"}", NETWORK).expect_err("missing xfp");
+    assert_eq!(error.code(), BdkErrorCode::Serialization);
+}
@@ -0,0 +1,79 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use std::collections::HashMap;
+
+use bitcoindevkit::{
+    set_panic_hook,
+    types::{BdkErrorCode, FeeEstimates, FeeRate},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn test_fee_rate_conversions() {
+    set_panic_hook();
+
+    let fee_rate = FeeRate::from_sat_per_vb(2).expect("fee_rate");
+    assert_eq!(fee_rate.to_sat_per_kwu(), 500);
+    assert!(fee_rate.equals(&FeeRate::from_sat_per_kwu(500)));
+
+    // Bitcoin Core's minimum relay fee.
+    let fee_rate = FeeRate::from_btc_per_kvb(0.00001).expect("fee_rate");
+    assert_eq!(fee_rate.to_sat_per_vb_ceil(), 1);
+    assert_eq!(fee_rate.to_sat_per_kwu(), 250);
+
+    let fee_rate = FeeRate::from_btc_per_kvb(0.00001234).expect("fee_rate");
+    assert_eq!(fee_rate.to_sat_per_kwu(), 309);
+    assert_eq!(fee_rate.to_sat_per_vb_floor(), 1);
+    assert_eq!(fee_rate.to_sat_per_vb_ceil(), 2);
+
+    let error = FeeRate::from_btc_per_kvb(-1.0).expect_err("negative");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+    let error = FeeRate::from_btc_per_kvb(f64::NAN).expect_err("NaN");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+    let error = FeeRate::from_sat_per_vb(u64::MAX).expect_err("too high");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+}
+
+#[wasm_bindgen_test]
+async fn test_fee_calculation() {
+    set_panic_hook();
+
+    let fee_rate = FeeRate::from_sat_per_kwu(625); // 2.5 sat/vB
+    assert_eq!(fee_rate.fee_for_vsize(141).expect("fee").to_sat(), 353);
+    assert_eq!(fee_rate.fee_for_weight(564).expect("fee").to_sat(), 353);
+
+    let error = FeeRate::from_sat_per_kwu(u64::MAX)
+        .fee_for_weight(2)
+        .expect_err("overflow");
+    assert_eq!(error.code(), BdkErrorCode::AmountOverflow);
+
+    assert_eq!(fee_rate.display(true), "2.5 sat/vB");
+    assert_eq!(fee_rate.display(false), "2.5");
+    assert_eq!(FeeRate::from_sat_per_kwu(250).display(true), "1 sat/vB");
+    assert_eq!(FeeRate::from_sat_per_kwu(251).display(true), "1.004 sat/vB");
+
+    let low = FeeRate::from_sat_per_kwu(250);
+    assert_eq!(low.compare(&fee_rate), -1);
+    assert_eq!(fee_rate.compare(&low), 1);
+    assert_eq!(low.compare(&FeeRate::from_sat_per_kwu(250)), 0);
+}
+
+#[wasm_bindgen_test]
+async fn test_fee_estimates() {
+    set_panic_hook();
+
+    let estimates: FeeEstimates = HashMap::from([(1, 10.5), (6, 4.0), (144, 1.001)]).into();
+
+    assert_eq!(estimates.targets(), vec![1, 6, 144]);
+    assert_eq!(estimates.fee_rate(1).expect("fee_rate").to_sat_per_kwu(), 2625);
+    assert_eq!(estimates.fee_rate(5).expect("fee_rate").to_sat_per_kwu(), 2625);
+    assert_eq!(estimates.fee_rate(6).expect("fee_rate").to_sat_per_kwu(), 1000);
+    assert_eq!(estimates.fee_rate(1008).expect("fee_rate").to_sat_per_kwu(), 251);
+    assert!(estimates.fee_rate(0).is_none());
+}
@@ -0,0 +1,232 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bdk_wallet::serde_json::{from_str, json, Value};
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    seed_to_descriptor, set_panic_hook,
+    types::{AddressType, BdkErrorCode, ChangeSet, KeychainKind, LabelType, Network, Transaction},
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+use common::receive;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const NETWORK: Network = Network::Testnet;
+const TXID: &str = "f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd";
+const PUBKEY: &str = "0283409659355b6d1cc3c32decd5d561abaac86c37a353b52895a5e6c196d6f448";
+const XPUB: &str =
+    "tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq";
+
+fn new_wallet() -> Wallet {
+    let descriptors = seed_to_descriptor(&[7; 64], NETWORK, AddressType::P2wpkh).expect("descriptors");
+    Wallet::create(NETWORK, descriptors).expect("wallet")
+}
+
+fn records(jsonl: &str) -> Vec<Value> {
+    jsonl.lines().map(|line| from_str(line).expect("record")).collect()
+}
+
+#[wasm_bindgen_test]
+async fn test_labels() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    let address = wallet.reveal_next_address(KeychainKind::External).expect("address");
+    assert_eq!(address.label(), None);
+
+    wallet
+        .set_label(LabelType::Addr, &address.address(), Some("Donations".into()))
+        .expect("set_label");
+    assert_eq!(
+        wallet
+            .peek_address(KeychainKind::External, 0)
+            .expect("address")
+            .label()
+            .as_deref(),
+        Some("Donations")
+    );
+    assert_eq!(
+        wallet.list_unused_addresses(KeychainKind::External).expect("addresses")[0]
+            .label()
+            .as_deref(),
+        Some("Donations")
+    );
+    assert_eq!(
+        wallet.peek_address(KeychainKind::External, 1).expect("address").label(),
+        None
+    );
+
+    // References are compared in their canonical form.
+    wallet
+        .set_label(LabelType::Tx, &TXID.to_uppercase(), Some("Rent".into()))
+        .expect("set_label");
+    assert_eq!(
+        wallet.label(LabelType::Tx, TXID).expect("label").as_deref(),
+        Some("Rent")
+    );
+
+    // Transactions of the history carry their label.
+    let txid = receive(&mut wallet, 10_000).txid.to_string();
+    assert_eq!(wallet.transactions()[0].label(), None);
+    wallet
+        .set_label(LabelType::Tx, &txid, Some("Salary".into()))
+        .expect("set_label");
+    let transactions = wallet.transactions();
+    assert_eq!(transactions[0].txid(), txid);
+    assert_eq!(transactions[0].label().as_deref(), Some("Salary"));
+    assert_eq!(
+        transactions[0],
+        Transaction::from_hex(&transactions[0].to_hex()).expect("transaction")
+    );
+
+    wallet.set_label(LabelType::Tx, TXID, None).expect("remove label");
+    assert_eq!(wallet.label(LabelType::Tx, TXID).expect("label"), None);
+    wallet
+        .set_label(LabelType::Tx, TXID, Some(String::new()))
+        .expect("empty label");
+    assert_eq!(wallet.label(LabelType::Tx, TXID).expect("label"), None);
+
+    for (label_type, reference) in [
+        (LabelType::Tx, "f91d0a8a"),
+        (LabelType::Addr, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"),
+        (LabelType::Pubkey, "02"),
+        (LabelType::Input, TXID),
+        (LabelType::Output, "txid:0"),
+        (LabelType::Xpub, PUBKEY),
+    ] {
+        let error = wallet
+            .set_label(label_type, reference, Some("label".into()))
+            .expect_err(reference);
+        assert_eq!(error.code(), BdkErrorCode::InvalidLabel, "{reference}");
+    }
+    let error = wallet.label(LabelType::__Invalid, TXID).expect_err("invalid type");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+}
+
+#[wasm_bindgen_test]
+async fn test_labels_import_export() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    let address = wallet
+        .peek_address(KeychainKind::Internal, 3)
+        .expect("address")
+        .address();
+    let jsonl = [
+        json!({ "type": "tx", "ref": TXID, "label": "Rent", "origin": "wpkh([27f9035f/84'/1'/0'])" }),
+        json!({ "type": "addr", "ref": address, "label": "Change" }),
+        json!({ "type": "pubkey", "ref": PUBKEY, "label": "Cold key" }),
+        json!({ "type": "input", "ref": format!("{TXID}:0"), "label": "Spent coin", "spendable": true }),
+        json!({ "type": "output", "ref": format!("{TXID}:1"), "label": "Dust", "spendable": false }),
+        json!({ "type": "xpub", "ref": XPUB, "label": "Account 0" }),
+        json!({ "type": "note", "ref": "anything", "label": "Unknown types are skipped" }),
+    ]
+    .map(|record| record.to_string())
+    .join("\n\n");
+    wallet.import_labels(&jsonl).expect("import");
+
+    assert_eq!(
+        wallet.label(LabelType::Tx, TXID).expect("label").as_deref(),
+        Some("Rent")
+    );
+    assert_eq!(
+        wallet
+            .peek_address(KeychainKind::Internal, 3)
+            .expect("address")
+            .label()
+            .as_deref(),
+        Some("Change")
+    );
+    assert_eq!(
+        wallet
+            .label(LabelType::Output, &format!("{TXID}:1"))
+            .expect("label")
+            .as_deref(),
+        Some("Dust")
+    );
+
+    let exported = records(&wallet.export_labels().expect("export"));
+    assert_eq!(exported.len(), 6);
+    assert_eq!(
+        exported[0],
+        json!({ "type": "tx", "ref": TXID, "label": "Rent", "origin": "wpkh([27f9035f/84'/1'/0'])" })
+    );
+    assert_eq!(
+        exported[3],
+        json!({ "type": "input", "ref": format!("{TXID}:0"), "label": "Spent coin" })
+    );
+    assert_eq!(
+        exported[4],
+        json!({ "type": "output", "ref": format!("{TXID}:1"), "label": "Dust", "spendable": false })
+    );
+
+    let mut other = new_wallet();
+    other.import_labels(&wallet.export_labels().unwrap()).expect("import");
+    assert_eq!(other.export_labels().unwrap(), wallet.export_labels().unwrap());
+
+    let invalid = format!(
+        "{}\n{}",
+        json!({ "type": "tx", "ref": TXID, "label": "Other" }),
+        json!({ "type": "output", "ref": TXID, "label": "Missing index" })
+    );
+    let error = wallet.import_labels(&invalid).expect_err("invalid reference");
+    assert_eq!(error.code(), BdkErrorCode::InvalidLabel);
+    assert!(error.message().starts_with("line 2:"), "{}", error.message());
+    assert_eq!(
+        wallet.label(LabelType::Tx, TXID).expect("label").as_deref(),
+        Some("Rent")
+    );
+
+    for invalid in ["{", "[]", "{\"type\": \"tx\"}", "{\"type\": \"tx\", \"ref\": 1}"] {
+        let error = wallet.import_labels(invalid).expect_err(invalid);
+        assert_eq!(error.code(), BdkErrorCode::InvalidLabel, "{invalid}");
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_labels_changeset() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    let mut changeset = wallet.take_staged().expect("initial changeset");
+    assert!(wallet.take_staged().is_none());
+
+    wallet
+        .set_label(LabelType::Tx, TXID, Some("Rent".into()))
+        .expect("set_label");
+    wallet
+        .set_label(LabelType::Xpub, XPUB, Some("Account 0".into()))
+        .expect("set_label");
+    let labels = wallet.take_staged().expect("labels changeset");
+    assert!(!labels.is_empty());
+    let json: Value = from_str(&labels.to_json().expect("to_json")).expect("json");
+    assert_eq!(json["labels"].as_array().map(Vec::len), Some(2));
+    changeset.merge(labels);
+
+    wallet.set_label(LabelType::Xpub, XPUB, None).expect("remove label");
+    changeset.merge(wallet.take_staged().expect("removal changeset"));
+
+    let changeset = ChangeSet::from_json(&changeset.to_json().expect("to_json")).expect("from_json");
+    let loaded = Wallet::load(changeset).expect("load");
+    assert_eq!(
+        loaded.label(LabelType::Tx, TXID).expect("label").as_deref(),
+        Some("Rent")
+    );
+    assert_eq!(loaded.label(LabelType::Xpub, XPUB).expect("label"), None);
+    assert_eq!(records(&loaded.export_labels().unwrap()).len(), 1);
+
+    let mut wallet = new_wallet();
+    wallet
+        .set_label(LabelType::Tx, TXID, Some("Rent".into()))
+        .expect("set_label");
+    let changeset = wallet.take_staged().expect("changeset");
+    let js = changeset.to_js().expect("to_js");
+    assert_eq!(ChangeSet::from_js(js).expect("from_js"), changeset);
+}
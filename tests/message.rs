@@ -0,0 +1,147 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bitcoindevkit::{
+    bitcoin::Wallet,
+    set_panic_hook,
+    types::{Address, BdkErrorCode, DescriptorPair, KeychainKind, Network, SignatureFormat},
+    verify_message,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const NETWORK: Network = Network::Bitcoin;
+// Private key and addresses of the BIP322 test vectors.
+const PRIVATE_KEY: &str = "L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k";
+const OTHER_KEY: &str = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+const P2TR_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+const EMPTY_SIGNATURE: &str = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+const HELLO_SIGNATURE: &str = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+const P2TR_HELLO_SIGNATURE: &str =
+    "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+
+fn single_key_wallet(script: &str) -> Wallet {
+    Wallet::create(
+        NETWORK,
+        DescriptorPair::new(format!("{script}({PRIVATE_KEY})"), format!("{script}({OTHER_KEY})")),
+    )
+    .expect("wallet")
+}
+
+fn address(address: &str) -> Address {
+    Address::from_string(address, NETWORK).expect("address")
+}
+
+#[wasm_bindgen_test]
+async fn test_verify_vectors() {
+    set_panic_hook();
+
+    let p2wpkh = address(P2WPKH_ADDRESS);
+    assert!(verify_message(&p2wpkh, "", EMPTY_SIGNATURE).expect("verify"));
+    assert!(verify_message(&p2wpkh, "Hello World", HELLO_SIGNATURE).expect("verify"));
+    assert!(!verify_message(&p2wpkh, "", HELLO_SIGNATURE).expect("verify"));
+    assert!(!verify_message(&p2wpkh, "Hello World", EMPTY_SIGNATURE).expect("verify"));
+
+    let p2tr = address(P2TR_ADDRESS);
+    assert!(verify_message(&p2tr, "Hello World", P2TR_HELLO_SIGNATURE).expect("verify"));
+    assert!(!verify_message(&p2tr, "Hello", P2TR_HELLO_SIGNATURE).expect("verify"));
+    assert!(!verify_message(&p2tr, "Hello World", HELLO_SIGNATURE).expect("verify"));
+
+    let error = verify_message(&p2wpkh, "", "not base64!").expect_err("invalid signature");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+}
+
+#[wasm_bindgen_test]
+async fn test_sign_message() {
+    set_panic_hook();
+
+    let wallet = single_key_wallet("wpkh");
+    let p2wpkh = address(P2WPKH_ADDRESS);
+    let signature = wallet.sign_message(&p2wpkh, "", SignatureFormat::Simple).expect("sign");
+    assert_eq!(signature, EMPTY_SIGNATURE);
+    let signature = wallet
+        .sign_message(&p2wpkh, "Hello World", SignatureFormat::Simple)
+        .expect("sign");
+    assert_eq!(signature, HELLO_SIGNATURE);
+
+    let signature = wallet
+        .sign_message(&p2wpkh, "Hello World", SignatureFormat::Full)
+        .expect("sign");
+    assert!(verify_message(&p2wpkh, "Hello World", &signature).expect("verify"));
+    assert!(!verify_message(&p2wpkh, "Hello", &signature).expect("verify"));
+
+    let error = wallet
+        .sign_message(&p2wpkh, "Hello World", SignatureFormat::Legacy)
+        .expect_err("legacy p2wpkh");
+    assert_eq!(error.code(), BdkErrorCode::UnsupportedAddressType);
+    let error = wallet
+        .sign_message(&address(P2TR_ADDRESS), "Hello World", SignatureFormat::Simple)
+        .expect_err("foreign address");
+    assert_eq!(error.code(), BdkErrorCode::InvalidAddress);
+    let error = wallet
+        .sign_message(&p2wpkh, "Hello World", SignatureFormat::__Invalid)
+        .expect_err("invalid format");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let wallet = single_key_wallet("tr");
+    let p2tr = address(P2TR_ADDRESS);
+    let signature = wallet
+        .sign_message(&p2tr, "Hello World", SignatureFormat::Simple)
+        .expect("sign");
+    assert!(verify_message(&p2tr, "Hello World", &signature).expect("verify"));
+    assert!(!verify_message(&p2tr, "", &signature).expect("verify"));
+}
+
+#[wasm_bindgen_test]
+async fn test_sign_message_p2pkh() {
+    set_panic_hook();
+
+    let mut wallet = single_key_wallet("pkh");
+    let p2pkh: Address = wallet
+        .reveal_next_address(KeychainKind::External)
+        .expect("address")
+        .address
+        .clone()
+        .into();
+    for format in [SignatureFormat::Legacy, SignatureFormat::Full] {
+        let signature = wallet.sign_message(&p2pkh, "Hello World", format).expect("sign");
+        assert!(verify_message(&p2pkh, "Hello World", &signature).expect("verify"));
+        assert!(!verify_message(&p2pkh, "Hello", &signature).expect("verify"));
+    }
+
+    let error = wallet
+        .sign_message(&p2pkh, "Hello World", SignatureFormat::Simple)
+        .expect_err("simple p2pkh");
+    assert_eq!(error.code(), BdkErrorCode::UnsupportedAddressType);
+}
+
+#[wasm_bindgen_test]
+async fn test_sign_message_unsupported() {
+    set_panic_hook();
+
+    // The library does not produce signatures that `verify_message` cannot verify.
+    for descriptor in [format!("sh(wpkh({PRIVATE_KEY}))"), format!("wsh(pk({PRIVATE_KEY}))")] {
+        let mut wallet = Wallet::create(
+            NETWORK,
+            DescriptorPair::new(descriptor.clone(), format!("wpkh({OTHER_KEY})")),
+        )
+        .expect("wallet");
+        let address: Address = wallet
+            .reveal_next_address(KeychainKind::External)
+            .expect("address")
+            .address
+            .clone()
+            .into();
+        for format in [SignatureFormat::Simple, SignatureFormat::Full] {
+            let error = wallet
+                .sign_message(&address, "Hello World", format)
+                .expect_err("unsupported address type");
+            assert_eq!(error.code(), BdkErrorCode::UnsupportedAddressType, "{descriptor}");
+        }
+    }
+}
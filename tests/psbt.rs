@@ -0,0 +1,489 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bdk_wallet::{
+    bitcoin::{
+        bip32::{Xpriv, Xpub},
+        psbt::PsbtSighashType,
+        secp256k1::Secp256k1,
+        sighash::EcdsaSighashType,
+        Amount as BdkAmount, Network as BdkNetwork, NetworkKind, Psbt as BdkPsbt,
+    },
+    SignOptions, Wallet as BdkWallet,
+};
+use bitcoindevkit::{
+    bitcoin::{Recipient, Wallet},
+    seed_to_descriptor, set_panic_hook,
+    types::{Address, AddressType, Amount, BdkErrorCode, DescriptorPair, ExternalSigner, KeychainKind, Network, Psbt},
+};
+use js_sys::{Object, Promise, Reflect};
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+mod common;
+
+use common::receive;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const NETWORK: Network = Network::Testnet;
+const RECIPIENT_ADDRESS: &str = "tb1qd28npep0s8frcm3y7dxqajkcy2m40eysplyr9v";
+
+fn master_key(index: u8) -> Xpriv {
+    Xpriv::new_master(NetworkKind::Test, &[index + 1; 32]).unwrap()
+}
+
+/// The keys of `count` cosigners deriving with `keychain`, private for the key at `index`.
+fn cosigner_keys(index: usize, count: usize, keychain: u32) -> Vec<String> {
+    let secp = Secp256k1::new();
+    (0..count)
+        .map(|i| {
+            let xprv = master_key(i as u8);
+            let key = if i == index {
+                xprv.to_string()
+            } else {
+                Xpub::from_priv(&secp, &xprv).to_string()
+            };
+            format!("{key}/{keychain}/*")
+        })
+        .collect()
+}
+
+/// The wallet of one of the cosigners of a 2-of-3 multisig, holding the private key at `index`.
+fn cosigner(index: usize) -> Wallet {
+    let descriptor = |keychain| format!("wsh(sortedmulti(2,{}))", cosigner_keys(index, 3, keychain).join(","));
+    Wallet::create(NETWORK, DescriptorPair::new(descriptor(0), descriptor(1))).expect("wallet")
+}
+
+/// The wallet of one of the two keys of `and_v(v:pk(A),pk(B))`, a policy that is not a multisig, holding the
+/// private key at `index`.
+fn and_cosigner(index: usize) -> Wallet {
+    let descriptor = |keychain| {
+        let keys = cosigner_keys(index, 2, keychain);
+        format!("wsh(and_v(v:pk({}),pk({})))", keys[0], keys[1])
+    };
+    Wallet::create(NETWORK, DescriptorPair::new(descriptor(0), descriptor(1))).expect("wallet")
+}
+
+fn fingerprint(index: u8) -> String {
+    master_key(index).fingerprint(&Secp256k1::new()).to_string()
+}
+
+/// Build a PSBT spending from the multisig, funded through the wallet of the first cosigner.
+fn unsigned_psbt(wallet: &mut Wallet, amount: u64) -> Psbt {
+    receive(wallet, 100_000);
+
+    let recipient = Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address");
+    wallet
+        .build_tx()
+        .add_recipient(Recipient::new(recipient, Amount::from_sat(amount)))
+        .finish()
+        .expect("finish")
+}
+
+/// A JavaScript signer object calling `sign_psbt`, and the number of times it was called.
+fn js_signer(sign_psbt: impl Fn(String) -> Promise + 'static) -> (ExternalSigner, Rc<Cell<u32>>) {
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    let closure = Closure::<dyn Fn(String) -> Promise>::new(move |psbt| {
+        counter.set(counter.get() + 1);
+        sign_psbt(psbt)
+    });
+    let signer = Object::new();
+    Reflect::set(&signer, &"sign_psbt".into(), closure.as_ref()).unwrap();
+    closure.forget();
+    (signer.unchecked_into(), calls)
+}
+
+/// A signer backed by the wallet of a cosigner, as a hardware wallet would be.
+fn cosigner_signer(index: usize) -> (ExternalSigner, Rc<Cell<u32>>) {
+    wallet_signer(cosigner(index))
+}
+
+fn wallet_signer(wallet: Wallet) -> (ExternalSigner, Rc<Cell<u32>>) {
+    js_signer(move |psbt| {
+        let mut psbt = Psbt::from_string(&psbt).unwrap();
+        wallet.sign(&mut psbt).unwrap();
+        Promise::resolve(&psbt.to_string().into())
+    })
+}
+
+#[wasm_bindgen_test]
+async fn test_multisig_workflow() {
+    set_panic_hook();
+
+    let mut wallets = (0..3).map(cosigner).collect::<Vec<_>>();
+    let mut psbt = unsigned_psbt(&mut wallets[0], 50_000);
+
+    let status = &psbt.signature_status()[0];
+    assert!(!status.finalized());
+    assert_eq!(status.required(), Some(2));
+    assert_eq!(status.remaining(), Some(2));
+    assert!(status.signed().is_empty());
+    let mut fingerprints = status
+        .missing()
+        .iter()
+        .map(|key| key.fingerprint().expect("fingerprint"))
+        .collect::<Vec<_>>();
+    fingerprints.sort();
+    let mut expected = (0..3).map(fingerprint).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(fingerprints, expected);
+
+    // Each cosigner signs its own copy, received serialized.
+    let mut second = Psbt::from_string(&psbt.to_string()).expect("from_string");
+    assert!(!wallets[0].sign(&mut psbt).expect("first sign"));
+    assert!(!wallets[1].sign(&mut second).expect("second sign"));
+
+    let status = &psbt.signature_status()[0];
+    assert_eq!(status.remaining(), Some(1));
+    assert_eq!(status.signed()[0].fingerprint(), Some(fingerprint(0)));
+    assert_eq!(status.signed()[0].derivation_path().as_deref(), Some("0/0"));
+    assert_eq!(status.missing().len(), 2);
+
+    psbt.combine(&second).expect("combine");
+    let status = &psbt.signature_status()[0];
+    assert_eq!(status.remaining(), Some(0));
+    assert_eq!(status.signed().len(), 2);
+    assert_eq!(status.missing()[0].fingerprint(), Some(fingerprint(2)));
+    // The cosigner's PSBT is borrowed, it can still be used.
+    assert_eq!(second.signature_status()[0].signed().len(), 1);
+
+    // Combining is idempotent.
+    psbt.combine(&second).expect("combine again");
+    assert_eq!(psbt.signature_status()[0].signed().len(), 2);
+
+    assert!(wallets[2].sign(&mut psbt).expect("finalize"));
+    assert!(psbt.signature_status()[0].finalized());
+    assert_eq!(psbt.signature_status()[0].remaining(), Some(0));
+    psbt.extract_tx().expect("extract_tx");
+}
+
+#[wasm_bindgen_test]
+async fn test_signature_status_policies() {
+    set_panic_hook();
+
+    let descriptors = seed_to_descriptor(&[3; 64], NETWORK, AddressType::P2sh).expect("descriptors");
+    let mut wallet = Wallet::create(NETWORK, descriptors).expect("wallet");
+    let mut psbt = unsigned_psbt(&mut wallet, 50_000);
+    assert_eq!(psbt.signature_status()[0].required(), Some(1));
+    assert_eq!(psbt.signature_status()[0].remaining(), Some(1));
+    assert!(wallet.sign(&mut psbt).expect("sign"));
+    assert_eq!(psbt.signature_status()[0].remaining(), Some(0));
+
+    // Both keys of the policy must sign, which is not counted as it is not a multisig.
+    let mut wallet = and_cosigner(0);
+    let mut psbt = unsigned_psbt(&mut wallet, 50_000);
+    let status = &psbt.signature_status()[0];
+    assert_eq!(status.required(), None);
+    assert_eq!(status.missing().len(), 2);
+    assert!(!wallet.sign(&mut psbt).expect("sign"));
+    let status = &psbt.signature_status()[0];
+    assert_eq!(status.required(), None);
+    assert_eq!(status.remaining(), None);
+    assert_eq!(status.signed()[0].fingerprint(), Some(fingerprint(0)));
+    assert_eq!(status.missing()[0].fingerprint(), Some(fingerprint(1)));
+}
+
+#[wasm_bindgen_test]
+async fn test_combine_conflicts() {
+    set_panic_hook();
+
+    let mut wallets = (0..3).map(cosigner).collect::<Vec<_>>();
+    let mut psbt = unsigned_psbt(&mut wallets[0], 50_000);
+    let other_tx = wallets[0]
+        .build_tx()
+        .add_recipient(Recipient::new(
+            Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address"),
+            Amount::from_sat(40_000),
+        ))
+        .finish()
+        .expect("finish");
+
+    let error = psbt.clone().combine(&other_tx).expect_err("different transactions");
+    assert_eq!(error.code(), BdkErrorCode::CombinePsbt);
+
+    assert!(!wallets[1].sign(&mut psbt).expect("sign"));
+    let mut conflicting = psbt.clone();
+    for signature in conflicting.inputs[0].partial_sigs.values_mut() {
+        signature.sighash_type = EcdsaSighashType::AllPlusAnyoneCanPay;
+    }
+    let before = psbt.clone();
+    let error = psbt.combine(&conflicting).expect_err("conflicting signatures");
+    assert_eq!(error.code(), BdkErrorCode::CombinePsbt);
+    assert_eq!(psbt, before);
+}
+
+#[wasm_bindgen_test]
+async fn test_external_signer() {
+    set_panic_hook();
+
+    let mut wallet = cosigner(0);
+    let psbt = unsigned_psbt(&mut wallet, 50_000);
+
+    let (second, second_calls) = cosigner_signer(1);
+    let (third, third_calls) = cosigner_signer(2);
+    wallet
+        .add_external_signer(&fingerprint(1), second)
+        .expect("second signer");
+    wallet
+        .add_external_signer(&fingerprint(2), third)
+        .expect("third signer");
+
+    let signed = wallet.sign_with_external_signers(&psbt).await.expect("sign");
+    assert!(signed.signature_status()[0].finalized());
+    signed.extract_tx().expect("extract_tx");
+    assert_eq!(second_calls.get(), 1);
+    // The threshold is reached after the second signature, the third signer is not prompted.
+    assert_eq!(third_calls.get(), 0);
+    assert!(!psbt.signature_status()[0].finalized());
+
+    let (failing, _) = js_signer(|_| Promise::reject(&js_sys::Error::new("rejected by the user").into()));
+    wallet
+        .add_external_signer(&fingerprint(1), failing)
+        .expect("replace signer");
+    let error = wallet.sign_with_external_signers(&psbt).await.expect_err("rejected");
+    assert_eq!(error.code(), BdkErrorCode::ExternalSigner);
+    assert_eq!(error.message(), "rejected by the user");
+
+    let (invalid, _) = js_signer(|_| Promise::resolve(&JsValue::from(42)));
+    wallet
+        .add_external_signer(&fingerprint(1), invalid)
+        .expect("replace signer");
+    let error = wallet.sign_with_external_signers(&psbt).await.expect_err("invalid");
+    assert_eq!(error.code(), BdkErrorCode::ExternalSigner);
+
+    // A PSBT for another transaction cannot be combined, the error is still reported as the signer's.
+    let other = unsigned_psbt(&mut wallet, 40_000).to_string();
+    let (mismatched, _) = js_signer(move |_| Promise::resolve(&other.clone().into()));
+    wallet
+        .add_external_signer(&fingerprint(1), mismatched)
+        .expect("replace signer");
+    let error = wallet
+        .sign_with_external_signers(&psbt)
+        .await
+        .expect_err("other transaction");
+    assert_eq!(error.code(), BdkErrorCode::ExternalSigner);
+    assert_eq!(
+        Reflect::get(&error.data(), &"fingerprint".into()).expect("fingerprint"),
+        JsValue::from(fingerprint(1))
+    );
+
+    // A signature with a sighash type that was not requested.
+    let descriptor = |keychain| format!("wsh(sortedmulti(2,{}))", cosigner_keys(1, 3, keychain).join(","));
+    let cosigner_wallet = BdkWallet::create(descriptor(0), descriptor(1))
+        .network(BdkNetwork::Testnet)
+        .create_wallet_no_persist()
+        .expect("wallet");
+    let (none_anyonecanpay, _) = js_signer(move |psbt| {
+        let mut psbt = BdkPsbt::from(Psbt::from_string(&psbt).unwrap());
+        for input in psbt.inputs.iter_mut() {
+            input.sighash_type = Some(EcdsaSighashType::NonePlusAnyoneCanPay.into());
+        }
+        let sign_options = SignOptions {
+            allow_all_sighashes: true,
+            try_finalize: false,
+            ..Default::default()
+        };
+        cosigner_wallet.sign(&mut psbt, sign_options).unwrap();
+        for input in psbt.inputs.iter_mut() {
+            input.sighash_type = None;
+        }
+        Promise::resolve(&Psbt::from(psbt).to_string().into())
+    });
+    wallet
+        .add_external_signer(&fingerprint(1), none_anyonecanpay)
+        .expect("replace signer");
+    let error = wallet
+        .sign_with_external_signers(&psbt)
+        .await
+        .expect_err("sighash type");
+    assert_eq!(error.code(), BdkErrorCode::ExternalSigner);
+    assert_eq!(
+        Reflect::get(&error.data(), &"fingerprint".into()).expect("fingerprint"),
+        JsValue::from(fingerprint(1))
+    );
+
+    let (unknown, _) = js_signer(|psbt| Promise::resolve(&psbt.into()));
+    let error = wallet
+        .add_external_signer("00000000", unknown)
+        .expect_err("unknown fingerprint");
+    assert_eq!(error.code(), BdkErrorCode::InvalidKey);
+    let (unknown, _) = js_signer(|psbt| Promise::resolve(&psbt.into()));
+    let error = wallet
+        .add_external_signer("not hex", unknown)
+        .expect_err("invalid fingerprint");
+    assert_eq!(error.code(), BdkErrorCode::InvalidKey);
+}
+
+/// A signing wallet and the watch-only wallet of its public descriptors.
+fn air_gapped_wallets(address_type: AddressType) -> (Wallet, Wallet) {
+    let descriptors = seed_to_descriptor(&[42; 64], NETWORK, address_type).expect("descriptors");
+    let signer = Wallet::create(NETWORK, descriptors).expect("signer");
+    let watch_only = Wallet::create(
+        NETWORK,
+        DescriptorPair::new(
+            signer
+                .public_descriptor(KeychainKind::External)
+                .expect("public_descriptor"),
+            signer
+                .public_descriptor(KeychainKind::Internal)
+                .expect("public_descriptor"),
+        ),
+    )
+    .expect("watch-only");
+    (signer, watch_only)
+}
+
+/// Sign with the keys of the air-gapped signer using `sighash` for every input, as a compromised signer could.
+fn sign_with_sighash(address_type: AddressType, psbt: &Psbt, sighash: PsbtSighashType, finalize: bool) -> Psbt {
+    let descriptors = seed_to_descriptor(&[42; 64], NETWORK, address_type).expect("descriptors");
+    let wallet = BdkWallet::create(descriptors.external(), descriptors.internal())
+        .network(BdkNetwork::Testnet)
+        .create_wallet_no_persist()
+        .expect("wallet");
+    let mut psbt = BdkPsbt::from(psbt.clone());
+    for input in psbt.inputs.iter_mut() {
+        input.sighash_type = Some(sighash);
+    }
+    let sign_options = SignOptions {
+        allow_all_sighashes: true,
+        try_finalize: finalize,
+        ..Default::default()
+    };
+    wallet.sign(&mut psbt, sign_options).expect("sign");
+    psbt.into()
+}
+
+#[wasm_bindgen_test]
+async fn test_air_gapped_signing() {
+    set_panic_hook();
+
+    for address_type in [AddressType::P2wpkh, AddressType::P2tr] {
+        let (signer, mut watch_only) = air_gapped_wallets(address_type);
+        let mut psbt = unsigned_psbt(&mut watch_only, 50_000);
+        // A PSBT received from elsewhere, without the data needed by the signer.
+        for input in psbt.inputs.iter_mut() {
+            input.non_witness_utxo = None;
+            input.bip32_derivation.clear();
+            input.tap_key_origins.clear();
+            input.tap_internal_key = None;
+        }
+        for output in psbt.outputs.iter_mut() {
+            output.bip32_derivation.clear();
+            output.tap_key_origins.clear();
+        }
+
+        let exported = watch_only.export_psbt(&psbt).expect("export");
+        let input = &exported.inputs[0];
+        assert!(input.witness_utxo.is_some());
+        assert_eq!(psbt.xpub.len(), 0);
+        assert_eq!(exported.xpub.len(), 1);
+        let change = exported
+            .outputs
+            .iter()
+            .find(|output| !output.bip32_derivation.is_empty() || !output.tap_key_origins.is_empty());
+        assert!(change.is_some(), "the change output has derivations");
+        if address_type == AddressType::P2tr {
+            assert!(input.non_witness_utxo.is_none());
+            assert!(input.tap_internal_key.is_some());
+            assert_eq!(input.tap_key_origins.len(), 1);
+        } else {
+            assert!(input.non_witness_utxo.is_some());
+            assert_eq!(input.bip32_derivation.len(), 1);
+        }
+
+        let mut signed = Psbt::from_string(&exported.to_string()).expect("from_string");
+        assert!(signer.sign(&mut signed).expect("sign"));
+        let finalized = watch_only.import_signed_psbt(&exported, &signed).expect("import");
+        finalized.extract_tx().expect("extract_tx");
+
+        let error = watch_only
+            .import_signed_psbt(&exported, &exported)
+            .expect_err("unsigned");
+        assert_eq!(error.code(), BdkErrorCode::Sign);
+
+        let other = unsigned_psbt(&mut watch_only, 40_000);
+        let error = watch_only
+            .import_signed_psbt(&other, &signed)
+            .expect_err("different transaction");
+        assert_eq!(error.code(), BdkErrorCode::PsbtMismatch);
+
+        let mut tampered = signed.clone();
+        if let Some(utxo) = tampered.inputs[0].witness_utxo.as_mut() {
+            utxo.value = BdkAmount::from_sat(1_000_000);
+        }
+        let error = watch_only
+            .import_signed_psbt(&exported, &tampered)
+            .expect_err("altered previous output");
+        assert_eq!(error.code(), BdkErrorCode::PsbtMismatch);
+
+        let mut stripped = exported.clone();
+        stripped.inputs[0].witness_utxo = None;
+        let error = watch_only
+            .import_signed_psbt(&stripped, &signed)
+            .expect_err("added previous output");
+        assert_eq!(error.code(), BdkErrorCode::PsbtMismatch);
+
+        let mut forged = signed.clone();
+        let witness = forged.inputs[0].final_script_witness.as_ref().expect("witness");
+        let mut elements = witness.to_vec();
+        elements[0][10] ^= 1;
+        forged.inputs[0].final_script_witness = Some(elements.into());
+        let error = watch_only
+            .import_signed_psbt(&exported, &forged)
+            .expect_err("invalid signature");
+        assert_eq!(error.code(), BdkErrorCode::InvalidPsbt);
+
+        // Signatures with NONE|ANYONECANPAY would let anyone change the outputs once the transaction is broadcast.
+        let sighash = PsbtSighashType::from_u32(0x82);
+        let partial = sign_with_sighash(address_type, &exported, sighash, false);
+        let error = watch_only
+            .import_signed_psbt(&exported, &partial)
+            .expect_err("changed sighash type");
+        assert_eq!(error.code(), BdkErrorCode::PsbtMismatch);
+        let mut partial = partial.clone();
+        partial.inputs[0].sighash_type = None;
+        let error = watch_only
+            .import_signed_psbt(&exported, &partial)
+            .expect_err("partial signature with another sighash type");
+        assert_eq!(error.code(), BdkErrorCode::PsbtMismatch);
+        let finalized = sign_with_sighash(address_type, &exported, sighash, true);
+        assert!(finalized.inputs[0].final_script_witness.is_some());
+        assert!(finalized.inputs[0].sighash_type.is_none());
+        let error = watch_only
+            .import_signed_psbt(&exported, &finalized)
+            .expect_err("final signature with another sighash type");
+        assert_eq!(error.code(), BdkErrorCode::PsbtMismatch);
+
+        // Unless the exported PSBT asked for it.
+        let mut requested = exported.clone();
+        requested.inputs[0].sighash_type = Some(sighash);
+        let signed = sign_with_sighash(address_type, &requested, sighash, true);
+        watch_only
+            .import_signed_psbt(&requested, &signed)
+            .expect("requested sighash type");
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_external_signer_policy() {
+    set_panic_hook();
+
+    let mut wallet = and_cosigner(0);
+    let psbt = unsigned_psbt(&mut wallet, 50_000);
+    let (signer, calls) = wallet_signer(and_cosigner(1));
+    wallet
+        .add_external_signer(&fingerprint(1), signer)
+        .expect("external signer");
+
+    // The signature of the wallet does not complete the input, the external signer is prompted.
+    let signed = wallet.sign_with_external_signers(&psbt).await.expect("sign");
+    assert_eq!(calls.get(), 1);
+    assert!(signed.signature_status()[0].finalized());
+    signed.extract_tx().expect("extract_tx");
+}
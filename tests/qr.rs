@@ -0,0 +1,404 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bdk_wallet::bitcoin::{
+    absolute,
+    consensus::serialize,
+    hashes::Hash,
+    hex::{DisplayHex, FromHex},
+    transaction, Amount as BdkAmount, OutPoint, Transaction as BdkTransaction, TxIn, TxOut, Txid,
+};
+use bitcoindevkit::{
+    bitcoin::{BbqrDecoder, BbqrEncoder, Recipient, UrDecoder, UrEncoder, Wallet},
+    seed_to_descriptor, set_panic_hook,
+    types::{
+        Address, AddressType, Amount, BbqrEncoding, BbqrFileType, BdkErrorCode, DescriptorPair, Network, Psbt,
+        Transaction,
+    },
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+use common::{public, receive};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const NETWORK: Network = Network::Testnet;
+const RECIPIENT_ADDRESS: &str = "tb1qd28npep0s8frcm3y7dxqajkcy2m40eysplyr9v";
+
+/// The message of the BCR-2020-005 test vectors: 256 bytes from Xoshiro256** seeded with `Wolf`.
+const WOLF_MESSAGE: &str = "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3ccba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f965e25ee29039fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3ec4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f595e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff173f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d";
+
+/// The first parts of `WOLF_MESSAGE` as a `bytes` UR in fragments of at most 30 bytes, from the reference
+/// implementation. The parts after the 9th mix the fragments chosen by Xoshiro256** seeded with their
+/// sequence number and the checksum.
+const WOLF_PARTS: [&str; 20] = [
+    "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh",
+    "ur:bytes/2-9/lpaoascfadaxcywenbpljkhdcagwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsgmghhkhstlrdcxaefz",
+    "ur:bytes/3-9/lpaxascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjksopdzmol",
+    "ur:bytes/4-9/lpaaascfadaxcywenbpljkhdcasotkhemthydawydtaxneurlkosgwcekonertkbrlwmplssjtammdplolsbrdzcrtas",
+    "ur:bytes/5-9/lpahascfadaxcywenbpljkhdcatbbdfmssrkzmcwnezelennjpfzbgmuktrhtejscktelgfpdlrkfyfwdajldejokbwf",
+    "ur:bytes/6-9/lpamascfadaxcywenbpljkhdcackjlhkhybssklbwefectpfnbbectrljectpavyrolkzczcpkmwidmwoxkilghdsowp",
+    "ur:bytes/7-9/lpatascfadaxcywenbpljkhdcavszmwnjkwtclrtvaynhpahrtoxmwvwatmedibkaegdosftvandiodagdhthtrlnnhy",
+    "ur:bytes/8-9/lpayascfadaxcywenbpljkhdcadmsponkkbbhgsoltjntegepmttmoonftnbuoiyrehfrtsabzsttorodklubbuyaetk",
+    "ur:bytes/9-9/lpasascfadaxcywenbpljkhdcajskecpmdckihdyhphfotjojtfmlnwmadspaxrkytbztpbauotbgtgtaeaevtgavtny",
+    "ur:bytes/10-9/lpbkascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtwdkiplzs",
+    "ur:bytes/11-9/lpbdascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjkvetiiapk",
+    "ur:bytes/12-9/lpbnascfadaxcywenbpljkhdcarllaluzmdmgstospeyiefmwejlwtpedamktksrvlcygmzemovovllarodtmtbnptrs",
+    "ur:bytes/13-9/lpbtascfadaxcywenbpljkhdcamtkgtpknghchchyketwsvwgwfdhpgmgtylctotzopdrpayoschcmhplffziachrfgd",
+    "ur:bytes/14-9/lpbaascfadaxcywenbpljkhdcapazewnvonnvdnsbyleynwtnsjkjndeoldydkbkdslgjkbbkortbelomueekgvstegt",
+    "ur:bytes/15-9/lpbsascfadaxcywenbpljkhdcaynmhpddpzmversbdqdfyrehnqzlugmjzmnmtwmrouohtstgsbsahpawkditkckynwt",
+    "ur:bytes/16-9/lpbeascfadaxcywenbpljkhdcawygekobamwtlihsnpalnsghenskkiynthdzotsimtojetprsttmukirlrsbtamjtpd",
+    "ur:bytes/17-9/lpbyascfadaxcywenbpljkhdcamklgftaxykpewyrtqzhydntpnytyisincxmhtbceaykolduortotiaiaiafhiaoyce",
+    "ur:bytes/18-9/lpbgascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtntwkbkwy",
+    "ur:bytes/19-9/lpbwascfadaxcywenbpljkhdcadekicpaajootjzpsdrbalpeywllbdsnbinaerkurspbncxgslgftvtsrjtksplcpeo",
+    "ur:bytes/20-9/lpbbascfadaxcywenbpljkhdcayapmrleeleaxpasfrtrdkncffwjyjzgyetdmlewtkpktgllepfrltataztksmhkbot",
+];
+
+fn descriptors(seed: u8, address_type: AddressType) -> DescriptorPair {
+    seed_to_descriptor(&[seed; 64], NETWORK, address_type).expect("descriptors")
+}
+
+/// The account extended key of a wallet, with its origin.
+fn account_key(seed: u8) -> String {
+    let (external, _) = public(&descriptors(seed, AddressType::P2wpkh));
+    let start = external.find('[').expect("origin");
+    let end = external.find("/0/*").expect("path");
+    external[start..end].to_string()
+}
+
+fn psbt() -> Psbt {
+    let mut wallet = Wallet::create(NETWORK, descriptors(1, AddressType::P2wpkh)).expect("wallet");
+    receive(&mut wallet, 100_000);
+    wallet
+        .build_tx()
+        .add_recipient(Recipient::new(
+            Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address"),
+            Amount::from_sat(50_000),
+        ))
+        .finish()
+        .expect("finish")
+}
+
+#[wasm_bindgen_test]
+async fn test_ur_psbt() {
+    set_panic_hook();
+
+    let psbt = psbt();
+    let mut encoder = UrEncoder::from_psbt(&psbt, 100).expect("encoder");
+    assert_eq!(encoder.ur_type(), "crypto-psbt");
+    let count = encoder.fragment_count();
+    assert!(count > 2);
+    assert!(!encoder.is_single_part());
+
+    let mut decoder = UrDecoder::new();
+    for index in 1..=count {
+        let part = encoder.next_part();
+        assert!(part.starts_with(&format!("ur:crypto-psbt/{index}-{count}/")));
+        assert!(!decoder.is_complete());
+        decoder.receive(&part).expect("receive");
+    }
+    assert!(decoder.is_complete());
+    assert_eq!(decoder.progress(), 1.0);
+    assert_eq!(decoder.ur_type().as_deref(), Some("crypto-psbt"));
+    assert_eq!(decoder.to_psbt().expect("psbt"), psbt);
+
+    // Recover the fragments of the first parts, missed by the scanner, from the parts mixing them.
+    let mut decoder = UrDecoder::new();
+    let mut received = 0;
+    for _ in 0..count * 10 {
+        let part = encoder.next_part();
+        if decoder.is_complete() {
+            break;
+        }
+        decoder.receive(&part.to_uppercase()).expect("receive");
+        received += 1;
+    }
+    assert!(decoder.is_complete(), "decoded after {received} parts");
+    assert_eq!(decoder.to_psbt().expect("psbt"), psbt);
+
+    let mut encoder = UrEncoder::from_psbt(&psbt, 10_000).expect("encoder");
+    assert!(encoder.is_single_part());
+    let part = encoder.next_part();
+    assert_eq!(part.matches('/').count(), 1);
+    let mut decoder = UrDecoder::new();
+    decoder.receive(&part).expect("receive");
+    assert_eq!(decoder.to_psbt().expect("psbt"), psbt);
+    let error = decoder.to_descriptor().expect_err("not an output");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+}
+
+#[wasm_bindgen_test]
+async fn test_ur_vectors() {
+    set_panic_hook();
+
+    let message = Vec::from_hex(WOLF_MESSAGE).unwrap();
+    let mut encoder = UrEncoder::from_bytes(&message, 30).expect("encoder");
+    assert_eq!(encoder.ur_type(), "bytes");
+    assert_eq!(encoder.fragment_count(), 9);
+    for part in WOLF_PARTS {
+        assert_eq!(encoder.next_part(), part);
+    }
+
+    // The first fragments are only recovered from the parts mixing them.
+    let mut decoder = UrDecoder::new();
+    for part in &WOLF_PARTS[3..] {
+        decoder.receive(&part.to_uppercase()).expect("receive");
+    }
+    assert!(decoder.is_complete());
+    assert_eq!(decoder.to_bytes().expect("bytes"), message);
+    let error = decoder.to_psbt().expect_err("not a PSBT");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+
+    // A part of a message of 10^8 fragments, mixing them all.
+    let error = UrDecoder::new()
+        .receive("ur:bytes/100000001-100000000/lpcyahykvyadcyahykvyaecyahykvyaeaefpaehgbyvwwm")
+        .expect_err("too many fragments");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+    let error = UrEncoder::from_bytes(&[0; 200_000], 10)
+        .err()
+        .expect("too many fragments");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+}
+
+#[wasm_bindgen_test]
+async fn test_ur_invalid_parts() {
+    set_panic_hook();
+
+    let psbt = psbt();
+    let mut encoder = UrEncoder::from_psbt(&psbt, 100).expect("encoder");
+    let mut decoder = UrDecoder::new();
+    let error = decoder.to_psbt().expect_err("incomplete");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+
+    let part = encoder.next_part();
+    for invalid in [
+        "crypto-psbt/lpadasbbcyaeaeaeae",
+        "ur:crypto-psbt/1-9/lpadasbbcyaeaeaeae",
+        "ur:crypto-psbt/1-9/2-9/lpad",
+        "ur:Crypto_psbt/aeae",
+        &part[..part.len() - 2],
+        &part.replace("/1-", "/2-"),
+    ] {
+        let error = decoder.receive(invalid).expect_err(invalid);
+        assert_eq!(error.code(), BdkErrorCode::InvalidQr, "{invalid}");
+    }
+
+    decoder.receive(&part).expect("receive");
+    let mut other = UrEncoder::from_psbt(&psbt, 90).expect("encoder");
+    let error = decoder.receive(&other.next_part()).expect_err("other data");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+    let mut output = UrEncoder::from_descriptor(&descriptors(1, AddressType::P2wpkh), 10).expect("encoder");
+    let error = decoder.receive(&output.next_part()).expect_err("other type");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+    assert!(decoder.progress() > 0.0 && decoder.progress() < 1.0);
+}
+
+#[wasm_bindgen_test]
+async fn test_ur_descriptors() {
+    set_panic_hook();
+
+    for address_type in [
+        AddressType::P2pkh,
+        AddressType::P2sh,
+        AddressType::P2wpkh,
+        AddressType::P2tr,
+    ] {
+        let descriptors = descriptors(1, address_type);
+        let mut encoder = UrEncoder::from_descriptor(&descriptors, 1000).expect("encoder");
+        assert_eq!(encoder.ur_type(), "crypto-output");
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&encoder.next_part()).expect("receive");
+        let decoded = decoder.to_descriptor().expect("descriptor");
+        assert_eq!((decoded.external(), decoded.internal()), public(&descriptors));
+    }
+
+    let keys = (1..=3)
+        .map(|seed| format!("{}/<0;1>/*", account_key(seed)))
+        .collect::<Vec<_>>();
+    for template in [
+        "wsh(sortedmulti(2,{}))",
+        "sh(wsh(multi(2,{})))",
+        "sh(sortedmulti(2,{}))",
+    ] {
+        let multisig = DescriptorPair::from_multipath(&template.replace("{}", &keys.join(","))).expect("multisig");
+        let mut encoder = UrEncoder::from_descriptor(&multisig, 50).expect("encoder");
+        assert!(encoder.fragment_count() > 1);
+        let mut decoder = UrDecoder::new();
+        while !decoder.is_complete() {
+            decoder.receive(&encoder.next_part()).expect("receive");
+        }
+        let decoded = decoder.to_descriptor().expect("descriptor");
+        assert_eq!((decoded.external(), decoded.internal()), public(&multisig));
+    }
+
+    let address_types = [AddressType::P2wpkh, AddressType::P2tr];
+    let account = address_types.map(|address_type| descriptors(1, address_type));
+    let mut encoder = UrEncoder::from_account(
+        address_types
+            .iter()
+            .map(|address_type| descriptors(1, *address_type))
+            .collect(),
+        1000,
+    )
+    .expect("encoder");
+    assert_eq!(encoder.ur_type(), "crypto-account");
+    let mut decoder = UrDecoder::new();
+    decoder.receive(&encoder.next_part()).expect("receive");
+    let decoded = decoder.to_account().expect("account");
+    assert_eq!(decoded.len(), 2);
+    for (decoded, descriptors) in decoded.iter().zip(&account) {
+        assert_eq!((decoded.external(), decoded.internal()), public(descriptors));
+    }
+
+    let error = UrEncoder::from_account(
+        vec![descriptors(1, AddressType::P2wpkh), descriptors(2, AddressType::P2wpkh)],
+        1000,
+    )
+    .err()
+    .expect("different master keys");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+
+    let hardened = DescriptorPair::from_multipath(&format!("wpkh({}/<0';1'>/*)", account_key(1))).expect("hardened");
+    let error = UrEncoder::from_descriptor(&hardened, 1000)
+        .err()
+        .expect("unsupported paths");
+    assert_eq!(error.code(), BdkErrorCode::InvalidDescriptor);
+}
+
+#[wasm_bindgen_test]
+async fn test_bbqr() {
+    set_panic_hook();
+
+    let psbt = psbt();
+    let bytes = psbt.serialize();
+
+    let encoder = BbqrEncoder::from_psbt(&psbt, BbqrEncoding::Hex, 10_000).expect("encoder");
+    let hex = bytes.iter().map(|byte| format!("{byte:02X}")).collect::<String>();
+    assert_eq!(encoder.parts(), vec![format!("B$HP0100{hex}")]);
+
+    for encoding in [BbqrEncoding::Hex, BbqrEncoding::Base32, BbqrEncoding::Zlib] {
+        let encoder = BbqrEncoder::from_psbt(&psbt, encoding, 100).expect("encoder");
+        assert_eq!(encoder.encoding(), encoding);
+        assert_eq!(encoder.file_type(), BbqrFileType::Psbt);
+        let parts = encoder.parts();
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| part.len() <= 100));
+
+        let mut decoder = BbqrDecoder::new();
+        for part in parts.iter().rev() {
+            assert!(!decoder.is_complete());
+            decoder.receive(part).expect("receive");
+            decoder.receive(part).expect("receive again");
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.file_type(), Some(BbqrFileType::Psbt));
+        assert_eq!(decoder.data().expect("data"), bytes);
+        assert_eq!(decoder.to_psbt().expect("psbt"), psbt);
+        let error = decoder.to_transaction().expect_err("not a transaction");
+        assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+    }
+    let zlib = BbqrEncoder::from_psbt(&psbt, BbqrEncoding::Zlib, 100).expect("encoder");
+    let base32 = BbqrEncoder::from_psbt(&psbt, BbqrEncoding::Base32, 100).expect("encoder");
+    assert!(zlib.parts().len() < base32.parts().len());
+    assert!(zlib.parts()[0].starts_with("B$ZP"));
+
+    let descriptors = descriptors(1, AddressType::P2wpkh);
+    let encoder = BbqrEncoder::from_descriptor(&descriptors, BbqrEncoding::Zlib, 60).expect("encoder");
+    let mut decoder = BbqrDecoder::new();
+    for part in encoder.parts() {
+        decoder.receive(&part).expect("receive");
+    }
+    let decoded = decoder.to_descriptor().expect("descriptor");
+    assert_eq!((decoded.external(), decoded.internal()), public(&descriptors));
+
+    let mut decoder = BbqrDecoder::new();
+    let parts = base32.parts();
+    decoder.receive(&parts[0]).expect("receive");
+    for invalid in [
+        "B$2P",
+        "X$2P0100AAAA",
+        "B$QP0100AAAA",
+        "B$2Q0100AAAA",
+        "B$2P0202AAAA",
+        &zlib.parts()[1],
+        &parts[0].replace("B$2P", "B$2T"),
+        &format!("{}AA", parts[0]),
+    ] {
+        let error = decoder.receive(invalid).expect_err(invalid);
+        assert_eq!(error.code(), BdkErrorCode::InvalidQr, "{invalid}");
+    }
+    assert_eq!(decoder.progress(), 1.0 / parts.len() as f64);
+    let error = decoder.to_psbt().expect_err("incomplete");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+
+    let error = BbqrEncoder::from_psbt(&psbt, BbqrEncoding::Base32, 15)
+        .err()
+        .expect("too short");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+}
+
+#[wasm_bindgen_test]
+async fn test_bbqr_vectors() {
+    set_panic_hook();
+
+    let recipient = Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address");
+    let tx = BdkTransaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            ..Default::default()
+        }],
+        output: vec![TxOut {
+            value: BdkAmount::from_sat(100_000),
+            script_pubkey: (*recipient).script_pubkey(),
+        }],
+    };
+    let transaction: Transaction = tx.clone().into();
+
+    let encoder = BbqrEncoder::from_transaction(&transaction, BbqrEncoding::Hex, 1000).expect("encoder");
+    assert_eq!(
+        encoder.parts(),
+        vec![format!("B$HT0100{}", serialize(&tx).to_upper_hex_string())]
+    );
+
+    // Deflated with fixed Huffman codes, as checked against zlib with a 1 KiB window.
+    let encoder = BbqrEncoder::from_transaction(&transaction, BbqrEncoding::Zlib, 40).expect("encoder");
+    assert_eq!(
+        encoder.parts(),
+        vec![
+            "B$ZT0300MNRGAYDAEQCIA2QYDD7AGAPDQI3AQR4M",
+            "B$ZT0301IESKXX7ARF7OGJM3HSK46PT4U5XCQ3IP",
+            "B$ZT0302P4ZACJAFAA",
+        ]
+    );
+
+    // Deflated by zlib with a 1 KiB window and dynamic Huffman codes, as by the reference implementation.
+    let mut decoder = BbqrDecoder::new();
+    decoder
+        .receive("B$ZT0201G5PLFSKT7HWMG57KQ3JPN4BHCNAFEAA")
+        .expect("receive");
+    decoder
+        .receive("B$ZT0200MNRGAYDAEQCBQQHAH4IDALTIQNYMIGCEWL5A3HXI")
+        .expect("receive");
+    assert_eq!(decoder.to_transaction().expect("transaction"), transaction);
+
+    // Deflated back references of 258 bytes inflating 13 bits to more than 4 MiB.
+    let bomb = |repeat| {
+        format!(
+            "B$ZB0100MM{}AAA",
+            "MALI3ACSGIEUJQBJDMCKAYAWRWAFEMQJITACSGYEUBQBNDMAKIZASRGAFENQJIDAC2GYAURSBFCMAKI3ASQGAFUNQBJDECKEYAURWBFA"
+                .repeat(repeat)
+        )
+    };
+    let mut decoder = BbqrDecoder::new();
+    decoder.receive(&bomb(1)).expect("receive");
+    assert_eq!(decoder.data().expect("data"), vec![0; 10_321]);
+    let error = BbqrDecoder::new().receive(&bomb(410)).expect_err("too large");
+    assert_eq!(error.code(), BdkErrorCode::InvalidQr);
+}
@@ -0,0 +1,73 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bitcoindevkit::{
+    set_panic_hook,
+    types::{Address, BdkErrorCode, Network, ScriptBuf, ScriptType},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const GENERATOR: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+#[wasm_bindgen_test]
+async fn test_script_classification() {
+    set_panic_hook();
+
+    let p2pkh = ScriptBuf::from_hex("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac").expect("p2pkh");
+    assert_eq!(p2pkh.script_type(), ScriptType::P2pkh);
+    assert_eq!(
+        p2pkh.to_asm(),
+        "OP_DUP OP_HASH160 OP_PUSHBYTES_20 62e907b15cbf27d5425399ebf6f0fb50ebb88f18 OP_EQUALVERIFY OP_CHECKSIG"
+    );
+    assert_eq!(
+        p2pkh.to_address(Network::Bitcoin).expect("address").to_string(),
+        "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+    );
+
+    let p2tr =
+        ScriptBuf::from_hex("5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c").expect("p2tr");
+    assert_eq!(p2tr.script_type(), ScriptType::P2tr);
+    assert_eq!(
+        p2tr.to_address(Network::Bitcoin).expect("address").to_string(),
+        "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+    );
+
+    let op_return = ScriptBuf::from_hex("6a0b68656c6c6f20776f726c64").expect("op_return");
+    assert_eq!(op_return.script_type(), ScriptType::OpReturn);
+    assert_eq!(op_return.to_asm(), "OP_RETURN OP_PUSHBYTES_11 68656c6c6f20776f726c64");
+    let error = op_return.to_address(Network::Bitcoin).expect_err("no address");
+    assert_eq!(error.code(), BdkErrorCode::InvalidAddress);
+
+    let multisig = ScriptBuf::from_hex(&format!("5121{GENERATOR}51ae")).expect("multisig");
+    assert_eq!(multisig.script_type(), ScriptType::Multisig);
+
+    let p2pk = ScriptBuf::from_hex(&format!("21{GENERATOR}ac")).expect("p2pk");
+    assert_eq!(p2pk.script_type(), ScriptType::P2pk);
+    assert!(p2pk.to_address(Network::Bitcoin).is_err());
+
+    let unknown = ScriptBuf::from_hex("51").expect("unknown");
+    assert_eq!(unknown.script_type(), ScriptType::Unknown);
+    assert_eq!(unknown.to_asm(), "OP_PUSHNUM_1");
+}
+
+#[wasm_bindgen_test]
+async fn test_script_from_address() {
+    set_panic_hook();
+
+    let address =
+        Address::from_string("tb1qd28npep0s8frcm3y7dxqajkcy2m40eysplyr9v", Network::Testnet).expect("address");
+    let script = ScriptBuf::from_address(&address);
+    assert_eq!(script, address.script_pubkey());
+    assert_eq!(script.script_type(), ScriptType::P2wpkh);
+    assert!(script.to_asm().starts_with("OP_0 OP_PUSHBYTES_20 "));
+    assert_eq!(script.to_address(Network::Testnet).expect("address"), address);
+    assert_eq!(
+        script.to_address(Network::Regtest).expect("address").to_string(),
+        "bcrt1qd28npep0s8frcm3y7dxqajkcy2m40eysrkawj9"
+    );
+}
@@ -0,0 +1,90 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use bitcoindevkit::{
+    set_panic_hook,
+    types::{Amount, BdkErrorCode, OutPoint, ScriptBuf, Transaction, TxOut},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const SEGWIT_TX: &str = "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c0100000000ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd2710e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed8941055d3bcb8627d085e94553e62f057dcc00000000";
+
+#[wasm_bindgen_test]
+async fn test_transaction_decoding() {
+    set_panic_hook();
+
+    let tx = Transaction::from_hex(SEGWIT_TX).expect("from_hex");
+    assert_eq!(
+        tx.txid(),
+        "f5864806e3565c34d1b41e716f72609d00b55ea5eac5b924c9719a842ef42206"
+    );
+    assert_eq!(
+        tx.wtxid(),
+        "80b7d8a82d5d5bf92905b06f2014dd699e03837ca172e3a59d51426ebbe3e7f5"
+    );
+    assert_eq!(tx.version(), 2);
+    assert_eq!(tx.lock_time(), 0);
+    assert_eq!(tx.weight(), 442);
+    assert_eq!(tx.vsize(), 111);
+    assert!(!tx.is_rbf());
+    assert!(!tx.is_coinbase());
+
+    let input = tx.input();
+    assert_eq!(input.len(), 1);
+    assert_eq!(
+        input[0].previous_output().to_string(),
+        "7cac3cf9a112cf04901a51d605058615d56ffe6d04b45270e89d1720ea955859:1"
+    );
+    assert_eq!(input[0].sequence(), 0xffffffff);
+    assert!(input[0].script_sig().is_empty());
+    assert_eq!(input[0].witness().len(), 2);
+
+    let output = tx.output();
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0].value().to_sat(), 506_078);
+    assert_eq!(
+        output[0].script_pubkey().to_hex(),
+        "a9140f3444e271620c736808aa7b33e370bd87cb5a0787"
+    );
+
+    assert_eq!(tx.to_hex(), SEGWIT_TX);
+    let from_bytes = Transaction::from_bytes(&tx.to_bytes()).expect("from_bytes");
+    assert_eq!(from_bytes, tx);
+
+    let error = Transaction::from_hex(&SEGWIT_TX[..100]).expect_err("truncated");
+    assert_eq!(error.code(), BdkErrorCode::InvalidTransaction);
+    let error = Transaction::from_hex("not hex").expect_err("not hex");
+    assert_eq!(error.code(), BdkErrorCode::InvalidTransaction);
+}
+
+#[wasm_bindgen_test]
+async fn test_outpoint_and_txout() {
+    set_panic_hook();
+
+    let txid = "f5864806e3565c34d1b41e716f72609d00b55ea5eac5b924c9719a842ef42206";
+    let outpoint = OutPoint::new(txid, 3).expect("outpoint");
+    assert_eq!(outpoint.txid(), txid);
+    assert_eq!(outpoint.vout(), 3);
+    assert_eq!(
+        OutPoint::from_string(&outpoint.to_string()).expect("from_string"),
+        outpoint
+    );
+
+    let error = OutPoint::new("not a txid", 0).expect_err("invalid txid");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+    let error = OutPoint::from_string(txid).expect_err("missing vout");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+
+    let script = ScriptBuf::from_hex("0014d28e6a88e1a1d1b2bc1f31e3f31fbfd3f56d5c8b").expect("script");
+    assert_eq!(script.len(), 22);
+    assert_eq!(ScriptBuf::from_bytes(script.to_bytes()), script);
+
+    let txout = TxOut::new(Amount::from_sat(1_000), script.clone());
+    assert_eq!(txout.value().to_sat(), 1_000);
+    assert_eq!(txout.script_pubkey(), script);
+}
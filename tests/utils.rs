@@ -4,12 +4,14 @@
 
 extern crate wasm_bindgen_test;
 
-use bdk_wallet::bip39::Mnemonic;
+use bdk_wallet::{bip39::Mnemonic, serde_json::json};
 use bitcoindevkit::{
-    seed_to_descriptor, seed_to_xpriv, set_panic_hook,
-    types::{AddressType, Network},
+    convert_slip132, seed_to_descriptor, seed_to_xpriv, set_panic_hook, slip10_to_descriptor, slip132_address_type,
+    types::{AddressType, Network, Slip132Version},
     xpriv_to_descriptor, xpub_to_descriptor,
 };
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
 use wasm_bindgen_test::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
@@ -83,3 +85,82 @@ async fn test_xpub_to_descriptor() {
         "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/1/*)#ltuly67e"
     );
 }
+
+#[wasm_bindgen_test]
+async fn test_slip132() {
+    set_panic_hook();
+    let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+    let xpub = "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V";
+
+    assert_eq!(
+        convert_slip132(zpub, Slip132Version::Xpub).expect("convert_slip132"),
+        xpub
+    );
+    assert_eq!(
+        convert_slip132(xpub, Slip132Version::Zpub).expect("convert_slip132"),
+        zpub
+    );
+    assert_eq!(
+        slip132_address_type(zpub).expect("slip132_address_type"),
+        Some(AddressType::P2wpkh)
+    );
+    assert_eq!(slip132_address_type(xpub).expect("slip132_address_type"), None);
+}
+
+#[wasm_bindgen_test]
+async fn test_slip132_to_descriptor() {
+    set_panic_hook();
+    let vpub = "vpub5Yiku67hekdfinXW8vjj3HCM7oe1GDbrx2aL3VEokGkNSPBNB97LVA4aNpTgPreyQX2nY12gWUa5if7pXWRB1r9W637jjDvuAVcwDnNEfDs";
+    let vprv = "vprv9KjQVaaopP5NWJT32uCig9FcZmoWrkt1aoejF6qCBwDPZarDdbo5wMk6XZDWTSBvxyHfc79SDrUDr1msgwtfwxF4dkkUeCPdMN3HNqsNeag";
+    let fingerprint = "27f9035f";
+
+    let descriptors = xpub_to_descriptor(vpub, fingerprint, NETWORK, ADDRESS_TYPE).expect("xpub_to_descriptor");
+    assert_eq!(
+        descriptors.external(),
+        "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#wle7e0wp"
+    );
+
+    let descriptors = xpriv_to_descriptor(vprv, fingerprint, NETWORK, ADDRESS_TYPE).expect("xpriv_to_descriptor");
+    assert_eq!(
+        descriptors.external(),
+        "wpkh([27f9035f/84'/1'/0']tprv8g4stFEyX1zQoi4oNBdUFy4cDqWcyWu1kacHgK3RRvTdTPDm8HTxhERpV9JLTct69h4479xKJXm85SYkFZ4eMUsru5MdUNkeouuzbivKAJp/0/*)#sx5quhf7"
+    );
+
+    assert!(xpub_to_descriptor(vpub, fingerprint, NETWORK, AddressType::P2tr).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_slip10_to_descriptor() {
+    set_panic_hook();
+    let node = json!({
+        "depth": 3,
+        "masterFingerprint": 0x27f9035f,
+        "parentFingerprint": 0x63bb3f85,
+        "index": 0x80000000u32,
+        "privateKey": "0x675ac47aec2dadc707cc594a686c981fbd9746591f88cd41c9b2ec6dca335f62",
+        "publicKey": "0x02838599a50bb00725d5e86f7937036f304895c31946ae8fd62ff5effc5b4c6903",
+        "chainCode": "0xa1e4b295ce26d8f547f409bd5cdacc868b8402ee0d9adfc1d5c529a156411ed6",
+        "curve": "secp256k1",
+    });
+    let slip10 = node.serialize(&Serializer::json_compatible()).unwrap();
+
+    let descriptors = slip10_to_descriptor(slip10, NETWORK, ADDRESS_TYPE).expect("slip10_to_descriptor");
+    assert_eq!(
+        descriptors.external(),
+        "wpkh([27f9035f/84'/1'/0']tprv8g4stFEyX1zQoi4oNBdUFy4cDqWcyWu1kacHgK3RRvTdTPDm8HTxhERpV9JLTct69h4479xKJXm85SYkFZ4eMUsru5MdUNkeouuzbivKAJp/0/*)#sx5quhf7"
+    );
+    assert_eq!(
+        descriptors.internal(),
+        "wpkh([27f9035f/84'/1'/0']tprv8g4stFEyX1zQoi4oNBdUFy4cDqWcyWu1kacHgK3RRvTdTPDm8HTxhERpV9JLTct69h4479xKJXm85SYkFZ4eMUsru5MdUNkeouuzbivKAJp/1/*)#pj3ppzex"
+    );
+
+    let mut ed25519 = node.clone();
+    ed25519["curve"] = "ed25519".into();
+    let slip10 = ed25519.serialize(&Serializer::json_compatible()).unwrap();
+    assert!(slip10_to_descriptor(slip10, NETWORK, ADDRESS_TYPE).is_err());
+
+    let mut coin_level = node;
+    coin_level["depth"] = 2.into();
+    let slip10 = coin_level.serialize(&Serializer::json_compatible()).unwrap();
+    assert!(slip10_to_descriptor(slip10, NETWORK, ADDRESS_TYPE).is_err());
+}
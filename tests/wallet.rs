@@ -4,19 +4,46 @@
 
 extern crate wasm_bindgen_test;
 
-use bdk_wallet::bip39::Mnemonic;
+use std::{cell::RefCell, rc::Rc};
+
+use bdk_wallet::{
+    bip39::Mnemonic,
+    bitcoin::{constants::genesis_block, hashes::Hash, BlockHash, Network as BdkNetwork, OutPoint, Txid},
+    chain::spk_client::{FullScanRequest as BdkFullScanRequest, SyncRequest as BdkSyncRequest},
+    serde_json::{from_str, Value},
+    KeychainKind as BdkKeychainKind,
+};
 use bitcoindevkit::{
-    bitcoin::Wallet,
+    bitcoin::{Recipient, Wallet},
     seed_to_descriptor, set_panic_hook,
-    types::{AddressType, ChangeSet, KeychainKind, Network},
+    types::{
+        Address, AddressType, Amount, BdkErrorCode, Birthday, ChangeSet, FeeRate, KeychainKind, Network, PsbtInput,
+        ScanProgress, ScriptType,
+    },
 };
+use wasm_bindgen::{closure::Closure, JsCast};
 use wasm_bindgen_test::*;
 
+mod common;
+
+use common::receive;
+
 wasm_bindgen_test_configure!(run_in_browser);
 
 const NETWORK: Network = Network::Testnet;
 const ADDRESS_TYPE: AddressType = AddressType::P2wpkh;
 const MNEMONIC: &str = "journey embrace permit coil indoor stereo welcome maid movie easy clock spider tent slush bright luxury awake waste legal modify awkward answer acid goose";
+const RECIPIENT_ADDRESS: &str = "tb1qd28npep0s8frcm3y7dxqajkcy2m40eysplyr9v";
+
+fn new_wallet() -> Wallet {
+    new_wallet_with(ADDRESS_TYPE)
+}
+
+fn new_wallet_with(address_type: AddressType) -> Wallet {
+    let seed = Mnemonic::parse(MNEMONIC).unwrap().to_seed("");
+    let descriptors = seed_to_descriptor(&seed, NETWORK, address_type).expect("seed_to_descriptor");
+    Wallet::create(NETWORK, descriptors).expect("wallet")
+}
 
 #[wasm_bindgen_test]
 async fn test_wallet() {
@@ -31,16 +58,16 @@ async fn test_wallet() {
 
     let block_height = wallet.latest_checkpoint().height();
     assert_eq!(block_height, 0);
-    assert_eq!(wallet.public_descriptor(KeychainKind::External), "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#wle7e0wp");
+    assert_eq!(wallet.public_descriptor(KeychainKind::External).expect("public_descriptor"), "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/0/*)#wle7e0wp");
     assert_eq!(
-        wallet.public_descriptor(KeychainKind::Internal),
+        wallet.public_descriptor(KeychainKind::Internal).expect("public_descriptor"),
         "wpkh([27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq/1/*)#ltuly67e"
     );
 
-    let address0 = wallet.reveal_next_address(KeychainKind::External);
+    let address0 = wallet.reveal_next_address(KeychainKind::External).expect("address");
     assert_eq!(address0.index(), 0);
 
-    let address1 = wallet.reveal_next_address(KeychainKind::External);
+    let address1 = wallet.reveal_next_address(KeychainKind::External).expect("address");
     assert_eq!(address1.index(), 1);
 }
 
@@ -55,10 +82,13 @@ async fn test_changeset() {
     let mut changeset = wallet.take_staged().expect("initial_changeset");
     assert!(!changeset.is_empty());
 
-    let changeset_from_json = ChangeSet::from_json(&changeset.to_json()).expect("changeset from_json");
+    let json = changeset.to_json().expect("changeset to_json");
+    let changeset_from_json = ChangeSet::from_json(&json).expect("changeset from_json");
     assert_eq!(changeset, changeset_from_json);
 
-    wallet.reveal_addresses_to(KeychainKind::External, 10);
+    wallet
+        .reveal_addresses_to(KeychainKind::External, 10)
+        .expect("addresses");
 
     let final_changeset = wallet.take_staged().expect("final_changeset");
     assert!(!final_changeset.is_empty());
@@ -66,3 +96,425 @@ async fn test_changeset() {
     changeset.merge(final_changeset);
     assert!(!changeset.is_empty());
 }
+
+#[wasm_bindgen_test]
+async fn test_birthday() {
+    set_panic_hook();
+
+    let seed = Mnemonic::parse(MNEMONIC).unwrap().to_seed("");
+    let descriptors = seed_to_descriptor(&seed, NETWORK, ADDRESS_TYPE).expect("seed_to_descriptor");
+    let hash = BlockHash::from_byte_array([2; 32]).to_string();
+    let birthday = Birthday::new(2_500_000, &hash, 1_700_000_000).expect("birthday");
+    let mut wallet = Wallet::create_with_birthday(NETWORK, descriptors, birthday).expect("wallet");
+
+    let checkpoint = wallet.latest_checkpoint();
+    assert_eq!(checkpoint.height(), 2_500_000);
+    assert_eq!(checkpoint.hash(), hash);
+    assert_eq!(checkpoint.prev().expect("genesis").height(), 0);
+    let request = wallet.start_full_scan();
+    assert_eq!(request.chain_tip().map(|tip| tip.height()), Some(2_500_000));
+    assert_eq!(request.birthday(), Some(2_500_000));
+
+    let export: Value = from_str(&wallet.export_fully_noded("Savings").expect("export")).expect("json");
+    assert_eq!(export["blockheight"], 2_500_000);
+    let request: Value = from_str(&wallet.export_core_descriptors()).expect("json");
+    assert_eq!(request[0]["timestamp"], 1_700_000_000);
+
+    let json = wallet.take_staged().expect("changeset").to_json().expect("to_json");
+    let loaded = Wallet::load(ChangeSet::from_json(&json).expect("from_json")).expect("load");
+    assert_eq!(loaded.birthday(), Some(birthday));
+    assert_eq!(loaded.latest_checkpoint().height(), 2_500_000);
+    assert_eq!(new_wallet().birthday(), None);
+    assert_eq!(new_wallet().start_full_scan().birthday(), None);
+
+    let genesis_hash = genesis_block(BdkNetwork::Testnet).block_hash().to_string();
+    let seed = Mnemonic::parse(MNEMONIC).unwrap().to_seed("");
+    let descriptors = seed_to_descriptor(&seed, NETWORK, ADDRESS_TYPE).expect("seed_to_descriptor");
+    let genesis = Birthday::new(0, &genesis_hash, 1_296_688_602).expect("birthday");
+    let wallet = Wallet::create_with_birthday(NETWORK, descriptors, genesis).expect("genesis birthday");
+    assert_eq!(wallet.latest_checkpoint().height(), 0);
+
+    let descriptors = seed_to_descriptor(&seed, NETWORK, ADDRESS_TYPE).expect("seed_to_descriptor");
+    let other_genesis = Birthday::new(0, &hash, 1_296_688_602).expect("birthday");
+    let Err(error) = Wallet::create_with_birthday(NETWORK, descriptors, other_genesis) else {
+        panic!("the birthday is not on the chain of the network");
+    };
+    assert_eq!(error.code(), BdkErrorCode::NetworkMismatch);
+
+    let error = Birthday::new(1, "00", 0).expect_err("invalid hash");
+    assert_eq!(error.code(), BdkErrorCode::InvalidValue);
+}
+
+type ProgressReports = Rc<RefCell<Vec<ScanProgress>>>;
+
+/// A progress callback collecting the reports.
+fn progress_callback() -> (Closure<dyn FnMut(ScanProgress)>, ProgressReports) {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let closure = Closure::<dyn FnMut(ScanProgress)>::new({
+        let reports = reports.clone();
+        move |progress| reports.borrow_mut().push(progress)
+    });
+    (closure, reports)
+}
+
+#[wasm_bindgen_test]
+async fn test_scan_progress() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    wallet
+        .reveal_addresses_to(KeychainKind::External, 2)
+        .expect("addresses");
+
+    let (callback, reports) = progress_callback();
+    let mut request: BdkFullScanRequest<BdkKeychainKind> = wallet
+        .start_full_scan_with_progress(callback.as_ref().clone().unchecked_into())
+        .into();
+    request.next_spk(BdkKeychainKind::External);
+    request.next_spk(BdkKeychainKind::External);
+    request.next_spk(BdkKeychainKind::Internal);
+    let reports = reports.take();
+    let reported = reports
+        .iter()
+        .map(|progress| (progress.keychain(), progress.index(), progress.spks_checked()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reported,
+        [
+            (Some(KeychainKind::External), Some(0), 1),
+            (Some(KeychainKind::External), Some(1), 2),
+            (Some(KeychainKind::Internal), Some(0), 3),
+        ]
+    );
+    assert!(reports.iter().all(|progress| progress.spks_remaining().is_none()));
+
+    let (callback, reports) = progress_callback();
+    let mut request: BdkSyncRequest<(BdkKeychainKind, u32)> = wallet
+        .start_sync_with_progress(callback.as_ref().clone().unchecked_into())
+        .into();
+    assert_eq!(request.iter_spks().count(), 3);
+    let reported = reports
+        .take()
+        .iter()
+        .map(|progress| (progress.index(), progress.spks_checked(), progress.spks_remaining()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reported,
+        [(Some(0), 1, Some(2)), (Some(1), 2, Some(1)), (Some(2), 3, Some(0))]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_build_tx() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    receive(&mut wallet, 100_000);
+
+    let address = Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address");
+    let fee_rate = FeeRate::from_sat_per_vb(2).expect("fee_rate");
+    let mut psbt = wallet
+        .build_tx()
+        .add_recipient(Recipient::new(address.clone(), Amount::from_sat(50_000)))
+        .fee_rate(fee_rate)
+        .finish()
+        .expect("finish");
+
+    assert!(psbt.fee_rate().expect("fee_rate") >= fee_rate);
+    assert_eq!(psbt.unsigned_tx.output.len(), 2);
+    assert!(wallet.sign(&mut psbt).expect("sign"));
+
+    let tx = psbt.extract_tx().expect("extract_tx");
+    assert_eq!(
+        wallet.calculate_fee(&tx).expect("calculate_fee"),
+        psbt.fee().expect("fee")
+    );
+    let txid = tx.txid();
+    wallet.insert_tx_at(tx, 2);
+    assert_eq!(wallet.transactions().len(), 2);
+
+    let bump_fee_rate = FeeRate::from_sat_per_vb(5).expect("fee_rate");
+    let bump_psbt = wallet
+        .build_fee_bump(&txid)
+        .expect("build_fee_bump")
+        .fee_rate(bump_fee_rate)
+        .finish()
+        .expect("finish fee bump");
+    assert!(bump_psbt.fee_rate().expect("fee_rate") >= bump_fee_rate);
+    assert!(bump_psbt.fee().expect("fee") > psbt.fee().expect("fee"));
+
+    let error = wallet
+        .build_tx()
+        .add_recipient(Recipient::new(address, Amount::from_sat(1_000_000)))
+        .finish()
+        .expect_err("insufficient funds");
+    assert_eq!(error.code(), BdkErrorCode::InsufficientFunds);
+}
+
+#[wasm_bindgen_test]
+async fn test_add_data() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    receive(&mut wallet, 100_000);
+
+    let commitment = [0xab; 32];
+    let psbt = wallet
+        .build_tx()
+        .add_data(commitment.to_vec())
+        .finish()
+        .expect("finish");
+    let outputs = psbt.op_return_outputs();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].value().to_sat(), 0);
+    assert_eq!(outputs[0].script_pubkey().script_type(), ScriptType::OpReturn);
+    assert_eq!(outputs[0].script_pubkey().op_return_data().expect("data"), commitment);
+
+    let error = wallet.build_tx().add_data(vec![0; 81]).finish().expect_err("too large");
+    assert_eq!(error.code(), BdkErrorCode::NonStandardData);
+
+    let error = wallet
+        .build_tx()
+        .add_data(vec![1])
+        .add_data(vec![2])
+        .finish()
+        .expect_err("multiple outputs");
+    assert_eq!(error.code(), BdkErrorCode::NonStandardData);
+
+    let psbt = wallet
+        .build_tx()
+        .add_data(vec![0; 100])
+        .allow_nonstandard_data(true)
+        .finish()
+        .expect("finish non-standard");
+    let tx = psbt.unsigned_tx();
+    assert_eq!(
+        tx.op_return_outputs()[0]
+            .script_pubkey()
+            .op_return_data()
+            .expect("data")
+            .len(),
+        100
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_timelocks() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    receive(&mut wallet, 100_000);
+    let address = Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address");
+    let recipient = Recipient::new(address, Amount::from_sat(50_000));
+
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .current_height(800)
+        .finish()
+        .expect("finish")
+        .unsigned_tx();
+    assert_eq!(tx.lock_time(), 800);
+    assert!(tx.is_rbf());
+
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .current_height(800)
+        .anti_fee_sniping(false)
+        .finish()
+        .expect("finish without anti-fee-sniping")
+        .unsigned_tx();
+    assert_eq!(tx.lock_time(), 0);
+
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .nlocktime(900_000)
+        .finish()
+        .expect("finish with nlocktime")
+        .unsigned_tx();
+    assert_eq!(tx.lock_time(), 900_000);
+    assert!(tx.is_lock_time_enabled());
+
+    let outpoint = tx.input()[0].previous_output();
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .input_sequence(outpoint, 144)
+        .finish()
+        .expect("finish with relative lock time")
+        .unsigned_tx();
+    assert_eq!(tx.version(), 2);
+    assert_eq!(tx.input()[0].sequence(), 144);
+
+    let error = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .nlocktime(900_000)
+        .set_exact_sequence(0xffffffff)
+        .finish()
+        .expect_err("disabled lock time");
+    assert_eq!(error.code(), BdkErrorCode::InvalidTimelock);
+
+    let unknown = OutPoint::new(Txid::from_byte_array([2; 32]), 0);
+    let error = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .input_sequence(unknown.into(), 144)
+        .finish()
+        .expect_err("unknown input");
+    assert_eq!(error.code(), BdkErrorCode::InvalidTimelock);
+
+    let error = wallet
+        .build_tx()
+        .add_recipient(recipient)
+        .current_height(600_000_000)
+        .finish()
+        .expect_err("timestamp as height");
+    assert_eq!(error.code(), BdkErrorCode::InvalidTimelock);
+}
+
+/// A wallet whose outputs are spent by `KEY` under a timelock, like `after(800000)`.
+fn timelocked_wallet(timelock: &str) -> Wallet {
+    const KEY: &str = "[27f9035f/84'/1'/0']tpubDCkv2fHDfPg5hB6bFqJ4fNiins2Z8r5vKtD4xq5irCG2HsUXkgHYsj3gfGTdvAv41hoJeXjfxu7EBQqZMm6SVkxztKFtaaE7HuLdkuL7KNq";
+    Wallet::create_from_multipath(NETWORK, &format!("wsh(and_v(v:pk({KEY}/<0;1>/*),{timelock}))")).expect("wallet")
+}
+
+#[wasm_bindgen_test]
+async fn test_policy_timelocks() {
+    set_panic_hook();
+
+    let address = Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address");
+    let recipient = Recipient::new(address, Amount::from_sat(50_000));
+
+    let mut wallet = timelocked_wallet("after(800000)");
+    let outpoint = receive(&mut wallet, 100_000);
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .anti_fee_sniping(false)
+        .finish()
+        .expect("finish without anti-fee-sniping")
+        .unsigned_tx();
+    assert_eq!(tx.lock_time(), 800_000);
+    assert!(tx.is_lock_time_enabled());
+
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .current_height(900_000)
+        .finish()
+        .expect("finish after the lock time")
+        .unsigned_tx();
+    assert_eq!(tx.lock_time(), 900_000);
+
+    let error = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .input_sequence(outpoint.into(), 0xffffffff)
+        .finish()
+        .expect_err("final sequence");
+    assert_eq!(error.code(), BdkErrorCode::InvalidTimelock);
+
+    let mut wallet = timelocked_wallet("older(144)");
+    let outpoint = receive(&mut wallet, 100_000);
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .anti_fee_sniping(false)
+        .finish()
+        .expect("finish")
+        .unsigned_tx();
+    assert_eq!(tx.version(), 2);
+    assert_eq!(tx.lock_time(), 0);
+    assert_eq!(tx.input()[0].sequence(), 144);
+
+    let tx = wallet
+        .build_tx()
+        .add_recipient(recipient.clone())
+        .input_sequence(outpoint.into(), 200)
+        .finish()
+        .expect("finish with a longer relative lock time")
+        .unsigned_tx();
+    assert_eq!(tx.input()[0].sequence(), 200);
+
+    for sequence in [10, 0xfffffffd, 144 | 1 << 22] {
+        let error = wallet
+            .build_tx()
+            .add_recipient(recipient.clone())
+            .input_sequence(outpoint.into(), sequence)
+            .finish()
+            .expect_err("sequence not satisfying older(144)");
+        assert_eq!(error.code(), BdkErrorCode::InvalidTimelock, "{sequence:#x}");
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_foreign_utxo() {
+    set_panic_hook();
+
+    let mut wallet = new_wallet();
+    let outpoint = receive(&mut wallet, 100_000);
+    receive(&mut wallet, 200_000);
+
+    // The counterparty shares its input, with its metadata, through a PSBT of its own.
+    let mut counterparty = new_wallet_with(AddressType::P2tr);
+    receive(&mut counterparty, 50_000);
+    let counterparty_psbt = counterparty
+        .build_tx()
+        .drain_wallet()
+        .drain_to(Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address"))
+        .finish()
+        .expect("counterparty finish");
+    let foreign_outpoint = counterparty_psbt.unsigned_tx().input()[0].previous_output();
+    let foreign_input = counterparty_psbt.inputs()[0].clone();
+    assert!(foreign_input.witness_utxo().is_some());
+
+    let address = Address::from_string(RECIPIENT_ADDRESS, NETWORK).expect("address");
+    let mut psbt = wallet
+        .build_tx()
+        .add_recipient(Recipient::new(address, Amount::from_sat(120_000)))
+        .only_spend_from(vec![outpoint.into()])
+        .add_foreign_utxo(foreign_outpoint, foreign_input.clone(), 66)
+        .finish()
+        .expect("finish");
+
+    let tx = psbt.unsigned_tx();
+    assert_eq!(tx.input().len(), 2);
+    assert!(tx.input().iter().any(|input| *input.previous_output() == outpoint));
+    let index = tx
+        .input()
+        .iter()
+        .position(|input| input.previous_output() == foreign_outpoint)
+        .expect("foreign input");
+    assert_eq!(psbt.inputs()[index].tap_internal_key, foreign_input.tap_internal_key);
+    assert!(tx.is_rbf());
+
+    assert!(!wallet.sign(&mut psbt).expect("sign"));
+    assert!(counterparty.sign(&mut psbt).expect("counterparty sign"));
+    assert!(psbt.inputs().iter().all(|input| input.is_finalized()));
+    psbt.extract_tx().expect("extract_tx");
+
+    let error = wallet
+        .build_tx()
+        .add_utxo(OutPoint::new(Txid::from_byte_array([2; 32]), 0).into())
+        .drain_wallet()
+        .finish()
+        .expect_err("unknown utxo");
+    assert_eq!(error.code(), BdkErrorCode::CreateTx);
+
+    // The outpoint must be an output of the `non_witness_utxo`.
+    let foreign_input = PsbtInput::from_non_witness_utxo(counterparty_psbt.unsigned_tx());
+    let error = wallet
+        .build_tx()
+        .drain_wallet()
+        .add_foreign_utxo(
+            OutPoint::new(Txid::from_byte_array([3; 32]), 0).into(),
+            foreign_input,
+            66,
+        )
+        .finish()
+        .expect_err("mismatched utxo");
+    assert_eq!(error.code(), BdkErrorCode::InvalidPsbt);
+}